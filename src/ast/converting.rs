@@ -1,5 +1,8 @@
 use super::{TraversalResult, TreeTraversalMode};
-use crate::{ast::nodes::*, parser::lexer::SourcePosition};
+use crate::{
+    ast::nodes::*,
+    parser::lexer::{Comment, SourcePosition},
+};
 
 /// The `AstConverting` trait is used for converting an Abstract Syntax Tree (AST)
 /// to some other form, such as an internal or intermediate representation.
@@ -14,6 +17,12 @@ pub trait AstConverting {
     /// Pops the source position of the current node from the stack.
     fn pop_source_position(&mut self);
 
+    /// Receives the comments preceding the current node, before the node is converted.
+    fn emit_leading_comments(&mut self, _comments: &[Comment]) {}
+
+    /// Receives the comments following the current node, after the node is converted.
+    fn emit_trailing_comments(&mut self, _comments: &[Comment]) {}
+
     /// Converts a `NodeByteStr` node.
     fn emit_byte_str(
         &mut self,
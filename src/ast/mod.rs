@@ -1,5 +1,6 @@
 pub mod converting;
 pub mod nodes;
+pub mod trivia;
 pub mod visitor;
 
 /// Enums used in various routines
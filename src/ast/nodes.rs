@@ -1,6 +1,8 @@
+#![allow(clippy::large_enum_variant)]
+
 use std::fmt;
 
-use crate::parser::lexer::SourcePosition;
+use crate::parser::lexer::{Comment, SourcePosition};
 
 /// A wrapper struct that adds source position to an AST node.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq)]
@@ -11,6 +13,37 @@ pub struct WithMetaData<T> {
     pub start: SourcePosition,
     /// The ending position of the AST node in the source code
     pub end: SourcePosition,
+    /// The comments attached to the AST node
+    pub trivia: Trivia,
+}
+
+/// The comments attached to an AST node.
+///
+/// A comment is attached to the outermost node starting right after it, unless it ends the line
+/// of the node before it, in which case it trails that node instead.
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd, Eq)]
+pub struct Trivia {
+    /// Comments preceding the node
+    pub leading: Vec<Comment>,
+    /// Comments following the node
+    pub trailing: Vec<Comment>,
+}
+
+impl Trivia {
+    /// Returns true if no comments are attached.
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.trailing.is_empty()
+    }
+
+    /// Returns the text of the leading `(* @doc ... *)` comments, one comment per line.
+    pub fn doc(&self) -> Option<String> {
+        let docs: Vec<&str> = self.leading.iter().filter_map(Comment::doc).collect();
+        if docs.is_empty() {
+            None
+        } else {
+            Some(docs.join("\n"))
+        }
+    }
 }
 
 /// Implementing Display trait for WithMetaData struct
@@ -613,6 +646,15 @@ pub enum NodeComponentId {
     WithRegularId(WithMetaData<String>),
 }
 
+impl fmt::Display for NodeComponentId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeComponentId::WithTypeLikeName(name) => write!(f, "{}", name),
+            NodeComponentId::WithRegularId(name) => write!(f, "{}", name),
+        }
+    }
+}
+
 /// NodeComponentParameters represents a component parameters node in the AST
 /// It contains a vector of parameters
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq)]
@@ -677,6 +719,18 @@ pub struct NodeProgram {
     pub contract_definition: WithMetaData<NodeContractDefinition>,
 }
 
+/// NodeLibraryModule represents a library module node in the AST, the content of a `.scillib` file
+/// It contains a version, optional import declarations and a library definition
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq)]
+pub struct NodeLibraryModule {
+    /// The version of the library module
+    pub version: WithMetaData<String>,
+    /// The import declarations of the library module
+    pub import_declarations: Option<WithMetaData<NodeImportDeclarations>>,
+    /// The library definition of the library module
+    pub library_definition: WithMetaData<NodeLibraryDefinition>,
+}
+
 /// NodeLibraryDefinition represents a library definition node in the AST
 /// It contains a name and a vector of definitions
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq)]
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::nodes::*,
+    parser::lexer::{Comment, SourcePosition},
+};
+
+/// Attaches the comments skipped by the lexer to the nodes of `root` as `Trivia`.
+///
+/// A comment that ends the line of the node before it trails the outermost node ending there, as
+/// does a comment after the last node. Every other comment leads the outermost node starting after it.
+pub fn attach_comments<T: WalkMetaData>(root: &mut T, comments: Vec<Comment>) {
+    if comments.is_empty() {
+        return;
+    }
+
+    // Nodes are identified by their position in a pre-order walk, so that among nodes sharing a
+    // position, the one with the lowest index is the outermost.
+    let mut spans = Vec::new();
+    root.walk(&mut |start, end, _| spans.push((*start, *end)));
+
+    let mut attached: HashMap<usize, Trivia> = HashMap::new();
+    for comment in comments {
+        let previous = spans
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, end))| end.position <= comment.start.position)
+            .max_by_key(|(index, (_, end))| (end.position, std::cmp::Reverse(*index)));
+        let next = spans
+            .iter()
+            .enumerate()
+            .filter(|(_, (start, _))| start.position >= comment.end.position)
+            .min_by_key(|(index, (start, _))| (start.position, *index));
+
+        match (previous, next) {
+            (Some((index, (_, end))), Some((_, (start, _))))
+                if end.line == comment.start.line && start.line > comment.end.line =>
+            {
+                attached.entry(index).or_default().trailing.push(comment)
+            }
+            (_, Some((index, _))) => attached.entry(index).or_default().leading.push(comment),
+            (Some((index, _)), None) => attached.entry(index).or_default().trailing.push(comment),
+            (None, None) => {}
+        }
+    }
+
+    let mut index = 0;
+    root.walk(&mut |_, _, trivia| {
+        if let Some(comments) = attached.remove(&index) {
+            *trivia = comments;
+        }
+        index += 1;
+    });
+}
+
+/// Receives the start, end and trivia of a node.
+pub type Callback<'a> = dyn FnMut(&SourcePosition, &SourcePosition, &mut Trivia) + 'a;
+
+/// Walks the `WithMetaData` nodes of a tree mutably, parents before children.
+pub trait WalkMetaData {
+    fn walk(&mut self, f: &mut Callback);
+}
+
+impl<T: WalkMetaData> WalkMetaData for WithMetaData<T> {
+    fn walk(&mut self, f: &mut Callback) {
+        f(&self.start, &self.end, &mut self.trivia);
+        self.node.walk(f);
+    }
+}
+
+impl<T: WalkMetaData> WalkMetaData for Box<T> {
+    fn walk(&mut self, f: &mut Callback) {
+        self.as_mut().walk(f);
+    }
+}
+
+impl<T: WalkMetaData> WalkMetaData for Option<T> {
+    fn walk(&mut self, f: &mut Callback) {
+        if let Some(node) = self {
+            node.walk(f);
+        }
+    }
+}
+
+impl<T: WalkMetaData> WalkMetaData for Vec<T> {
+    fn walk(&mut self, f: &mut Callback) {
+        for node in self {
+            node.walk(f);
+        }
+    }
+}
+
+impl WalkMetaData for String {
+    fn walk(&mut self, _: &mut Callback) {}
+}
+
+macro_rules! walk_struct {
+    ($name:ident { $($field:ident),* $(,)? }) => {
+        impl WalkMetaData for $name {
+            fn walk(&mut self, f: &mut Callback) {
+                $(self.$field.walk(f);)*
+            }
+        }
+    };
+}
+
+macro_rules! walk_enum {
+    ($name:ident { $($variant:ident $(($($value:ident),*))? $({$($field:ident),*})?),* $(,)? }) => {
+        impl WalkMetaData for $name {
+            #[allow(unused_variables)]
+            fn walk(&mut self, f: &mut Callback) {
+                match self {
+                    $($name::$variant $(($($value),*))? $({$($field),*})? => {
+                        $($($value.walk(f);)*)?
+                        $($($field.walk(f);)*)?
+                    })*
+                }
+            }
+        }
+    };
+}
+
+walk_enum!(NodeByteStr { Constant(a), Type(a) });
+walk_enum!(NodeTypeNameIdentifier {
+    ByteStringType(a),
+    EventType,
+    TypeOrEnumLikeIdentifier(a),
+});
+walk_enum!(NodeImportedName {
+    RegularImport(a),
+    AliasedImport(a, b),
+});
+walk_struct!(NodeImportDeclarations { import_list });
+walk_enum!(NodeMetaIdentifier {
+    MetaName(a),
+    MetaNameInNamespace(a, b),
+    MetaNameInHexspace(a, b),
+    ByteString,
+});
+walk_enum!(NodeVariableIdentifier {
+    VariableName(a),
+    SpecialIdentifier(a),
+    VariableInNamespace(a, b),
+});
+walk_struct!(NodeBuiltinArguments { arguments });
+walk_enum!(NodeTypeMapKey {
+    GenericMapKey(a),
+    EnclosedGenericId(a),
+    EnclosedAddressMapKeyType(a),
+    AddressMapKeyType(a),
+});
+walk_enum!(NodeTypeMapValue {
+    MapValueTypeOrEnumLikeIdentifier(a),
+    MapKeyValue(a),
+    MapValueParenthesizedType(a),
+    MapValueAddressType(a),
+});
+walk_enum!(NodeTypeArgument {
+    EnclosedTypeArgument(a),
+    GenericTypeArgument(a),
+    TemplateTypeArgument(a),
+    AddressTypeArgument(a),
+    MapTypeArgument(a, b),
+});
+walk_enum!(NodeScillaType {
+    GenericTypeWithArgs(a, b),
+    MapType(a, b),
+    FunctionType(a, b),
+    EnclosedType(a),
+    ScillaAddresseType(a),
+    PolyFunctionType(a, b),
+    TypeVarType(a),
+});
+walk_struct!(NodeTypeMapEntry { key, value });
+walk_struct!(NodeAddressTypeField {
+    identifier,
+    type_name
+});
+walk_struct!(NodeAddressType {
+    identifier,
+    type_name,
+    address_fields,
+});
+walk_enum!(NodeFullExpression {
+    LocalVariableDeclaration {
+        identifier_name,
+        expression,
+        type_annotation,
+        containing_expression
+    },
+    FunctionDeclaration {
+        identier_value,
+        type_annotation,
+        expression
+    },
+    FunctionCall {
+        function_name,
+        argument_list
+    },
+    ExpressionAtomic(a),
+    ExpressionBuiltin { b, targs, xs },
+    Message(a),
+    Match {
+        match_expression,
+        clauses
+    },
+    ConstructorCall {
+        identifier_name,
+        contract_type_arguments,
+        argument_list
+    },
+    TemplateFunction {
+        identifier_name,
+        expression
+    },
+    TApp {
+        identifier_name,
+        type_arguments
+    },
+});
+walk_enum!(NodeMessageEntry {
+    MessageLiteral(a, b),
+    MessageVariable(a, b),
+});
+walk_struct!(NodePatternMatchExpressionClause {
+    pattern,
+    expression
+});
+walk_enum!(NodeAtomicExpression {
+    AtomicSid(a),
+    AtomicLit(a),
+});
+walk_struct!(NodeContractTypeArguments { type_arguments });
+walk_enum!(NodeValueLiteral {
+    LiteralInt(a, b),
+    LiteralHex(a),
+    LiteralString(a),
+    LiteralEmptyMap(a, b),
+});
+walk_struct!(NodeMapAccess { identifier_name });
+walk_enum!(NodePattern {
+    Wildcard,
+    Binder(a),
+    Constructor(a, b),
+});
+walk_enum!(NodeArgumentPattern {
+    WildcardArgument,
+    BinderArgument(a),
+    ConstructorArgument(a),
+    PatternArgument(a),
+});
+walk_struct!(NodePatternMatchClause {
+    pattern_expression,
+    statement_block,
+});
+walk_struct!(NodeBlockchainFetchArguments { arguments });
+walk_enum!(NodeStatement {
+    Load {
+        left_hand_side,
+        right_hand_side
+    },
+    RemoteFetch(a),
+    Store {
+        left_hand_side,
+        right_hand_side
+    },
+    Bind {
+        left_hand_side,
+        right_hand_side
+    },
+    ReadFromBC {
+        left_hand_side,
+        type_name,
+        arguments
+    },
+    MapGet {
+        left_hand_side,
+        keys,
+        right_hand_side
+    },
+    MapGetExists {
+        left_hand_side,
+        keys,
+        right_hand_side
+    },
+    MapUpdate {
+        left_hand_side,
+        keys,
+        right_hand_side
+    },
+    MapUpdateDelete {
+        left_hand_side,
+        keys
+    },
+    Accept,
+    Send { identifier_name },
+    CreateEvnt { identifier_name },
+    Throw { error_variable },
+    MatchStmt { variable, clauses },
+    CallProc {
+        component_id,
+        arguments
+    },
+    Iterate {
+        identifier_name,
+        component_id
+    },
+});
+walk_enum!(NodeRemoteFetchStatement {
+    ReadStateMutable(a, b, c),
+    ReadStateMutableSpecialId(a, b, c),
+    ReadStateMutableMapAccess(a, b, c, d),
+    ReadStateMutableMapAccessExists(a, b, c, d),
+    ReadStateMutableCastAddress(a, b, c),
+});
+walk_enum!(NodeComponentId {
+    WithTypeLikeName(a),
+    WithRegularId(a),
+});
+walk_struct!(NodeComponentParameters { parameters });
+walk_struct!(NodeParameterPair {
+    identifier_with_type
+});
+walk_struct!(NodeComponentBody { statement_block });
+walk_struct!(NodeStatementBlock { statements });
+walk_struct!(NodeTypedIdentifier {
+    identifier_name,
+    annotation
+});
+walk_struct!(NodeTypeAnnotation { type_name });
+walk_struct!(NodeProgram {
+    version,
+    import_declarations,
+    library_definition,
+    contract_definition,
+});
+walk_struct!(NodeLibraryModule {
+    version,
+    import_declarations,
+    library_definition,
+});
+walk_struct!(NodeLibraryDefinition { name, definitions });
+walk_enum!(NodeLibrarySingleDefinition {
+    LetDefinition {
+        variable_name,
+        type_annotation,
+        expression
+    },
+    TypeDefinition(a, b),
+});
+walk_struct!(NodeContractDefinition {
+    contract_name,
+    parameters,
+    constraint,
+    fields,
+    components,
+});
+walk_struct!(NodeContractField {
+    typed_identifier,
+    right_hand_side
+});
+walk_struct!(NodeWithConstraint { expression });
+walk_enum!(NodeComponentDefinition {
+    TransitionComponent(a),
+    ProcedureComponent(a),
+});
+walk_struct!(NodeProcedureDefinition {
+    name,
+    parameters,
+    body
+});
+walk_struct!(NodeTransitionDefinition {
+    name,
+    parameters,
+    body
+});
+walk_enum!(NodeTypeAlternativeClause {
+    ClauseType(a),
+    ClauseTypeWithArgs(a, b),
+});
+walk_enum!(NodeTypeMapValueArguments {
+    EnclosedTypeMapValue(a),
+    GenericMapValueArgument(a),
+    MapKeyValueType(a, b),
+});
+walk_enum!(NodeTypeMapValueAllowingTypeArguments {
+    TypeMapValueNoArgs(a),
+    TypeMapValueWithArgs(a, b),
+});
@@ -13,7 +13,9 @@ pub trait AstVisitor {
 impl<T: AstVisitor> AstVisitor for WithMetaData<T> {
     fn visit(&self, emitter: &mut dyn AstConverting) -> Result<TraversalResult, String> {
         emitter.push_source_position(&self.start, &self.end);
+        emitter.emit_leading_comments(&self.trivia.leading);
         let ret = self.node.visit(emitter);
+        emitter.emit_trailing_comments(&self.trivia.trailing);
         emitter.pop_source_position();
 
         ret
@@ -988,13 +990,13 @@ impl AstVisitor for NodeLibrarySingleDefinition {
             match self {
                 NodeLibrarySingleDefinition::LetDefinition {
                     variable_name: _,
-                    type_annotation: _,
+                    type_annotation,
                     expression,
                 } => {
-                    // TODO: Unused variables aboce
-                    let _ = expression.visit(emitter)?;
-
-                    unimplemented!()
+                    if let Some(type_annotation) = type_annotation {
+                        type_annotation.visit(emitter)?;
+                    }
+                    expression.visit(emitter)
                 }
                 NodeLibrarySingleDefinition::TypeDefinition(name, option_clause) => {
                     let result = name.visit(emitter);
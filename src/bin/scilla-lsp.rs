@@ -0,0 +1,12 @@
+//! A language server for Scilla contracts, speaking the Language Server Protocol over stdio.
+
+use lsp_server::Connection;
+use scilla_parser::Error;
+
+fn main() -> Result<(), Error> {
+    let (connection, io_threads) = Connection::stdio();
+    scilla_parser::lsp::run(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,490 @@
+//! The catalogue of Scilla's builtin functions, e.g. `builtin add a b`, and the checking of calls
+//! to them.
+//! See https://scilla.readthedocs.io/en/latest/scilla-in-depth.html#primitive-data-types-operations
+
+use std::{borrow::Cow, collections::HashMap, fmt, sync::OnceLock};
+
+use crate::{
+    ast::nodes::{NodeFullExpression, WithMetaData},
+    typecheck::{compatible, substitute},
+    Type, TypeError, TypeErrorKind,
+};
+
+/// The types a type variable of a builtin signature may stand for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeClass {
+    Any,
+    /// `Int32` to `Int256` and `Uint32` to `Uint256`.
+    Integer,
+    /// `Uint32` to `Uint256`.
+    Unsigned,
+    /// Byte strings of a fixed length, e.g. `ByStr20`, addresses included.
+    ByStrX,
+    /// `ByStr` and the byte strings of a fixed length.
+    ByteString,
+    /// The types that can be compared with `eq` and used as map keys: integers, `String`, `BNum`
+    /// and byte strings.
+    Primitive,
+}
+
+impl TypeClass {
+    /// Returns true if `r#type` belongs to the class. Unknown types belong to every class.
+    pub fn contains(&self, r#type: &Type) -> bool {
+        let unsigned = matches!(
+            r#type,
+            Type::Uint32 | Type::Uint64 | Type::Uint128 | Type::Uint256
+        );
+        let integer = unsigned
+            || matches!(
+                r#type,
+                Type::Int32 | Type::Int64 | Type::Int128 | Type::Int256
+            );
+        let bystrx = matches!(
+            r#type,
+            Type::ByStrX(_) | Type::ByStr20 | Type::ByStr20With(_)
+        );
+        match r#type {
+            Type::TypeVar(_) | Type::Other(_) => true,
+            _ => match self {
+                TypeClass::Any => true,
+                TypeClass::Integer => integer,
+                TypeClass::Unsigned => unsigned,
+                TypeClass::ByStrX => bystrx,
+                TypeClass::ByteString => bystrx || *r#type == Type::ByStr,
+                TypeClass::Primitive => {
+                    integer || bystrx || matches!(r#type, Type::ByStr | Type::String | Type::BNum)
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for TypeClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TypeClass::Any => "any type",
+            TypeClass::Integer => "an integer type",
+            TypeClass::Unsigned => "an unsigned integer type",
+            TypeClass::ByStrX => "a ByStrX type",
+            TypeClass::ByteString => "a byte string type",
+            TypeClass::Primitive => "a primitive type",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// One way of calling a builtin, e.g. `'A -> 'A -> 'A` for `add` where `'A` is an integer type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltinSignature {
+    /// The types of the arguments, with type variables standing for the types of `constraints`.
+    pub arguments: Vec<Type>,
+    pub result: Type,
+    /// The class of each type variable of the signature.
+    pub constraints: Vec<(String, TypeClass)>,
+}
+
+impl BuiltinSignature {
+    fn new(arguments: Vec<Type>, result: Type) -> Self {
+        Self {
+            arguments,
+            result,
+            constraints: vec![],
+        }
+    }
+
+    fn with(mut self, variable: &str, class: TypeClass) -> Self {
+        self.constraints.push((variable.to_string(), class));
+        self
+    }
+
+    /// Returns the type of the result for arguments of the given types, or `None` if the
+    /// signature does not accept them. Type variables are left in the result where it depends
+    /// on arguments of unknown type.
+    pub fn apply(&self, arguments: &[Type]) -> Option<Type> {
+        if arguments.len() != self.arguments.len() {
+            return None;
+        }
+        let mut bindings = HashMap::new();
+        for (expected, found) in self.arguments.iter().zip(arguments) {
+            if !self.unify(expected, found, &mut bindings) {
+                return None;
+            }
+        }
+        let result = bindings
+            .iter()
+            .fold(self.result.clone(), |result, (variable, by)| {
+                substitute(&result, variable, by)
+            });
+        Some(result)
+    }
+
+    /// Matches `found` against `expected`, binding the type variables of `expected`.
+    fn unify<'a>(
+        &'a self,
+        expected: &'a Type,
+        found: &Type,
+        bindings: &mut HashMap<&'a str, Type>,
+    ) -> bool {
+        match (expected, found) {
+            // A value of unknown type is accepted without telling what the variables stand for.
+            (_, Type::TypeVar(_) | Type::Other(_)) => true,
+            (Type::TypeVar(variable), found) => match bindings.get(variable.as_str()) {
+                Some(bound) => compatible(bound, found),
+                None => {
+                    let class = self
+                        .constraints
+                        .iter()
+                        .find(|(v, _)| v == variable)
+                        .map_or(TypeClass::Any, |(_, class)| *class);
+                    bindings.insert(variable, found.clone());
+                    class.contains(found)
+                }
+            },
+            (Type::Map(k1, v1), Type::Map(k2, v2)) | (Type::Pair(k1, v1), Type::Pair(k2, v2)) => {
+                self.unify(k1, k2, bindings) && self.unify(v1, v2, bindings)
+            }
+            (Type::Option(t1), Type::Option(t2)) | (Type::List(t1), Type::List(t2)) => {
+                self.unify(t1, t2, bindings)
+            }
+            (expected, found) => compatible(expected, found),
+        }
+    }
+}
+
+impl fmt::Display for BuiltinSignature {
+    /// Prints the signature as a function type followed by its constraints, e.g.
+    /// `'A -> 'A -> 'A where 'A is an integer type`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for argument in &self.arguments {
+            match argument {
+                Type::Function(..) => write!(f, "({argument}) -> ")?,
+                _ => write!(f, "{argument} -> ")?,
+            }
+        }
+        write!(f, "{}", self.result)?;
+        for (i, (variable, class)) in self.constraints.iter().enumerate() {
+            let separator = if i == 0 { " where" } else { "," };
+            write!(f, "{separator} {variable} is {class}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A builtin function with the signatures it can be called with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Builtin {
+    pub name: Cow<'static, str>,
+    pub signatures: Vec<BuiltinSignature>,
+}
+
+impl Builtin {
+    /// Returns the type of the result for arguments of the given types, from the first signature
+    /// accepting them.
+    pub fn apply(&self, arguments: &[Type]) -> Option<Type> {
+        let result = self
+            .signatures
+            .iter()
+            .find_map(|signature| signature.apply(arguments))?;
+        Some(concat_lengths(self, arguments, result))
+    }
+
+    /// Returns true if some signature takes `count` arguments.
+    pub fn accepts_arity(&self, count: usize) -> bool {
+        self.signatures
+            .iter()
+            .any(|signature| signature.arguments.len() == count)
+    }
+}
+
+/// Returns every builtin function.
+pub fn builtins() -> &'static [Builtin] {
+    static BUILTINS: OnceLock<Vec<Builtin>> = OnceLock::new();
+    BUILTINS.get_or_init(catalogue)
+}
+
+/// Returns the builtin function called `name`.
+pub fn find_builtin(name: &str) -> Option<&'static Builtin> {
+    builtins().iter().find(|builtin| builtin.name == name)
+}
+
+/// Checks a builtin call `builtin f x y ...` given the types of its arguments, `None` where
+/// unknown, and returns the type of its result if it can be worked out. Other expressions are
+/// not checked.
+///
+/// Unknown builtins and wrong argument counts are reported at the builtin's name, arguments of
+/// the wrong type at the whole call.
+#[allow(clippy::result_large_err)]
+pub fn check_call(
+    call: &WithMetaData<NodeFullExpression>,
+    argument_types: &[Option<Type>],
+) -> Result<Option<Type>, TypeError> {
+    let NodeFullExpression::ExpressionBuiltin { b, .. } = &call.node else {
+        return Ok(None);
+    };
+    let Some(builtin) = find_builtin(&b.node) else {
+        return Err(TypeError::at(
+            TypeErrorKind::UnknownBuiltin(b.node.clone()),
+            b,
+        ));
+    };
+    if !builtin.accepts_arity(argument_types.len()) {
+        let kind = TypeErrorKind::Arity {
+            name: b.node.clone(),
+            expected: builtin.signatures[0].arguments.len(),
+            found: argument_types.len(),
+        };
+        return Err(TypeError::at(kind, b));
+    }
+
+    let unknown = Type::Other(String::new());
+    let arguments: Vec<Type> = argument_types
+        .iter()
+        .map(|t| t.clone().unwrap_or_else(|| unknown.clone()))
+        .collect();
+    match builtin.apply(&arguments) {
+        Some(result) if mentions_unknown(&result) => Ok(None),
+        Some(result) => Ok(Some(result)),
+        // Arguments of unknown type may make the call valid.
+        None if argument_types.iter().any(Option::is_none) => Ok(None),
+        None => Err(TypeError::at(
+            TypeErrorKind::BuiltinArguments {
+                builtin: b.node.clone(),
+                arguments,
+            },
+            call,
+        )),
+    }
+}
+
+fn mentions_unknown(r#type: &Type) -> bool {
+    match r#type {
+        Type::TypeVar(_) | Type::Other(_) => true,
+        Type::Map(a, b) | Type::Pair(a, b) | Type::Function(a, b) => {
+            mentions_unknown(a) || mentions_unknown(b)
+        }
+        Type::Option(t) | Type::List(t) | Type::Forall(_, t) => mentions_unknown(t),
+        Type::Adt { args, .. } => args.iter().any(mentions_unknown),
+        _ => false,
+    }
+}
+
+/// The result of `concat` on byte strings of fixed lengths is as long as both together, which
+/// signatures cannot express.
+fn concat_lengths(builtin: &Builtin, arguments: &[Type], result: Type) -> Type {
+    let length = |t: &Type| match t {
+        Type::ByStrX(n) => Some(*n),
+        Type::ByStr20 | Type::ByStr20With(_) => Some(20),
+        _ => None,
+    };
+    match (builtin.name.as_ref(), arguments) {
+        ("concat", [a, b]) => match (length(a), length(b)) {
+            (Some(a), Some(b)) => Type::ByStrX(a + b),
+            _ => result,
+        },
+        _ => result,
+    }
+}
+
+fn catalogue() -> Vec<Builtin> {
+    use Type::*;
+
+    let a = || TypeVar("'A".to_string());
+    let b = || TypeVar("'B".to_string());
+    let k = || TypeVar("'K".to_string());
+    let v = || TypeVar("'V".to_string());
+    let option = |t: Type| Option(Box::new(t));
+    let map = || Map(Box::new(k()), Box::new(v()));
+    let nat = || Adt {
+        name: "Nat".to_string(),
+        args: vec![],
+        constructors: vec![],
+    };
+    let signature = BuiltinSignature::new;
+    let builtin = |name: &'static str, signatures| Builtin {
+        name: Cow::Borrowed(name),
+        signatures,
+    };
+
+    let mut builtins = vec![
+        builtin(
+            "eq",
+            vec![signature(vec![a(), a()], Bool).with("'A", TypeClass::Primitive)],
+        ),
+        builtin(
+            "concat",
+            vec![
+                signature(vec![String, String], String),
+                signature(vec![ByStr, ByStr], ByStr),
+                signature(vec![a(), b()], ByStr)
+                    .with("'A", TypeClass::ByStrX)
+                    .with("'B", TypeClass::ByStrX),
+            ],
+        ),
+        builtin(
+            "substr",
+            vec![signature(vec![String, Uint32, Uint32], String)],
+        ),
+        builtin("strlen", vec![signature(vec![String], Uint32)]),
+        builtin("strrev", vec![signature(vec![String], String)]),
+        builtin(
+            "to_string",
+            vec![signature(vec![a()], String).with("'A", TypeClass::Primitive)],
+        ),
+        builtin(
+            "to_ascii",
+            vec![signature(vec![a()], String).with("'A", TypeClass::ByteString)],
+        ),
+        builtin("sha256hash", vec![signature(vec![a()], ByStrX(32))]),
+        builtin("keccak256hash", vec![signature(vec![a()], ByStrX(32))]),
+        builtin("ripemd160hash", vec![signature(vec![a()], ByStr20)]),
+        builtin(
+            "to_bystr",
+            vec![signature(vec![a()], ByStr).with("'A", TypeClass::ByStrX)],
+        ),
+        builtin(
+            "bech32_to_bystr20",
+            vec![signature(vec![String, String], option(ByStr20))],
+        ),
+        builtin(
+            "bystr20_to_bech32",
+            vec![signature(vec![String, ByStr20], option(String))],
+        ),
+        builtin(
+            "schnorr_verify",
+            vec![signature(vec![ByStrX(33), ByStr, ByStrX(64)], Bool)],
+        ),
+        builtin(
+            "ecdsa_verify",
+            vec![signature(vec![ByStrX(33), ByStr, ByStrX(64)], Bool)],
+        ),
+        builtin(
+            "schnorr_get_address",
+            vec![signature(vec![ByStrX(33)], ByStr20)],
+        ),
+        builtin(
+            "ecdsa_recover_pk",
+            vec![signature(vec![ByStr, ByStrX(64), Uint32], ByStrX(65))],
+        ),
+        builtin(
+            "put",
+            vec![signature(vec![map(), k(), v()], map()).with("'K", TypeClass::Primitive)],
+        ),
+        builtin(
+            "get",
+            vec![signature(vec![map(), k()], option(v())).with("'K", TypeClass::Primitive)],
+        ),
+        builtin(
+            "contains",
+            vec![signature(vec![map(), k()], Bool).with("'K", TypeClass::Primitive)],
+        ),
+        builtin(
+            "remove",
+            vec![signature(vec![map(), k()], map()).with("'K", TypeClass::Primitive)],
+        ),
+        builtin(
+            "to_list",
+            vec![signature(
+                vec![map()],
+                List(Box::new(Pair(Box::new(k()), Box::new(v())))),
+            )],
+        ),
+        builtin("size", vec![signature(vec![map()], Uint32)]),
+        builtin("blt", vec![signature(vec![BNum, BNum], Bool)]),
+        builtin(
+            "badd",
+            vec![signature(vec![BNum, a()], BNum).with("'A", TypeClass::Unsigned)],
+        ),
+        builtin("bsub", vec![signature(vec![BNum, BNum], Int256)]),
+        builtin(
+            "pow",
+            vec![signature(vec![a(), Uint32], a()).with("'A", TypeClass::Integer)],
+        ),
+        builtin(
+            "isqrt",
+            vec![signature(vec![a()], a()).with("'A", TypeClass::Unsigned)],
+        ),
+        builtin(
+            "lt",
+            vec![signature(vec![a(), a()], Bool).with("'A", TypeClass::Integer)],
+        ),
+        builtin("to_nat", vec![signature(vec![Uint32], nat())]),
+    ];
+
+    for name in ["add", "sub", "mul", "div", "rem"] {
+        builtins.push(builtin(
+            name,
+            vec![signature(vec![a(), a()], a()).with("'A", TypeClass::Integer)],
+        ));
+    }
+
+    let integers = [
+        ("to_int32", Int32),
+        ("to_int64", Int64),
+        ("to_int128", Int128),
+        ("to_int256", Int256),
+        ("to_uint32", Uint32),
+        ("to_uint64", Uint64),
+        ("to_uint128", Uint128),
+        ("to_uint256", Uint256),
+    ];
+    for (name, integer) in integers {
+        let mut signatures = vec![
+            signature(vec![a()], option(integer.clone())).with("'A", TypeClass::Integer),
+            signature(vec![String], option(integer.clone())),
+        ];
+        // Byte strings are read as big-endian unsigned integers.
+        if TypeClass::Unsigned.contains(&integer) {
+            signatures.push(signature(vec![a()], integer).with("'A", TypeClass::ByStrX));
+        }
+        builtins.push(builtin(name, signatures));
+    }
+
+    // `to_bystrX` reads a `ByStr` of length X, or writes an unsigned integer of X bytes big-endian.
+    // Lengths go up to the 65 bytes of an uncompressed public key.
+    for length in 1..=65 {
+        let bystrx = if length == 20 {
+            ByStr20
+        } else {
+            ByStrX(length)
+        };
+        let mut signatures = vec![signature(vec![ByStr], option(bystrx.clone()))];
+        let unsigned = match length {
+            4 => Some(Uint32),
+            8 => Some(Uint64),
+            16 => Some(Uint128),
+            32 => Some(Uint256),
+            _ => None,
+        };
+        if let Some(unsigned) = unsigned {
+            signatures.push(signature(vec![unsigned], bystrx));
+        }
+        builtins.push(Builtin {
+            name: Cow::Owned(format!("to_bystr{length}")),
+            signatures,
+        });
+    }
+
+    // Points of the alt_bn128 curve: G1 as a pair of coordinates of 32 bytes, G2 of 64 bytes.
+    let pair = |a: Type, b: Type| Pair(Box::new(a), Box::new(b));
+    let g1 = || pair(ByStrX(32), ByStrX(32));
+    let g2 = || pair(ByStrX(64), ByStrX(64));
+    builtins.extend([
+        builtin(
+            "alt_bn128_G1_add",
+            vec![signature(vec![g1(), g1()], option(g1()))],
+        ),
+        builtin(
+            "alt_bn128_G1_mul",
+            vec![signature(vec![g1(), ByStrX(32)], option(g1()))],
+        ),
+        builtin(
+            "alt_bn128_pairing_product",
+            vec![signature(
+                vec![List(Box::new(pair(g1(), g2())))],
+                option(Bool),
+            )],
+        ),
+    ]);
+
+    builtins
+}
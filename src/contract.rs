@@ -1,23 +1,32 @@
 use std::{path::Path, str::FromStr};
 
 use crate::{
-    parser::{lexer::Lexer, parser},
-    simplified_representation::emitter::SrEmitter,
-    Error, FieldList, TransitionList,
+    ast::nodes::NodeProgram, parser, simplified_representation::emitter::SrEmitter, Error,
+    FieldList, Import, Library, ProcedureList, TransitionList,
 };
 
 #[derive(Debug, PartialEq, Default)]
 /// The `Contract` struct represents a parsed contract in Rust, including its name, initialization
-/// parameters, fields, and transitions.
+/// parameters, fields, transitions, procedures and library.
 pub struct Contract {
+    /// The `scilla_version` the contract is written for.
+    pub scilla_version: u32,
+    /// Libraries imported by the contract.
+    pub imports: Vec<Import>,
     /// Name of the parsed contract
     pub name: String,
     /// List of parameters needed to deploy the contract.
     pub init_params: FieldList,
+    /// The source of the contract's `with ... =>` constraint expression, if it has one.
+    pub constraint: Option<String>,
     /// List of the contract's fields.
     pub fields: FieldList,
     /// List of the contract's transitions.
     pub transitions: TransitionList,
+    /// List of the contract's procedures.
+    pub procedures: ProcedureList,
+    /// The contract's library, if it has one.
+    pub library: Option<Library>,
 }
 
 impl FromStr for Contract {
@@ -27,7 +36,10 @@ impl FromStr for Contract {
     /// # Example
     /// ```
     /// use std::{error::Error, path::PathBuf};
-    /// use scilla_parser::{Contract, Field, FieldList, Transition, TransitionList, Type};
+    /// use scilla_parser::{
+    ///     Contract, Event, Field, FieldList, MessageParam, ProcedureList, Transition, TransitionList,
+    ///     Type,
+    /// };
     /// let contract_path = PathBuf::from("tests/contracts/chainid.scilla");
     /// let contract_str = include_str!("../tests/contracts/chainid.scilla");
     /// let contract = contract_str.parse::<Contract>().unwrap();
@@ -35,46 +47,85 @@ impl FromStr for Contract {
     /// assert_eq!(
     ///     contract,
     ///     Contract {
+    ///         scilla_version: 0,
+    ///         imports: vec![],
     ///         name: "ChainId".to_string(),
     ///         fields: FieldList(vec![Field::new("dummy_field", Type::Uint256)]),
     ///         init_params: FieldList::default(),
-    ///         transitions: TransitionList(vec![Transition::new(
-    ///             "EventChainID",
-    ///             FieldList::default()
-    ///         )])
+    ///         transitions: TransitionList(vec![Transition {
+    ///             events: vec![Event::new(
+    ///                 "ChainID",
+    ///                 vec![MessageParam::new("chain_id", Some(Type::Uint32))],
+    ///             )],
+    ///             ..Transition::new("EventChainID", FieldList::default())
+    ///         }]),
+    ///         procedures: ProcedureList::default(),
+    ///         constraint: None,
+    ///         library: None,
     ///     }
     /// );
     /// ```
     fn from_str(contract: &str) -> Result<Self, Self::Err> {
-        let mut errors = vec![];
-        let parsed = parser::ProgramParser::new().parse(&mut errors, Lexer::new(contract))?;
+        let (parsed, errors) = parser::parse_program(contract);
+        let parsed = match parsed {
+            Some(parsed) if errors.is_empty() => parsed,
+            _ => return Err(Error::ParseError(errors)),
+        };
 
-        let emitter = SrEmitter::default();
-        emitter.emit(&parsed).map_err(Error::ParseError)
+        Self::from_program(&parsed, contract)
     }
 }
 
 impl Contract {
+    /// Builds a contract from the AST of its `source`.
+    pub(crate) fn from_program(program: &NodeProgram, source: &str) -> Result<Self, Error> {
+        let emitter = SrEmitter::default();
+        let mut contract = emitter.emit(program).map_err(Error::AstVisitError)?;
+        // The AST does not keep the source, so the constraint is sliced out of it here.
+        contract.constraint =
+            program
+                .contract_definition
+                .node
+                .constraint
+                .as_ref()
+                .map(|constraint| {
+                    let expression = &constraint.node.expression;
+                    source[expression.start.position..expression.end.position].to_string()
+                });
+        Ok(contract)
+    }
+
     /// Parse a contract from a given path.
     ///
     /// # Examples
     ///
     /// ```
     /// use std::{error::Error, path::PathBuf};
-    /// use scilla_parser::{Contract, Field, FieldList, Transition, TransitionList, Type};
+    /// use scilla_parser::{
+    ///     Contract, Event, Field, FieldList, MessageParam, ProcedureList, Transition, TransitionList,
+    ///     Type,
+    /// };
     /// let contract_path = PathBuf::from("tests/contracts/chainid.scilla");
     /// let contract = Contract::parse(&contract_path).unwrap();
     ///
     /// assert_eq!(
     ///     contract,
     ///     Contract {
+    ///         scilla_version: 0,
+    ///         imports: vec![],
     ///         name: "ChainId".to_string(),
     ///         fields: FieldList(vec![Field::new("dummy_field", Type::Uint256)]),
     ///         init_params: FieldList::default(),
-    ///         transitions: TransitionList(vec![Transition::new(
-    ///             "EventChainID",
-    ///             FieldList::default()
-    ///         )])
+    ///         transitions: TransitionList(vec![Transition {
+    ///             events: vec![Event::new(
+    ///                 "ChainID",
+    ///                 vec![MessageParam::new("chain_id", Some(Type::Uint32))],
+    ///             )],
+    ///             ..Transition::new("EventChainID", FieldList::default())
+    ///         }]),
+    ///         procedures: ProcedureList::default(),
+    ///         constraint: None,
+    ///         library: None,
     ///     }
     /// );
     /// ```
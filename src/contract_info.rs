@@ -0,0 +1,213 @@
+//! Serialization of a [`Contract`] into the JSON produced by `scilla-checker -contractinfo`.
+
+use serde::Serialize;
+
+use crate::{
+    validation::implicit_contract_params, Constructor, Contract, Error, Field, MessageParam, Type,
+};
+
+/// The contract info of a contract, in the shape of `scilla-checker -contractinfo`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ContractInfo {
+    pub scilla_major_version: String,
+    pub vname: String,
+    pub params: Vec<ParamInfo>,
+    pub fields: Vec<FieldInfo>,
+    pub transitions: Vec<ComponentInfo>,
+    pub procedures: Vec<ComponentInfo>,
+    pub events: Vec<ComponentInfo>,
+    #[serde(rename = "ADTs")]
+    pub adts: Vec<AdtInfo>,
+}
+
+/// A named and typed value, e.g. an init parameter or a transition parameter.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ParamInfo {
+    pub vname: String,
+    /// The type of the value. Empty for an event parameter whose type could not be worked out.
+    #[serde(rename = "type")]
+    pub r#type: String,
+}
+
+/// A mutable field. `depth` is the number of nested maps in the field's type.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FieldInfo {
+    pub vname: String,
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub depth: usize,
+}
+
+/// A transition, procedure or event with its parameters.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ComponentInfo {
+    pub vname: String,
+    pub params: Vec<ParamInfo>,
+}
+
+/// An algebraic data type in scope of the contract.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AdtInfo {
+    pub tname: String,
+    pub tparams: Vec<String>,
+    pub tmap: Vec<ConstructorInfo>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ConstructorInfo {
+    pub cname: String,
+    pub argtypes: Vec<String>,
+}
+
+/// The top-level object of the `scilla-checker -contractinfo` output.
+#[derive(Serialize)]
+struct CheckerOutput<'a> {
+    contract_info: &'a ContractInfo,
+}
+
+impl ContractInfo {
+    /// Serializes the contract info into pretty-printed JSON, inside the `contract_info` object
+    /// scilla-checker wraps it in.
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(&CheckerOutput {
+            contract_info: self,
+        })?)
+    }
+}
+
+impl Contract {
+    /// Returns the contract info of the contract, as `scilla-checker -contractinfo` would.
+    pub fn contract_info(&self) -> ContractInfo {
+        self.into()
+    }
+}
+
+impl From<&Contract> for ContractInfo {
+    fn from(contract: &Contract) -> Self {
+        let mut events = vec![];
+        for event in contract.transitions.iter().flat_map(|t| t.events.iter()) {
+            let Some(name) = &event.name else {
+                continue;
+            };
+            let event = ComponentInfo {
+                vname: name.clone(),
+                params: event.params.iter().map(message_param_info).collect(),
+            };
+            if !events.contains(&event) {
+                events.push(event);
+            }
+        }
+
+        let mut adts: Vec<AdtInfo> = contract
+            .library
+            .iter()
+            .flat_map(|library| library.type_definitions.iter())
+            .map(|definition| AdtInfo {
+                tname: definition.name.clone(),
+                tparams: vec![],
+                tmap: definition
+                    .constructors
+                    .iter()
+                    .map(constructor_info)
+                    .collect(),
+            })
+            .collect();
+        adts.extend(builtin_adts());
+
+        Self {
+            scilla_major_version: contract.scilla_version.to_string(),
+            vname: contract.name.clone(),
+            params: implicit_contract_params()
+                .iter()
+                .chain(contract.init_params.iter())
+                .map(param_info)
+                .collect(),
+            fields: contract
+                .fields
+                .iter()
+                .map(|field| FieldInfo {
+                    vname: field.name.clone(),
+                    r#type: field.r#type.to_string(),
+                    depth: map_depth(&field.r#type),
+                })
+                .collect(),
+            transitions: contract
+                .transitions
+                .iter()
+                .map(|transition| ComponentInfo {
+                    vname: transition.name.clone(),
+                    params: transition.params.iter().map(param_info).collect(),
+                })
+                .collect(),
+            procedures: contract
+                .procedures
+                .iter()
+                .map(|procedure| ComponentInfo {
+                    vname: procedure.name.clone(),
+                    params: procedure.params.iter().map(param_info).collect(),
+                })
+                .collect(),
+            events,
+            adts,
+        }
+    }
+}
+
+fn param_info(field: &Field) -> ParamInfo {
+    ParamInfo {
+        vname: field.name.clone(),
+        r#type: field.r#type.to_string(),
+    }
+}
+
+fn message_param_info(param: &MessageParam) -> ParamInfo {
+    ParamInfo {
+        vname: param.name.clone(),
+        r#type: param
+            .r#type
+            .as_ref()
+            .map(Type::to_string)
+            .unwrap_or_default(),
+    }
+}
+
+fn constructor_info(constructor: &Constructor) -> ConstructorInfo {
+    ConstructorInfo {
+        cname: constructor.name.clone(),
+        argtypes: constructor.arg_types.iter().map(Type::to_string).collect(),
+    }
+}
+
+fn map_depth(r#type: &Type) -> usize {
+    match r#type {
+        Type::Map(_, value) => 1 + map_depth(value),
+        _ => 0,
+    }
+}
+
+/// The ADTs scilla-checker reports for every contract.
+fn builtin_adts() -> Vec<AdtInfo> {
+    let adt = |tname: &str, tparams: &[&str], tmap: &[(&str, &[&str])]| AdtInfo {
+        tname: tname.to_string(),
+        tparams: tparams.iter().map(|p| p.to_string()).collect(),
+        tmap: tmap
+            .iter()
+            .map(|(cname, argtypes)| ConstructorInfo {
+                cname: cname.to_string(),
+                argtypes: argtypes.iter().map(|t| t.to_string()).collect(),
+            })
+            .collect(),
+    };
+
+    vec![
+        adt("Option", &["'A"], &[("Some", &["'A"]), ("None", &[])]),
+        adt("Bool", &[], &[("True", &[]), ("False", &[])]),
+        adt("Nat", &[], &[("Zero", &[]), ("Succ", &["Nat"])]),
+        adt(
+            "List",
+            &["'A"],
+            &[("Cons", &["'A", "List ('A)"]), ("Nil", &[])],
+        ),
+        adt("Pair", &["'A", "'B"], &[("Pair", &["'A", "'B"])]),
+    ]
+}
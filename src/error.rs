@@ -1,31 +1,78 @@
-use core::fmt;
-use std::string::FromUtf8Error;
+use std::{path::PathBuf, string::FromUtf8Error};
 
-use lalrpop_util::ParseError;
 use thiserror::Error as ThisError;
 
+use crate::parser::ParserError;
+
 #[derive(Debug, ThisError)]
 pub enum Error {
-    #[error("Failed to parse the contract. {0}")]
-    ParseError(String),
+    /// Every syntax error found in the contract.
+    #[error("Failed to parse the contract.\n{}", display_errors(.0))]
+    ParseError(Vec<ParserError>),
+
+    /// Every syntax error found in a type parsed on its own.
+    #[error("Failed to parse the type.\n{}", display_errors(.0))]
+    TypeParseError(Vec<ParserError>),
 
     #[error("Failed to visit AST {0}")]
     AstVisitError(String),
 
+    #[error("Invalid type: {0}")]
+    InvalidType(String),
+
+    /// Every syntax error found in an imported library file.
+    #[error("Failed to parse {}.\n{}", path.display(), display_errors(errors))]
+    LibraryParseError {
+        path: PathBuf,
+        errors: Vec<ParserError>,
+    },
+
+    /// An imported library is in none of the search paths.
+    #[error("Library {name} imported by {} was not found", importer.display())]
+    MissingImport { name: String, importer: PathBuf },
+
+    /// A library file found for an import defines a library with another name.
+    #[error("{} defines library {found}, not the imported {name}", path.display())]
+    LibraryNameMismatch {
+        name: String,
+        found: String,
+        path: PathBuf,
+    },
+
+    /// Libraries importing each other, from the first library of the cycle back to itself.
+    #[error("Cyclic import: {}", .0.join(" -> "))]
+    CyclicImport(Vec<String>),
+
     #[error(transparent)]
     IoError(#[from] std::io::Error),
 
     #[error(transparent)]
     FromUtf8Error(#[from] FromUtf8Error),
+
+    #[cfg(feature = "json")]
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[cfg(feature = "lsp")]
+    #[error(transparent)]
+    ProtocolError(#[from] lsp_server::ProtocolError),
+
+    /// The client of the language server went away.
+    #[cfg(feature = "lsp")]
+    #[error("The connection to the client is closed")]
+    Disconnected,
 }
 
-impl<L, T, E> From<ParseError<L, T, E>> for Error
-where
-    L: fmt::Debug,
-    T: fmt::Debug,
-    E: fmt::Debug,
-{
-    fn from(value: ParseError<L, T, E>) -> Self {
-        Self::ParseError(format!("{:?}", value))
+impl From<ParserError> for Error {
+    fn from(error: ParserError) -> Self {
+        Error::ParseError(vec![error])
     }
 }
+
+fn display_errors(errors: &[ParserError]) -> String {
+    errors
+        .iter()
+        .map(ParserError::to_string)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
@@ -4,6 +4,8 @@ use crate::Type;
 pub struct Field {
     pub name: String,
     pub r#type: Type,
+    /// The `(* @doc ... *)` comments above a contract field.
+    pub doc: Option<String>,
 }
 
 impl Default for Field {
@@ -11,6 +13,7 @@ impl Default for Field {
         Self {
             name: Default::default(),
             r#type: Type::Other(Default::default()),
+            doc: None,
         }
     }
 }
@@ -26,6 +29,7 @@ impl Field {
         Self {
             name: name.to_string(),
             r#type,
+            doc: None,
         }
     }
 }
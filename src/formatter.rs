@@ -0,0 +1,1337 @@
+//! Pretty-printing of a Scilla AST back into canonical Scilla source.
+
+use crate::{
+    ast::{
+        converting::AstConverting, nodes::*, visitor::AstVisitor, TraversalResult,
+        TreeTraversalMode,
+    },
+    parser::{
+        self,
+        lexer::{Comment, SourcePosition},
+    },
+    Error,
+};
+
+/// Number of spaces per indentation level.
+const INDENT_WIDTH: usize = 2;
+
+/// The `ScillaFormatter` prints a Scilla AST as source code with consistent layout.
+/// It implements the `AstConverting` trait: every node prints itself on `Enter`, visiting its
+/// children in source order, and skips the default traversal of its children.
+#[derive(Default)]
+pub struct ScillaFormatter {
+    output: String,
+    indent: usize,
+    /// Set after a line break, the indentation is written with the next token.
+    at_line_start: bool,
+    /// The end positions of the nodes being printed.
+    node_ends: Vec<SourcePosition>,
+    /// Leading comments waiting for the next token.
+    leading_comments: Vec<Comment>,
+    /// Leading comments already written ahead of the node they are attached to.
+    hoisted_comments: Vec<SourcePosition>,
+    /// Trailing comments waiting for the end of the line, and whether each goes on a line of its own.
+    trailing_comments: Vec<(Comment, bool)>,
+}
+
+/// Parses `source` and prints it back in canonical layout, keeping its comments.
+pub fn format(source: &str) -> Result<String, Error> {
+    let (program, errors) = parser::parse_program(source);
+    match program {
+        Some(program) if errors.is_empty() => ScillaFormatter::default().format(&program),
+        _ => Err(Error::ParseError(errors)),
+    }
+}
+
+impl ScillaFormatter {
+    /// Prints `program` as Scilla source.
+    pub fn format(mut self, program: &NodeProgram) -> Result<String, Error> {
+        program.visit(&mut self).map_err(Error::AstVisitError)?;
+        if !self.leading_comments.is_empty() || !self.trailing_comments.is_empty() {
+            self.write("");
+            self.new_line();
+        }
+        Ok(self.output)
+    }
+
+    /// Writes `text`, preceded by the pending leading comments. These go on lines of their own,
+    /// unless code precedes them on the line.
+    fn write(&mut self, text: &str) {
+        for comment in std::mem::take(&mut self.leading_comments) {
+            if self.output.is_empty() || self.at_line_start {
+                self.write_raw(&comment.to_string());
+                self.output.push('\n');
+                self.at_line_start = true;
+            } else {
+                self.write_raw(&format!("{comment} "));
+            }
+        }
+        self.write_raw(text);
+    }
+
+    fn write_raw(&mut self, text: &str) {
+        if self.at_line_start {
+            self.output
+                .push_str(&" ".repeat(self.indent * INDENT_WIDTH));
+            self.at_line_start = false;
+        }
+        self.output.push_str(text);
+    }
+
+    /// Ends the line, followed by the trailing comments collected on it.
+    fn new_line(&mut self) {
+        let (own_line, same_line): (Vec<_>, Vec<_>) = std::mem::take(&mut self.trailing_comments)
+            .into_iter()
+            .partition(|(_, own_line)| *own_line);
+        for (comment, _) in same_line {
+            self.output.push_str(&format!(" {comment}"));
+        }
+        self.output.push('\n');
+        self.at_line_start = true;
+        for (comment, _) in own_line {
+            self.write_raw(&comment.to_string());
+            self.output.push('\n');
+            self.at_line_start = true;
+        }
+    }
+
+    fn blank_line(&mut self) {
+        self.new_line();
+        self.new_line();
+    }
+
+    /// Visits `items`, writing `separator` between them.
+    fn write_separated<T: AstVisitor>(
+        &mut self,
+        items: &[T],
+        separator: &str,
+    ) -> Result<(), String> {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.write(separator);
+            }
+            item.visit(self)?;
+        }
+        Ok(())
+    }
+
+    /// Visits each of `items`, preceded by a space.
+    fn write_arguments<T: AstVisitor>(&mut self, items: &[T]) -> Result<(), String> {
+        for item in items {
+            self.write(" ");
+            item.visit(self)?;
+        }
+        Ok(())
+    }
+
+    /// Visits `node` on the following line, one level deeper.
+    fn write_indented<T: AstVisitor>(&mut self, node: &T) -> Result<(), String> {
+        self.indent += 1;
+        self.new_line();
+        node.visit(self)?;
+        self.indent -= 1;
+        Ok(())
+    }
+
+    /// Writes the right hand side of a binding, e.g. `x = e`. Expressions spanning several
+    /// lines start on the next line.
+    fn write_binding(
+        &mut self,
+        expression: &WithMetaData<NodeFullExpression>,
+    ) -> Result<(), String> {
+        self.write(" =");
+        if is_multi_line(expression) {
+            self.write_indented(expression)
+        } else {
+            self.write(" ");
+            expression.visit(self).map(|_| ())
+        }
+    }
+
+    fn write_keys(&mut self, keys: &[WithMetaData<NodeMapAccess>]) -> Result<(), String> {
+        self.write_separated(keys, "")
+    }
+
+    /// Writes the leading comments of a statement's first node before the statement's keyword.
+    fn hoist_leading_comments(&mut self, trivia: &Trivia) {
+        self.leading_comments.extend_from_slice(&trivia.leading);
+        self.hoisted_comments
+            .extend(trivia.leading.iter().map(|comment| comment.start));
+    }
+
+    /// Writes a name along with its comments, which the visitor does not reach.
+    fn write_name(&mut self, name: &WithMetaData<String>) {
+        self.push_source_position(&name.start, &name.end);
+        self.emit_leading_comments(&name.trivia.leading);
+        self.write(&name.node);
+        self.emit_trailing_comments(&name.trivia.trailing);
+        self.pop_source_position();
+    }
+}
+
+/// Expressions that are always printed over several lines, or start with a comment.
+fn is_multi_line(expression: &WithMetaData<NodeFullExpression>) -> bool {
+    !expression.trivia.leading.is_empty()
+        || matches!(
+            expression.node,
+            NodeFullExpression::LocalVariableDeclaration { .. }
+                | NodeFullExpression::FunctionDeclaration { .. }
+                | NodeFullExpression::Match { .. }
+                | NodeFullExpression::TemplateFunction { .. }
+        )
+}
+
+impl AstConverting for ScillaFormatter {
+    fn push_source_position(&mut self, _start: &SourcePosition, end: &SourcePosition) {
+        self.node_ends.push(*end);
+    }
+
+    fn pop_source_position(&mut self) {
+        self.node_ends.pop();
+    }
+
+    /// Comments are written with the first token of the node.
+    fn emit_leading_comments(&mut self, comments: &[Comment]) {
+        for comment in comments {
+            if !self.hoisted_comments.contains(&comment.start) {
+                self.leading_comments.push(comment.clone());
+            }
+        }
+    }
+
+    /// Comments are written at the end of the line, or below it if they started on a later line.
+    fn emit_trailing_comments(&mut self, comments: &[Comment]) {
+        let end_line = self.node_ends.last().map(|end| end.line);
+        for comment in comments {
+            let own_line = end_line.is_some_and(|line| comment.start.line > line);
+            self.trailing_comments.push((comment.clone(), own_line));
+        }
+    }
+
+    fn emit_byte_str(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeByteStr,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write(&node.to_string());
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_name_identifier(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeNameIdentifier,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write(&node.to_string());
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_imported_name(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeImportedName,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeImportedName::RegularImport(name) => {
+                    name.visit(self)?;
+                }
+                NodeImportedName::AliasedImport(name, alias) => {
+                    name.visit(self)?;
+                    self.write(" as ");
+                    alias.visit(self)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_import_declarations(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeImportDeclarations,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("import");
+            self.write_arguments(&node.import_list)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_meta_identifier(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeMetaIdentifier,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write(&node.to_string());
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_variable_identifier(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeVariableIdentifier,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write(&node.to_string());
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_builtin_arguments(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeBuiltinArguments,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            if node.arguments.is_empty() {
+                self.write("()");
+            } else {
+                self.write_separated(&node.arguments, " ")?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_map_key(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeMapKey,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeTypeMapKey::GenericMapKey(key) => {
+                    key.visit(self)?;
+                }
+                NodeTypeMapKey::EnclosedGenericId(key) => {
+                    self.write("(");
+                    key.visit(self)?;
+                    self.write(")");
+                }
+                NodeTypeMapKey::EnclosedAddressMapKeyType(key) => {
+                    self.write("(");
+                    key.visit(self)?;
+                    self.write(")");
+                }
+                NodeTypeMapKey::AddressMapKeyType(key) => {
+                    key.visit(self)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_map_value(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeMapValue,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeTypeMapValue::MapValueTypeOrEnumLikeIdentifier(value) => {
+                    value.visit(self)?;
+                }
+                NodeTypeMapValue::MapKeyValue(entry) => {
+                    entry.visit(self)?;
+                }
+                NodeTypeMapValue::MapValueParenthesizedType(value) => {
+                    self.write("(");
+                    value.visit(self)?;
+                    self.write(")");
+                }
+                NodeTypeMapValue::MapValueAddressType(value) => {
+                    value.visit(self)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_argument(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeArgument,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeTypeArgument::EnclosedTypeArgument(argument) => {
+                    self.write("(");
+                    argument.visit(self)?;
+                    self.write(")");
+                }
+                NodeTypeArgument::GenericTypeArgument(argument) => {
+                    argument.visit(self)?;
+                }
+                NodeTypeArgument::TemplateTypeArgument(argument) => {
+                    self.write_name(argument);
+                }
+                NodeTypeArgument::AddressTypeArgument(argument) => {
+                    argument.visit(self)?;
+                }
+                NodeTypeArgument::MapTypeArgument(key, value) => {
+                    self.write("Map ");
+                    key.visit(self)?;
+                    self.write(" ");
+                    value.visit(self)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_scilla_type(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeScillaType,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeScillaType::GenericTypeWithArgs(name, arguments) => {
+                    name.visit(self)?;
+                    self.write_arguments(arguments)?;
+                }
+                NodeScillaType::MapType(key, value) => {
+                    self.write("Map ");
+                    key.visit(self)?;
+                    self.write(" ");
+                    value.visit(self)?;
+                }
+                NodeScillaType::FunctionType(from, to) => {
+                    from.visit(self)?;
+                    self.write(" -> ");
+                    to.visit(self)?;
+                }
+                NodeScillaType::EnclosedType(inner) => {
+                    self.write("(");
+                    inner.visit(self)?;
+                    self.write(")");
+                }
+                NodeScillaType::ScillaAddresseType(address) => {
+                    address.visit(self)?;
+                }
+                NodeScillaType::PolyFunctionType(variable, inner) => {
+                    self.write("forall ");
+                    self.write_name(variable);
+                    self.write(". ");
+                    inner.visit(self)?;
+                }
+                NodeScillaType::TypeVarType(variable) => {
+                    self.write_name(variable);
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_map_entry(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeMapEntry,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("Map ");
+            node.key.visit(self)?;
+            self.write(" ");
+            node.value.visit(self)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_address_type_field(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeAddressTypeField,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("field ");
+            node.identifier.visit(self)?;
+            self.write(" : ");
+            node.type_name.visit(self)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_address_type(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeAddressType,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            node.identifier.visit(self)?;
+            self.write(" with ");
+            match node.type_name.node.as_str() {
+                "" => {}
+                "contract" if !node.address_fields.is_empty() => {
+                    self.write("contract ");
+                    self.write_separated(&node.address_fields, ", ")?;
+                    self.write(" ");
+                }
+                kind => self.write(&format!("{kind} ")),
+            }
+            self.write("end");
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_full_expression(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeFullExpression,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Exit = mode {
+            return Ok(TraversalResult::SkipChildren);
+        }
+        match node {
+            NodeFullExpression::LocalVariableDeclaration {
+                identifier_name,
+                expression,
+                type_annotation,
+                containing_expression,
+            } => {
+                self.write("let ");
+                self.write_name(identifier_name);
+                if let Some(type_annotation) = type_annotation {
+                    self.write(" ");
+                    type_annotation.visit(self)?;
+                }
+                self.write_binding(expression)?;
+                if is_multi_line(expression) {
+                    self.new_line();
+                    self.write("in");
+                } else {
+                    self.write(" in");
+                }
+                self.new_line();
+                containing_expression.visit(self)?;
+            }
+            NodeFullExpression::FunctionDeclaration {
+                identier_value,
+                type_annotation,
+                expression,
+            } => {
+                self.write("fun (");
+                self.write_name(identier_value);
+                self.write(" ");
+                type_annotation.visit(self)?;
+                self.write(") =>");
+                self.write_indented(expression.as_ref())?;
+            }
+            NodeFullExpression::FunctionCall {
+                function_name,
+                argument_list,
+            } => {
+                function_name.visit(self)?;
+                self.write_arguments(argument_list)?;
+            }
+            NodeFullExpression::ExpressionAtomic(expression) => {
+                expression.visit(self)?;
+            }
+            NodeFullExpression::ExpressionBuiltin { b, targs, xs } => {
+                self.write("builtin ");
+                self.write_name(b);
+                if let Some(targs) = targs {
+                    self.write(" ");
+                    targs.visit(self)?;
+                }
+                self.write(" ");
+                xs.visit(self)?;
+            }
+            NodeFullExpression::Message(entries) => {
+                self.write("{");
+                self.write_separated(entries, "; ")?;
+                self.write("}");
+            }
+            NodeFullExpression::Match {
+                match_expression,
+                clauses,
+            } => {
+                self.write("match ");
+                match_expression.visit(self)?;
+                self.write(" with");
+                for clause in clauses {
+                    self.new_line();
+                    clause.visit(self)?;
+                }
+                self.new_line();
+                self.write("end");
+            }
+            NodeFullExpression::ConstructorCall {
+                identifier_name,
+                contract_type_arguments,
+                argument_list,
+            } => {
+                identifier_name.visit(self)?;
+                if let Some(contract_type_arguments) = contract_type_arguments {
+                    self.write(" ");
+                    contract_type_arguments.visit(self)?;
+                }
+                self.write_arguments(argument_list)?;
+            }
+            NodeFullExpression::TemplateFunction {
+                identifier_name,
+                expression,
+            } => {
+                self.write("tfun ");
+                self.write_name(identifier_name);
+                self.write(" =>");
+                self.write_indented(expression.as_ref())?;
+            }
+            NodeFullExpression::TApp {
+                identifier_name,
+                type_arguments,
+            } => {
+                self.write("@");
+                identifier_name.visit(self)?;
+                self.write_arguments(type_arguments)?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_message_entry(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeMessageEntry,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeMessageEntry::MessageLiteral(key, value) => {
+                    key.visit(self)?;
+                    self.write(" : ");
+                    value.visit(self)?;
+                }
+                NodeMessageEntry::MessageVariable(key, value) => {
+                    key.visit(self)?;
+                    self.write(" : ");
+                    value.visit(self)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_pattern_match_expression_clause(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodePatternMatchExpressionClause,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("| ");
+            node.pattern.visit(self)?;
+            self.write(" =>");
+            if is_multi_line(&node.expression) {
+                self.write_indented(&node.expression)?;
+            } else {
+                self.write(" ");
+                node.expression.visit(self)?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_atomic_expression(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeAtomicExpression,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeAtomicExpression::AtomicSid(identifier) => identifier.visit(self)?,
+                NodeAtomicExpression::AtomicLit(literal) => literal.visit(self)?,
+            };
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_contract_type_arguments(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeContractTypeArguments,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("{");
+            self.write_separated(&node.type_arguments, " ")?;
+            self.write("}");
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_value_literal(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeValueLiteral,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeValueLiteral::LiteralInt(type_name, value) => {
+                    type_name.visit(self)?;
+                    self.write(" ");
+                    self.write_name(value);
+                }
+                NodeValueLiteral::LiteralHex(value) | NodeValueLiteral::LiteralString(value) => {
+                    self.write_name(value);
+                }
+                NodeValueLiteral::LiteralEmptyMap(key, value) => {
+                    self.write("Emp ");
+                    key.visit(self)?;
+                    self.write(" ");
+                    value.visit(self)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_map_access(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeMapAccess,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("[");
+            node.identifier_name.visit(self)?;
+            self.write("]");
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_pattern(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodePattern,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodePattern::Wildcard => self.write("_"),
+                NodePattern::Binder(name) => self.write_name(name),
+                NodePattern::Constructor(name, arguments) => {
+                    name.visit(self)?;
+                    self.write_arguments(arguments)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_argument_pattern(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeArgumentPattern,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeArgumentPattern::WildcardArgument => self.write("_"),
+                NodeArgumentPattern::BinderArgument(name) => self.write_name(name),
+                NodeArgumentPattern::ConstructorArgument(name) => {
+                    name.visit(self)?;
+                }
+                NodeArgumentPattern::PatternArgument(pattern) => {
+                    self.write("(");
+                    pattern.visit(self)?;
+                    self.write(")");
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_pattern_match_clause(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodePatternMatchClause,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("| ");
+            node.pattern_expression.visit(self)?;
+            self.write(" =>");
+            if let Some(statement_block) = &node.statement_block {
+                self.write_indented(statement_block)?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_blockchain_fetch_arguments(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeBlockchainFetchArguments,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("(");
+            self.write_separated(&node.arguments, " ")?;
+            self.write(")");
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_statement(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeStatement,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Exit = mode {
+            return Ok(TraversalResult::SkipChildren);
+        }
+        match node {
+            NodeStatement::MapUpdateDelete {
+                left_hand_side: WithMetaData { trivia, .. },
+                ..
+            }
+            | NodeStatement::Send {
+                identifier_name: WithMetaData { trivia, .. },
+            }
+            | NodeStatement::CreateEvnt {
+                identifier_name: WithMetaData { trivia, .. },
+            }
+            | NodeStatement::Throw {
+                error_variable: Some(WithMetaData { trivia, .. }),
+            }
+            | NodeStatement::MatchStmt {
+                variable: WithMetaData { trivia, .. },
+                ..
+            }
+            | NodeStatement::Iterate {
+                identifier_name: WithMetaData { trivia, .. },
+                ..
+            } => self.hoist_leading_comments(trivia),
+            _ => {}
+        }
+        match node {
+            NodeStatement::Load {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                self.write_name(left_hand_side);
+                self.write(" <- ");
+                right_hand_side.visit(self)?;
+            }
+            NodeStatement::RemoteFetch(statement) => {
+                statement.visit(self)?;
+            }
+            NodeStatement::Store {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                self.write_name(left_hand_side);
+                self.write(" := ");
+                right_hand_side.visit(self)?;
+            }
+            NodeStatement::Bind {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                self.write_name(left_hand_side);
+                self.write_binding(right_hand_side)?;
+            }
+            NodeStatement::ReadFromBC {
+                left_hand_side,
+                type_name,
+                arguments,
+            } => {
+                self.write_name(left_hand_side);
+                self.write(" <- & ");
+                type_name.visit(self)?;
+                if let Some(arguments) = arguments {
+                    self.write(" ");
+                    arguments.visit(self)?;
+                }
+            }
+            NodeStatement::MapGet {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                self.write_name(left_hand_side);
+                self.write(" <- ");
+                self.write_name(right_hand_side);
+                self.write_keys(keys)?;
+            }
+            NodeStatement::MapGetExists {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                self.write_name(left_hand_side);
+                self.write(" <- exists ");
+                self.write_name(right_hand_side);
+                self.write_keys(keys)?;
+            }
+            NodeStatement::MapUpdate {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                self.write_name(left_hand_side);
+                self.write_keys(keys)?;
+                self.write(" := ");
+                right_hand_side.visit(self)?;
+            }
+            NodeStatement::MapUpdateDelete {
+                left_hand_side,
+                keys,
+            } => {
+                self.write("delete ");
+                self.write_name(left_hand_side);
+                self.write_keys(keys)?;
+            }
+            NodeStatement::Accept => self.write("accept"),
+            NodeStatement::Send { identifier_name } => {
+                self.write("send ");
+                identifier_name.visit(self)?;
+            }
+            NodeStatement::CreateEvnt { identifier_name } => {
+                self.write("event ");
+                identifier_name.visit(self)?;
+            }
+            NodeStatement::Throw { error_variable } => {
+                self.write("throw");
+                if let Some(error_variable) = error_variable {
+                    self.write(" ");
+                    error_variable.visit(self)?;
+                }
+            }
+            NodeStatement::MatchStmt { variable, clauses } => {
+                self.write("match ");
+                variable.visit(self)?;
+                self.write(" with");
+                for clause in clauses {
+                    self.new_line();
+                    clause.visit(self)?;
+                }
+                self.new_line();
+                self.write("end");
+            }
+            NodeStatement::CallProc {
+                component_id,
+                arguments,
+            } => {
+                component_id.visit(self)?;
+                self.write_arguments(arguments)?;
+            }
+            NodeStatement::Iterate {
+                identifier_name,
+                component_id,
+            } => {
+                self.write("forall ");
+                identifier_name.visit(self)?;
+                self.write(" ");
+                component_id.visit(self)?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_remote_fetch_statement(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeRemoteFetchStatement,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Exit = mode {
+            return Ok(TraversalResult::SkipChildren);
+        }
+        match node {
+            NodeRemoteFetchStatement::ReadStateMutable(left_hand_side, address, field) => {
+                self.write_name(left_hand_side);
+                self.write(" <- & ");
+                self.write_name(address);
+                self.write(".");
+                field.visit(self)?;
+            }
+            NodeRemoteFetchStatement::ReadStateMutableSpecialId(left_hand_side, address, field) => {
+                self.write_name(left_hand_side);
+                self.write(" <- & ");
+                self.write_name(address);
+                self.write(".");
+                self.write_name(field);
+            }
+            NodeRemoteFetchStatement::ReadStateMutableMapAccess(
+                left_hand_side,
+                address,
+                field,
+                keys,
+            ) => {
+                self.write_name(left_hand_side);
+                self.write(" <- & ");
+                self.write_name(address);
+                self.write(".");
+                self.write_name(field);
+                self.write_keys(keys)?;
+            }
+            NodeRemoteFetchStatement::ReadStateMutableMapAccessExists(
+                left_hand_side,
+                address,
+                field,
+                keys,
+            ) => {
+                self.write_name(left_hand_side);
+                self.write(" <- & exists ");
+                self.write_name(address);
+                self.write(".");
+                self.write_name(field);
+                self.write_keys(keys)?;
+            }
+            NodeRemoteFetchStatement::ReadStateMutableCastAddress(
+                left_hand_side,
+                address,
+                address_type,
+            ) => {
+                self.write_name(left_hand_side);
+                self.write(" <- & ");
+                address.visit(self)?;
+                self.write(" as ");
+                address_type.visit(self)?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_component_id(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeComponentId,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeComponentId::WithTypeLikeName(name) => {
+                    name.visit(self)?;
+                }
+                NodeComponentId::WithRegularId(name) => self.write_name(name),
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_component_parameters(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeComponentParameters,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("(");
+            self.write_separated(&node.parameters, ", ")?;
+            self.write(")");
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_parameter_pair(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeParameterPair,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            node.identifier_with_type.visit(self)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_component_body(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeComponentBody,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            if let Some(statement_block) = &node.statement_block {
+                self.write_indented(statement_block)?;
+            }
+            self.new_line();
+            self.write("end");
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_statement_block(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeStatementBlock,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            for (i, statement) in node.statements.iter().enumerate() {
+                if i > 0 {
+                    self.write(";");
+                    self.new_line();
+                }
+                statement.visit(self)?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_typed_identifier(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypedIdentifier,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write_name(&node.identifier_name);
+            self.write(" ");
+            node.annotation.visit(self)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_annotation(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeAnnotation,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write(": ");
+            node.type_name.visit(self)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_program(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeProgram,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            let version = &node.version;
+            self.emit_leading_comments(&version.trivia.leading);
+            self.write(&format!("scilla_version {}", version.node));
+            self.push_source_position(&version.start, &version.end);
+            self.emit_trailing_comments(&version.trivia.trailing);
+            self.pop_source_position();
+            if let Some(import_declarations) = &node.import_declarations {
+                self.blank_line();
+                import_declarations.visit(self)?;
+            }
+            if let Some(library_definition) = &node.library_definition {
+                self.blank_line();
+                library_definition.visit(self)?;
+            }
+            self.blank_line();
+            node.contract_definition.visit(self)?;
+            self.new_line();
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_library_definition(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeLibraryDefinition,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("library ");
+            node.name.visit(self)?;
+            for definition in &node.definitions {
+                self.blank_line();
+                definition.visit(self)?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_library_single_definition(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeLibrarySingleDefinition,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Exit = mode {
+            return Ok(TraversalResult::SkipChildren);
+        }
+        match node {
+            NodeLibrarySingleDefinition::LetDefinition {
+                variable_name,
+                type_annotation,
+                expression,
+            } => {
+                self.write("let ");
+                self.write_name(variable_name);
+                if let Some(type_annotation) = type_annotation {
+                    self.write(" ");
+                    type_annotation.visit(self)?;
+                }
+                self.write_binding(expression)?;
+            }
+            NodeLibrarySingleDefinition::TypeDefinition(name, clauses) => {
+                self.write("type ");
+                name.visit(self)?;
+                if let Some(clauses) = clauses {
+                    self.write(" =");
+                    self.indent += 1;
+                    for clause in clauses {
+                        self.new_line();
+                        clause.visit(self)?;
+                    }
+                    self.indent -= 1;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_contract_definition(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeContractDefinition,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("contract ");
+            node.contract_name.visit(self)?;
+            self.new_line();
+            node.parameters.visit(self)?;
+            if let Some(constraint) = &node.constraint {
+                self.new_line();
+                constraint.visit(self)?;
+            }
+            for field in &node.fields {
+                self.blank_line();
+                field.visit(self)?;
+            }
+            for component in &node.components {
+                self.blank_line();
+                component.visit(self)?;
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_contract_field(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeContractField,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("field ");
+            node.typed_identifier.visit(self)?;
+            self.write_binding(&node.right_hand_side)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_with_constraint(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeWithConstraint,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("with");
+            self.write_indented(node.expression.as_ref())?;
+            self.new_line();
+            self.write("=>");
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_component_definition(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeComponentDefinition,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeComponentDefinition::TransitionComponent(transition) => {
+                    transition.visit(self)?
+                }
+                NodeComponentDefinition::ProcedureComponent(procedure) => procedure.visit(self)?,
+            };
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_procedure_definition(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeProcedureDefinition,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("procedure ");
+            node.name.visit(self)?;
+            self.write(" ");
+            node.parameters.visit(self)?;
+            node.body.visit(self)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_transition_definition(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTransitionDefinition,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("transition ");
+            node.name.visit(self)?;
+            self.write(" ");
+            node.parameters.visit(self)?;
+            node.body.visit(self)?;
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_alternative_clause(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeAlternativeClause,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            self.write("| ");
+            match node {
+                NodeTypeAlternativeClause::ClauseType(name) => {
+                    name.visit(self)?;
+                }
+                NodeTypeAlternativeClause::ClauseTypeWithArgs(name, arguments) => {
+                    name.visit(self)?;
+                    self.write(" of");
+                    self.write_arguments(arguments)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_map_value_arguments(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeMapValueArguments,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeTypeMapValueArguments::EnclosedTypeMapValue(value) => {
+                    self.write("(");
+                    value.visit(self)?;
+                    self.write(")");
+                }
+                NodeTypeMapValueArguments::GenericMapValueArgument(value) => {
+                    value.visit(self)?;
+                }
+                NodeTypeMapValueArguments::MapKeyValueType(key, value) => {
+                    self.write("Map ");
+                    key.visit(self)?;
+                    self.write(" ");
+                    value.visit(self)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+
+    fn emit_type_map_value_allowing_type_arguments(
+        &mut self,
+        mode: TreeTraversalMode,
+        node: &NodeTypeMapValueAllowingTypeArguments,
+    ) -> Result<TraversalResult, String> {
+        if let TreeTraversalMode::Enter = mode {
+            match node {
+                NodeTypeMapValueAllowingTypeArguments::TypeMapValueNoArgs(value) => {
+                    value.visit(self)?;
+                }
+                NodeTypeMapValueAllowingTypeArguments::TypeMapValueWithArgs(name, arguments) => {
+                    name.visit(self)?;
+                    self.write_arguments(arguments)?;
+                }
+            }
+        }
+        Ok(TraversalResult::SkipChildren)
+    }
+}
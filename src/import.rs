@@ -0,0 +1,26 @@
+/// A library imported by a contract, e.g. `import ListUtils` or `import IntUtils as IU`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Import {
+    /// Name of the imported library
+    pub name: String,
+    /// The name the library is imported under, if it is imported with `as`.
+    pub alias: Option<String>,
+}
+
+impl Import {
+    /// Creates an import of the library `name`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            alias: None,
+        }
+    }
+
+    /// Creates an import of the library `name` under the name `alias`.
+    pub fn with_alias(name: &str, alias: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            alias: Some(alias.to_string()),
+        }
+    }
+}
@@ -1,14 +1,44 @@
 pub mod ast;
+pub mod builtins;
 pub mod contract;
+#[cfg(feature = "json")]
+pub mod contract_info;
 pub mod error;
 pub mod field;
+pub mod formatter;
+pub mod import;
+pub mod library;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod message;
+pub mod navigation;
 pub mod parser;
+pub mod procedure;
+pub mod project;
 pub mod simplified_representation;
+pub mod symbols;
 pub mod transition;
 pub mod r#type;
+pub mod typecheck;
+#[cfg(feature = "json")]
+pub mod validation;
+#[cfg(feature = "json")]
+pub mod value;
 
 pub use contract::*;
+#[cfg(feature = "json")]
+pub use contract_info::*;
 pub use error::Error;
 pub use field::*;
+pub use import::*;
+pub use library::*;
+pub use message::*;
+pub use navigation::*;
+pub use procedure::*;
+pub use project::*;
 pub use r#type::*;
+pub use symbols::*;
 pub use transition::*;
+pub use typecheck::*;
+#[cfg(feature = "json")]
+pub use value::*;
@@ -0,0 +1,130 @@
+use std::{path::Path, str::FromStr};
+
+use crate::{parser, simplified_representation::emitter::SrEmitter, Error, Import, Type};
+
+/// The `LibraryModule` struct represents a parsed library module, the content of a `.scillib`
+/// file: a library without a contract.
+#[derive(Debug, PartialEq, Default)]
+pub struct LibraryModule {
+    /// The `scilla_version` the library is written for.
+    pub scilla_version: u32,
+    /// Libraries imported by the library.
+    pub imports: Vec<Import>,
+    /// The library itself.
+    pub library: Library,
+}
+
+impl FromStr for LibraryModule {
+    type Err = Error;
+
+    /// Parse a library module from a string slice
+    /// # Example
+    /// ```
+    /// use scilla_parser::{Constructor, LetDefinition, LibraryModule, TypeDefinition, Type};
+    /// let module: LibraryModule = r#"scilla_version 0
+    ///     library Flags
+    ///     type Flag = | On | Off
+    ///     let enabled : Bool = True"#
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(module.library.name, "Flags");
+    /// assert_eq!(
+    ///     module.library.type_definitions,
+    ///     vec![TypeDefinition::new(
+    ///         "Flag",
+    ///         vec![Constructor::new("On", vec![]), Constructor::new("Off", vec![])]
+    ///     )]
+    /// );
+    /// assert_eq!(
+    ///     module.library.let_definitions,
+    ///     vec![LetDefinition::new("enabled", Some(Type::Bool))]
+    /// );
+    /// ```
+    fn from_str(module: &str) -> Result<Self, Self::Err> {
+        let (parsed, errors) = parser::parse_library_module(module);
+        let parsed = match parsed {
+            Some(parsed) if errors.is_empty() => parsed,
+            _ => return Err(Error::ParseError(errors)),
+        };
+
+        let emitter = SrEmitter::default();
+        emitter
+            .emit_library_module(&parsed)
+            .map_err(Error::AstVisitError)
+    }
+}
+
+impl LibraryModule {
+    /// Parse a library module from a given path.
+    pub fn parse(module_path: &Path) -> Result<Self, Error> {
+        std::fs::read_to_string(module_path)?.parse()
+    }
+}
+
+/// The `Library` struct represents the `library` block of a contract, including its name,
+/// let definitions and user-defined algebraic data types.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct Library {
+    /// Name of the library
+    pub name: String,
+    /// List of the library's let definitions, in declaration order.
+    pub let_definitions: Vec<LetDefinition>,
+    /// List of the library's type definitions, in declaration order.
+    pub type_definitions: Vec<TypeDefinition>,
+}
+
+/// A `let` definition in a library, e.g. `let zero = Uint128 0`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LetDefinition {
+    pub name: String,
+    /// The annotated type, if the definition has one (`let f : Uint128 -> Bool = ...`).
+    pub r#type: Option<Type>,
+}
+
+impl LetDefinition {
+    /// Creates a new let definition.
+    ///
+    /// Arguments:
+    ///
+    /// * `name`: Name of the definition.
+    /// * `r#type`: Annotated type of the definition, if any.
+    pub fn new(name: &str, r#type: Option<Type>) -> Self {
+        Self {
+            name: name.to_string(),
+            r#type,
+        }
+    }
+}
+
+/// A user-defined algebraic data type, e.g. `type Denom = | Zil | Token of ByStr20`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypeDefinition {
+    pub name: String,
+    pub constructors: Vec<Constructor>,
+}
+
+impl TypeDefinition {
+    pub fn new(name: &str, constructors: Vec<Constructor>) -> Self {
+        Self {
+            name: name.to_string(),
+            constructors,
+        }
+    }
+}
+
+/// A constructor of a user-defined algebraic data type together with its argument types.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Constructor {
+    pub name: String,
+    pub arg_types: Vec<Type>,
+}
+
+impl Constructor {
+    pub fn new(name: &str, arg_types: Vec<Type>) -> Self {
+        Self {
+            name: name.to_string(),
+            arg_types,
+        }
+    }
+}
@@ -0,0 +1,484 @@
+//! A language server for Scilla, speaking the Language Server Protocol. The `scilla-lsp` binary
+//! runs it over stdio.
+//!
+//! The server reports parse errors as diagnostics and answers requests for document symbols,
+//! hovers, definitions, references and formatting. Documents are synchronized in full and
+//! resolved on their own: names imported from libraries are not resolved.
+
+use std::collections::HashMap;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{
+        DocumentSymbolRequest, Formatting, GotoDefinition, HoverRequest, References,
+        Request as RequestTrait,
+    },
+    Diagnostic, DiagnosticSeverity, DocumentFormattingParams, DocumentSymbol, DocumentSymbolParams,
+    DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, MarkupContent, MarkupKind, OneOf, Position,
+    PublishDiagnosticsParams, Range, ReferenceParams, ServerCapabilities, SymbolKind,
+    TextDocumentIdentifier, TextDocumentPositionParams, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Uri,
+};
+use serde::Serialize;
+
+use crate::{
+    ast::nodes::*, formatter, parser, parser::ParserError, typecheck::annotation_type, Error, Span,
+    SymbolTable,
+};
+
+/// The capabilities the server announces to its clients.
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..ServerCapabilities::default()
+    }
+}
+
+/// Serves a client until it shuts the server down or goes away.
+pub fn run(connection: &Connection) -> Result<(), Error> {
+    connection.initialize(serde_json::to_value(capabilities())?)?;
+    let mut server = Server::default();
+    for message in &connection.receiver {
+        let replies = match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                vec![Message::Response(server.handle_request(request))]
+            }
+            Message::Notification(notification) => server
+                .handle_notification(notification)
+                .into_iter()
+                .map(Message::Notification)
+                .collect(),
+            Message::Response(_) => vec![],
+        };
+        for reply in replies {
+            connection
+                .sender
+                .send(reply)
+                .map_err(|_| Error::Disconnected)?;
+        }
+    }
+    Ok(())
+}
+
+/// The text of the open documents.
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Uri, String>,
+}
+
+impl Server {
+    fn handle_request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            DocumentSymbolRequest::METHOD => {
+                respond::<DocumentSymbolRequest>(request, |params| self.document_symbols(params))
+            }
+            HoverRequest::METHOD => respond::<HoverRequest>(request, |params| self.hover(params)),
+            GotoDefinition::METHOD => {
+                respond::<GotoDefinition>(request, |params| self.definition(params))
+            }
+            References::METHOD => respond::<References>(request, |params| self.references(params)),
+            Formatting::METHOD => respond::<Formatting>(request, |params| self.format(params)),
+            method => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request {method}"),
+            ),
+        }
+    }
+
+    /// Updates the open documents, returning the diagnostics to publish.
+    fn handle_notification(&mut self, notification: Notification) -> Option<Notification> {
+        let (uri, text) = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)
+                    .ok()?;
+                (params.text_document.uri, params.text_document.text)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidChangeTextDocumentParams>(
+                        DidChangeTextDocument::METHOD,
+                    )
+                    .ok()?;
+                // With full synchronization, the last change holds the whole text.
+                let text = params.content_changes.into_iter().last()?.text;
+                (params.text_document.uri, text)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)
+                    .ok()?;
+                self.documents.remove(&params.text_document.uri);
+                return Some(diagnostics(params.text_document.uri, vec![]));
+            }
+            _ => return None,
+        };
+
+        let errors = parser::parse_program(&text).1;
+        let items = errors
+            .iter()
+            .map(|error| Diagnostic {
+                range: range(&text, error.start.position, error.end.position),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("scilla".to_string()),
+                message: message(error),
+                ..Diagnostic::default()
+            })
+            .collect();
+        self.documents.insert(uri.clone(), text);
+        Some(diagnostics(uri, items))
+    }
+
+    /// Returns the text of a document and the program parsed from it, if it could be parsed.
+    fn program(&self, document: &TextDocumentIdentifier) -> Option<(&str, NodeProgram)> {
+        let text = self.documents.get(&document.uri)?;
+        let program = parser::parse_program(text).0?;
+        Some((text, program))
+    }
+
+    fn document_symbols(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let (text, program) = self.program(&params.text_document)?;
+        let mut symbols = vec![];
+        if let Some(library) = &program.library_definition {
+            symbols.push(library_symbol(text, library));
+        }
+        symbols.push(contract_symbol(text, &program.contract_definition));
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position_params;
+        let (text, program) = self.program(&text_document)?;
+        let table = SymbolTable::resolve(&program);
+        let reference = table.reference_under_cursor(offset(text, position))?;
+        let symbol = &table.symbols[reference.symbol?];
+
+        let mut value = match &symbol.r#type {
+            Some(r#type) => format!(
+                "```scilla\n{} : {type}\n```\n\n{}",
+                symbol.name, symbol.kind
+            ),
+            None => format!("```scilla\n{}\n```\n\n{}", symbol.name, symbol.kind),
+        };
+        if let Some(container) = &symbol.container {
+            value.push_str(&format!(" of `{container}`"));
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(span_range(text, reference.span)),
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position_params;
+        let (text, program) = self.program(&text_document)?;
+        let location = SymbolTable::resolve(&program).definition_at(offset(text, position))?;
+        if location.library.is_some() {
+            return None;
+        }
+        Some(GotoDefinitionResponse::Scalar(lsp_types::Location {
+            uri: text_document.uri,
+            range: span_range(text, location.span),
+        }))
+    }
+
+    fn references(&self, params: ReferenceParams) -> Option<Vec<lsp_types::Location>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position;
+        let (text, program) = self.program(&text_document)?;
+        let spans = SymbolTable::resolve(&program)
+            .references_at(offset(text, position), params.context.include_declaration);
+        let locations = spans
+            .into_iter()
+            .map(|span| lsp_types::Location {
+                uri: text_document.uri.clone(),
+                range: span_range(text, span),
+            })
+            .collect();
+        Some(locations)
+    }
+
+    /// Formats a document that parses without errors.
+    fn format(&self, params: DocumentFormattingParams) -> Option<Vec<TextEdit>> {
+        let text = self.documents.get(&params.text_document.uri)?;
+        let formatted = formatter::format(text).ok()?;
+        if formatted == *text {
+            return Some(vec![]);
+        }
+        Some(vec![TextEdit {
+            range: range(text, 0, text.len()),
+            new_text: formatted,
+        }])
+    }
+}
+
+/// Answers a request with the result of `handler`, or an error if its parameters are invalid.
+fn respond<R: RequestTrait>(
+    request: Request,
+    handler: impl FnOnce(R::Params) -> R::Result,
+) -> Response {
+    match serde_json::from_value::<R::Params>(request.params) {
+        Ok(params) => Response::new_ok(request.id, handler(params)),
+        Err(error) => Response::new_err(
+            request.id,
+            ErrorCode::InvalidParams as i32,
+            error.to_string(),
+        ),
+    }
+}
+
+fn diagnostics(uri: Uri, diagnostics: Vec<Diagnostic>) -> Notification {
+    notification::<PublishDiagnostics>(PublishDiagnosticsParams {
+        uri,
+        diagnostics,
+        version: None,
+    })
+}
+
+fn notification<N: NotificationTrait>(params: N::Params) -> Notification
+where
+    N::Params: Serialize,
+{
+    Notification::new(N::METHOD.to_string(), params)
+}
+
+/// The message of a parse error, without its position.
+fn message(error: &ParserError) -> String {
+    match error.expected.as_slice() {
+        [] => error.message.clone(),
+        [expected] => format!("{}, expected {expected}", error.message),
+        expected => format!("{}, expected one of {}", error.message, expected.join(", ")),
+    }
+}
+
+/// Converts a byte offset of `text` to a position, whose character counts UTF-16 code units as
+/// the protocol requires.
+fn position(text: &str, offset: usize) -> Position {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+/// Converts a position to a byte offset of `text`, clamped to the end of its line.
+fn offset(text: &str, position: Position) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    let line = text[line_start..].split('\n').next().unwrap_or_default();
+    let mut units = 0;
+    for (index, character) in line.char_indices() {
+        if units >= position.character as usize {
+            return line_start + index;
+        }
+        units += character.len_utf16();
+    }
+    line_start + line.len()
+}
+
+fn range(text: &str, start: usize, end: usize) -> Range {
+    Range {
+        start: position(text, start),
+        end: position(text, end),
+    }
+}
+
+fn span_range(text: &str, span: Span) -> Range {
+    range(text, span.start.position, span.end.position)
+}
+
+fn node_range<T>(text: &str, node: &WithMetaData<T>) -> Range {
+    span_range(text, Span::of(node))
+}
+
+#[allow(deprecated)]
+fn document_symbol(
+    name: String,
+    detail: Option<String>,
+    kind: SymbolKind,
+    range: Range,
+    selection_range: Range,
+    children: Vec<DocumentSymbol>,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+/// The library, with its values, types and their constructors.
+fn library_symbol(text: &str, library: &WithMetaData<NodeLibraryDefinition>) -> DocumentSymbol {
+    let children = library
+        .node
+        .definitions
+        .iter()
+        .map(|definition| match &definition.node {
+            NodeLibrarySingleDefinition::LetDefinition {
+                variable_name,
+                type_annotation,
+                expression,
+            } => {
+                let kind = match expression.node {
+                    NodeFullExpression::FunctionDeclaration { .. }
+                    | NodeFullExpression::TemplateFunction { .. } => SymbolKind::FUNCTION,
+                    _ => SymbolKind::CONSTANT,
+                };
+                let detail = type_annotation
+                    .as_ref()
+                    .and_then(|annotation| annotation_type(&annotation.node))
+                    .map(|r#type| r#type.to_string());
+                document_symbol(
+                    variable_name.node.clone(),
+                    detail,
+                    kind,
+                    node_range(text, definition),
+                    node_range(text, variable_name),
+                    vec![],
+                )
+            }
+            NodeLibrarySingleDefinition::TypeDefinition(name, clauses) => {
+                let constructors = clauses
+                    .iter()
+                    .flatten()
+                    .map(|clause| {
+                        let constructor = match &clause.node {
+                            NodeTypeAlternativeClause::ClauseType(constructor)
+                            | NodeTypeAlternativeClause::ClauseTypeWithArgs(constructor, _) => {
+                                constructor
+                            }
+                        };
+                        document_symbol(
+                            constructor.to_string(),
+                            None,
+                            SymbolKind::ENUM_MEMBER,
+                            node_range(text, clause),
+                            node_range(text, constructor),
+                            vec![],
+                        )
+                    })
+                    .collect();
+                document_symbol(
+                    name.to_string(),
+                    None,
+                    SymbolKind::ENUM,
+                    node_range(text, definition),
+                    node_range(text, name),
+                    constructors,
+                )
+            }
+        })
+        .collect();
+    document_symbol(
+        library.node.name.to_string(),
+        None,
+        SymbolKind::NAMESPACE,
+        node_range(text, library),
+        node_range(text, &library.node.name),
+        children,
+    )
+}
+
+/// The contract, with its fields, transitions and procedures.
+fn contract_symbol(text: &str, contract: &WithMetaData<NodeContractDefinition>) -> DocumentSymbol {
+    let mut children: Vec<_> = contract
+        .node
+        .fields
+        .iter()
+        .map(|field| {
+            let typed_identifier = &field.node.typed_identifier.node;
+            document_symbol(
+                typed_identifier.identifier_name.node.clone(),
+                annotation_type(&typed_identifier.annotation.node).map(|t| t.to_string()),
+                SymbolKind::FIELD,
+                node_range(text, field),
+                node_range(text, &typed_identifier.identifier_name),
+                vec![],
+            )
+        })
+        .collect();
+    for component in &contract.node.components {
+        let (name, parameters, kind) = match &component.node {
+            NodeComponentDefinition::TransitionComponent(transition) => (
+                &transition.node.name,
+                &transition.node.parameters,
+                SymbolKind::METHOD,
+            ),
+            NodeComponentDefinition::ProcedureComponent(procedure) => (
+                &procedure.node.name,
+                &procedure.node.parameters,
+                SymbolKind::FUNCTION,
+            ),
+        };
+        children.push(document_symbol(
+            name.to_string(),
+            Some(parameter_list(&parameters.node)),
+            kind,
+            node_range(text, component),
+            node_range(text, name),
+            vec![],
+        ));
+    }
+    document_symbol(
+        contract.node.contract_name.to_string(),
+        Some(parameter_list(&contract.node.parameters.node)),
+        SymbolKind::CLASS,
+        node_range(text, contract),
+        node_range(text, &contract.node.contract_name),
+        children,
+    )
+}
+
+/// The parameters of a contract or component as written in Scilla, e.g. `(to : ByStr20)`.
+fn parameter_list(parameters: &NodeComponentParameters) -> String {
+    let parameters: Vec<_> = parameters
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let identifier = &parameter.node.identifier_with_type.node;
+            match annotation_type(&identifier.annotation.node) {
+                Some(r#type) => format!("{} : {type}", identifier.identifier_name),
+                None => identifier.identifier_name.to_string(),
+            }
+        })
+        .collect();
+    format!("({})", parameters.join(", "))
+}
@@ -0,0 +1,78 @@
+use crate::Type;
+
+/// An entry of a message literal, e.g. `amount: amt` in `{_eventname: "Minted"; amount: amt}`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MessageParam {
+    pub name: String,
+    /// Type of the entry's value, if it can be inferred from the component body.
+    pub r#type: Option<Type>,
+}
+
+impl MessageParam {
+    /// Creates a new message entry.
+    ///
+    /// Arguments:
+    ///
+    /// * `name`: Key of the entry.
+    /// * `r#type`: Type of the entry's value, if known.
+    pub fn new(name: &str, r#type: Option<Type>) -> Self {
+        Self {
+            name: name.to_string(),
+            r#type,
+        }
+    }
+}
+
+/// An event a component may emit, e.g. `{_eventname: "Minted"; amount: amt}`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Event {
+    /// The `_eventname` of the event, or `None` if it is not a string constant.
+    pub name: Option<String>,
+    /// Entries of the event, without `_eventname`.
+    pub params: Vec<MessageParam>,
+}
+
+impl Event {
+    pub fn new(name: &str, params: Vec<MessageParam>) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            params,
+        }
+    }
+}
+
+/// A message a component may send, e.g. `{_tag: "AddFunds"; _recipient: to; _amount: amt}`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Message {
+    /// The `_tag` of the message, or `None` if it is not a string constant.
+    pub tag: Option<String>,
+    /// Entries of the message, without `_tag`. `_recipient` and `_amount` are kept.
+    pub params: Vec<MessageParam>,
+}
+
+impl Message {
+    pub fn new(tag: &str, params: Vec<MessageParam>) -> Self {
+        Self {
+            tag: Some(tag.to_string()),
+            params,
+        }
+    }
+}
+
+/// An exception a component may throw, e.g. `{_exception: "Error"; code: code}`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Exception {
+    /// The `_exception` of the exception, or `None` if it is not a string constant.
+    pub name: Option<String>,
+    /// Entries of the exception, without `_exception`.
+    pub params: Vec<MessageParam>,
+}
+
+impl Exception {
+    pub fn new(name: &str, params: Vec<MessageParam>) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            params,
+        }
+    }
+}
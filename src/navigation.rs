@@ -0,0 +1,62 @@
+//! Go-to-definition and find-references over a resolved program, for editor integrations.
+//!
+//! Positions are byte offsets in the source. A cursor right after a name, as editors place it
+//! once the name is typed, is on that name.
+
+use std::path::Path;
+
+use crate::{Project, Reference, Span, SymbolTable};
+
+/// Where a name is defined or mentioned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The imported library whose file the span is in, `None` for the resolved file itself.
+    pub library: Option<String>,
+    pub span: Span,
+}
+
+impl SymbolTable {
+    /// Returns the occurrence of a name under the cursor at byte offset `position`.
+    pub fn reference_under_cursor(&self, position: usize) -> Option<&Reference> {
+        self.reference_at(position).or_else(|| {
+            let before = position.checked_sub(1)?;
+            self.reference_at(before)
+        })
+    }
+
+    /// Returns where the symbol under the cursor is defined. Builtin symbols, such as `Uint128`
+    /// or `_sender`, have no definition.
+    pub fn definition_at(&self, position: usize) -> Option<Location> {
+        let symbol = &self.symbols[self.reference_under_cursor(position)?.symbol?];
+        Some(Location {
+            library: symbol.library.clone(),
+            span: symbol.definition?,
+        })
+    }
+
+    /// Returns the spans of the occurrences of the symbol under the cursor in the resolved file,
+    /// in source order. Its definition is included if `include_definition` is true and it is
+    /// defined in the file.
+    pub fn references_at(&self, position: usize, include_definition: bool) -> Vec<Span> {
+        let Some(symbol) = self
+            .reference_under_cursor(position)
+            .and_then(|reference| reference.symbol)
+        else {
+            return vec![];
+        };
+        self.references_to(symbol)
+            .filter(|reference| include_definition || !reference.is_definition)
+            .map(|reference| reference.span)
+            .collect()
+    }
+}
+
+impl Project {
+    /// Returns the file a location is in: the file of its library, or the contract.
+    pub fn path_of(&self, location: &Location) -> Option<&Path> {
+        match &location.library {
+            Some(library) => self.library(library).map(|library| library.path.as_path()),
+            None => Some(self.contract_path.as_path()),
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use std::{convert::From, iter::Peekable, str::CharIndices, string::String};
+use std::{convert::From, iter::Peekable, str::CharIndices, string::String, sync::LazyLock};
 
 use regex::Regex;
+use thiserror::Error as ThisError;
 
 pub type Spanned<Tok, Loc, Error> = Result<(Loc, Tok, Loc), Error>;
 
@@ -34,6 +35,19 @@ const KEYWORD_EVENT: &str = "event";
 const KEYWORD_EVENT_TYPE: &str = "Event";
 const KEYWORD_BYSTR: &str = "ByStr";
 
+static BYSTR_WITH_SIZE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^ByStr[0-9]+").unwrap());
+static SIGNED_INTEGER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[+-]?[0-9]+").unwrap());
+static HEX_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^0(x|X)([a-fA-F0-9][a-fA-F0-9])*").unwrap());
+static STRING_LITERAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^"(?:\\.|[^"])*""#).unwrap());
+static REGULAR_ID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[a-z][a-zA-Z0-9_]*").unwrap());
+static TEMPLATE_TYPE_ID: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^['][A-Z][a-zA-Z0-9_]*").unwrap());
+static CUSTOM_TYPE_ID: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Z][a-zA-Z0-9_]*").unwrap());
+static SPECIAL_ID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[_][a-zA-Z0-9_]*").unwrap());
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Token<S> {
     Plus,
@@ -177,9 +191,47 @@ impl<S: ToString> From<Token<S>> for String {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// An error found while tokenizing a source file.
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
 pub enum ParseError {
-    // Not possible
+    #[error("Unterminated string literal")]
+    UnterminatedString { start: SourcePosition },
+
+    #[error("Unterminated comment")]
+    UnterminatedComment { start: SourcePosition },
+
+    /// A hex literal with an odd number of digits or a digit that is not hexadecimal.
+    #[error("Invalid hex literal `{literal}`")]
+    InvalidHex {
+        literal: String,
+        start: SourcePosition,
+        end: SourcePosition,
+    },
+
+    #[error("Invalid character `{character}`")]
+    InvalidChar {
+        character: char,
+        start: SourcePosition,
+    },
+}
+
+impl ParseError {
+    /// Returns the start and end position of the offending input.
+    pub fn span(&self) -> (SourcePosition, SourcePosition) {
+        match self {
+            ParseError::UnterminatedString { start } => {
+                (*start, start.with_end(start.position + 1))
+            }
+            ParseError::UnterminatedComment { start } => {
+                (*start, start.with_end(start.position + 2))
+            }
+            ParseError::InvalidHex { start, end, .. } => (*start, *end),
+            ParseError::InvalidChar { character, start } => (
+                *start,
+                start.with_end(start.position + character.len_utf8()),
+            ),
+        }
+    }
 }
 
 /// Provides the ability to tokenize a source file.
@@ -195,6 +247,9 @@ pub struct Lexer<'input> {
 
     /// The last position the lexer visited
     last_position: usize,
+
+    /// The comments skipped while tokenizing.
+    comments: Vec<Comment>,
 }
 
 impl<'input> Lexer<'input> {
@@ -205,8 +260,26 @@ impl<'input> Lexer<'input> {
             line: 0, // Note: We use machine indices, not human indices
             character: 0,
             last_position: 0,
+            comments: Vec::new(),
         }
     }
+
+    /// The comments skipped so far, in source order.
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Consumes the lexer, returning the comments it skipped.
+    pub fn into_comments(self) -> Vec<Comment> {
+        self.comments
+    }
+
+    /// Moves the position bookkeeping to the start of the line following the newline at `position`.
+    fn new_line(&mut self, position: usize) {
+        self.line += 1;
+        self.character = 0;
+        self.last_position = position + 1;
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Default)]
@@ -242,6 +315,29 @@ impl SourcePosition {
     }
 }
 
+/// A `(* ... *)` comment in the source.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq)]
+pub struct Comment {
+    /// The text between `(*` and `*)`.
+    pub text: String,
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+impl Comment {
+    /// Returns the text of a `(* @doc ... *)` comment without the `@doc` marker.
+    pub fn doc(&self) -> Option<&str> {
+        let doc = self.text.trim().strip_prefix("@doc")?;
+        Some(doc.strip_prefix(':').unwrap_or(doc).trim())
+    }
+}
+
+impl std::fmt::Display for Comment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(*{}*)", self.text)
+    }
+}
+
 impl<'input> Iterator for Lexer<'input> {
     type Item = Spanned<Token<&'input str>, SourcePosition, ParseError>;
 
@@ -249,6 +345,8 @@ impl<'input> Iterator for Lexer<'input> {
 
     fn next(&mut self) -> Option<Self::Item> {
         while let Some((start, ch)) = self.chars.next() {
+            self.character += start - self.last_position;
+            self.last_position = start;
             let source_position = SourcePosition {
                 position: start,
                 line: self.line,
@@ -257,8 +355,6 @@ impl<'input> Iterator for Lexer<'input> {
 
             let (token, end): (Token<&'input str>, SourcePosition) = {
                 let look_ahead = self.chars.peek().map(|(_, next_ch)| *next_ch);
-                self.character += start - self.last_position;
-                self.last_position = start;
 
                 let next_is_alpha_num_under = look_ahead
                     .map(|c| c.is_alphanumeric() || c == '_')
@@ -268,8 +364,7 @@ impl<'input> Iterator for Lexer<'input> {
                 // Handle more complex tokens, whitespace, and comments
                 if ch.is_whitespace() {
                     if ch == '\n' {
-                        self.character = 0;
-                        self.line += 1;
+                        self.new_line(start);
                     }
                     continue;
                 } else if ch == '=' && look_ahead == Some('>') {
@@ -311,23 +406,41 @@ impl<'input> Iterator for Lexer<'input> {
 
                     self.chars.next(); // Consume '*'
                     let mut comment = String::new();
+                    let mut terminated = false;
 
-                    while let Some((_, ch)) = self.chars.next() {
+                    while let Some((position, ch)) = self.chars.next() {
                         if ch == '*' && self.chars.peek().map(|(_, next_ch)| *next_ch) == Some(')')
                         {
                             self.chars.next();
+                            terminated = true;
                             break;
                         } else {
+                            if ch == '\n' {
+                                self.new_line(position);
+                            }
                             comment.push(ch);
                         }
                     }
 
+                    if !terminated {
+                        return Some(Err(ParseError::UnterminatedComment {
+                            start: source_position,
+                        }));
+                    }
+
+                    // Comments are kept aside rather than emitted so the grammar does not have to
+                    // allow them between every pair of tokens.
+                    let end = start + comment.len() + 4; // +4: `(*` and `*)`
+                    self.comments.push(Comment {
+                        text: comment,
+                        start: source_position,
+                        end: SourcePosition {
+                            position: end,
+                            line: self.line,
+                            column: self.character + end - self.last_position,
+                        },
+                    });
                     continue;
-                    // TODO: Hack to avoid emitting comment. However, ideally these should be part of the AST or at least the token stream
-                    // let len = comment.len();
-                    // let end = start + len + 2 + 1; // +2: `*)`, +1: move to char beyond last
-                    // let s = &self.document[start + 2..end - 1]; // +2: skip `(*`
-                    // (Token::Comment(s), end)
                 } else {
                     let (token, end): (Token<&'input str>, SourcePosition) = match ch {
                         '+' => (Token::Plus, source_position.with_end(start + ch.len_utf8())),
@@ -385,8 +498,7 @@ impl<'input> Iterator for Lexer<'input> {
                         _ => {
                             let token_str: &str = &self.document[start..];
                             let mut index = 0;
-                            let token_str_chars = token_str.chars();
-                            for (i, c) in token_str_chars.enumerate() {
+                            for (i, c) in token_str.char_indices() {
                                 if !c.is_alphanumeric() && c != '_' {
                                     index = i;
                                     break;
@@ -602,24 +714,7 @@ impl<'input> Iterator for Lexer<'input> {
                                         )
                                     }
                                     _ => {
-                                        // Handle other cases here
-                                        let bystr_with_size = Regex::new(r"^ByStr[0-9]+").unwrap();
-
-                                        let signed_integer = Regex::new(r"^[+-]?[0-9]+").unwrap();
-                                        let hex_number =
-                                            Regex::new(r"^0(x|X)([a-fA-F0-9][a-fA-F0-9])*")
-                                                .unwrap();
-                                        let string_literal =
-                                            Regex::new(r#"^"(?:\\.|[^"])*""#).unwrap();
-                                        let regular_id =
-                                            Regex::new(r"^[a-z][a-zA-Z0-9_]*").unwrap();
-                                        let template_type_id =
-                                            Regex::new(r"^['][A-Z][a-zA-Z0-9_]*").unwrap();
-                                        let custom_type_id =
-                                            Regex::new(r"^[A-Z][a-zA-Z0-9_]*").unwrap();
-                                        let special_id = Regex::new(r"^[_][a-zA-Z0-9_]*").unwrap();
-
-                                        if let Some(mat) = bystr_with_size.find(token_str) {
+                                        if let Some(mat) = BYSTR_WITH_SIZE.find(token_str) {
                                             let end = start + mat.end();
                                             let s = &self.document[start..end];
                                             if mat.end() > 1 {
@@ -635,7 +730,16 @@ impl<'input> Iterator for Lexer<'input> {
                                                 source_position
                                                     .with_end(start + KEYWORD_BYSTR.len()),
                                             )
-                                        } else if let Some(mat) = hex_number.find(token_str) {
+                                        } else if let Some(mat) = HEX_NUMBER.find(token_str) {
+                                            if mat.end() != keyword_token.len() {
+                                                self.chars.nth(keyword_token.chars().count() - 2);
+                                                return Some(Err(ParseError::InvalidHex {
+                                                    literal: keyword_token.to_string(),
+                                                    start: source_position,
+                                                    end: source_position
+                                                        .with_end(start + keyword_token.len()),
+                                                }));
+                                            }
                                             let end = start + mat.end();
                                             let s = &self.document[start..end];
                                             if mat.end() > 1 {
@@ -644,7 +748,7 @@ impl<'input> Iterator for Lexer<'input> {
                                             }
 
                                             (Token::HexNumber(s), source_position.with_end(end))
-                                        } else if let Some(mat) = signed_integer.find(token_str) {
+                                        } else if let Some(mat) = SIGNED_INTEGER.find(token_str) {
                                             let end = start + mat.end();
                                             let s = &self.document[start..end];
                                             if mat.end() > 1 {
@@ -652,7 +756,7 @@ impl<'input> Iterator for Lexer<'input> {
                                                 // -2, because we already consumed the first char
                                             }
                                             (Token::Number(s), source_position.with_end(end))
-                                        } else if let Some(mat) = string_literal.find(token_str) {
+                                        } else if let Some(mat) = STRING_LITERAL.find(token_str) {
                                             let end = start + mat.end();
                                             let s = &self.document[start..end];
                                             if mat.end() > 1 {
@@ -661,7 +765,7 @@ impl<'input> Iterator for Lexer<'input> {
                                             }
 
                                             (Token::StringLiteral(s), source_position.with_end(end))
-                                        } else if let Some(mat) = regular_id.find(token_str) {
+                                        } else if let Some(mat) = REGULAR_ID.find(token_str) {
                                             let end = start + mat.end();
                                             let s = &self.document[start..end];
                                             if mat.end() > 1 {
@@ -670,7 +774,7 @@ impl<'input> Iterator for Lexer<'input> {
                                             }
 
                                             (Token::Identifier(s), source_position.with_end(end))
-                                        } else if let Some(mat) = template_type_id.find(token_str) {
+                                        } else if let Some(mat) = TEMPLATE_TYPE_ID.find(token_str) {
                                             let end = start + mat.end();
                                             let s = &self.document[start..end];
                                             if mat.end() > 1 {
@@ -682,7 +786,7 @@ impl<'input> Iterator for Lexer<'input> {
                                                 Token::TemplateIdentifier(s),
                                                 source_position.with_end(end),
                                             )
-                                        } else if let Some(mat) = custom_type_id.find(token_str) {
+                                        } else if let Some(mat) = CUSTOM_TYPE_ID.find(token_str) {
                                             let end = start + mat.end();
                                             let s = &self.document[start..end];
                                             if mat.end() > 1 {
@@ -692,7 +796,7 @@ impl<'input> Iterator for Lexer<'input> {
                                                 Token::CustomIdentifier(s),
                                                 source_position.with_end(end),
                                             )
-                                        } else if let Some(mat) = special_id.find(token_str) {
+                                        } else if let Some(mat) = SPECIAL_ID.find(token_str) {
                                             let end = start + mat.end();
                                             let s = &self.document[start..end];
                                             if mat.end() > 1 {
@@ -704,8 +808,17 @@ impl<'input> Iterator for Lexer<'input> {
                                                 Token::SpecialIdentifier(s),
                                                 source_position.with_end(end),
                                             )
+                                        } else if ch == '"' {
+                                            // The string runs to the end of the input.
+                                            self.chars.by_ref().for_each(drop);
+                                            return Some(Err(ParseError::UnterminatedString {
+                                                start: source_position,
+                                            }));
                                         } else {
-                                            (Token::Unknown, source_position.with_end(start))
+                                            return Some(Err(ParseError::InvalidChar {
+                                                character: ch,
+                                                start: source_position,
+                                            }));
                                         }
                                     }
                                 };
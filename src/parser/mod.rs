@@ -1,27 +1,242 @@
-use lalrpop_util::lalrpop_mod;
+use lalrpop_util::{lalrpop_mod, ErrorRecovery};
+
+use crate::{
+    ast::{
+        nodes::{NodeLibraryModule, NodeProgram, NodeScillaType, WithMetaData},
+        trivia::{attach_comments, WalkMetaData},
+    },
+    parser::lexer::{Lexer, SourcePosition, Token},
+};
 
 pub mod lexer;
 lalrpop_mod!(#[allow(clippy::all)]pub parser, "/parser/parser.rs");
 
-#[derive(Debug)]
+/// A syntax error, with the span of the offending token and the tokens the parser expected.
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParserError {
     pub message: String,
-    pub line: usize,
-    pub column: usize,
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+    /// The token that could not be parsed, or `None` at the end of the input.
+    pub unexpected: Option<String>,
+    /// The tokens that would have been accepted at `start`.
+    pub expected: Vec<String>,
+    /// The source line containing `start`, if known. Used to print an excerpt of the source.
+    pub source_line: Option<String>,
+    /// The lexer error, if the input could not be tokenized.
+    pub lexer_error: Option<lexer::ParseError>,
+}
+
+impl ParserError {
+    /// Attaches the line of `source` the error points at, so it can be shown with the error.
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source_line = source.lines().nth(self.start.line).map(str::to_string);
+        self
+    }
+}
+
+impl<'input> From<lalrpop_util::ParseError<SourcePosition, Token<&'input str>, lexer::ParseError>>
+    for ParserError
+{
+    fn from(
+        error: lalrpop_util::ParseError<SourcePosition, Token<&'input str>, lexer::ParseError>,
+    ) -> Self {
+        use lalrpop_util::ParseError;
+
+        let mut lexer_error = None;
+        let (message, start, end, unexpected, expected) = match error {
+            ParseError::InvalidToken { location } => (
+                "Invalid token".to_string(),
+                location,
+                location,
+                None,
+                vec![],
+            ),
+            ParseError::UnrecognizedEof { location, expected } => (
+                "Unexpected end of input".to_string(),
+                location,
+                location,
+                None,
+                expected,
+            ),
+            ParseError::UnrecognizedToken {
+                token: (start, token, end),
+                expected,
+            } => {
+                let token = String::from(token);
+                (
+                    format!("Unexpected token `{token}`"),
+                    start,
+                    end,
+                    Some(token),
+                    expected,
+                )
+            }
+            ParseError::ExtraToken {
+                token: (start, token, end),
+            } => {
+                let token = String::from(token);
+                (
+                    format!("Extra token `{token}`"),
+                    start,
+                    end,
+                    Some(token),
+                    vec![],
+                )
+            }
+            ParseError::User { error } => {
+                let (start, end) = error.span();
+                let message = error.to_string();
+                lexer_error = Some(error);
+                (message, start, end, None, vec![])
+            }
+        };
+
+        Self {
+            message,
+            start,
+            end,
+            unexpected,
+            expected: expected.into_iter().map(terminal_name).collect(),
+            source_line: None,
+            lexer_error,
+        }
+    }
 }
 
-impl std::error::Error for ParserError {
-    fn description(&self) -> &str {
-        &self.message
+/// Names the terminals of the grammar that are regular expressions by the tokens they match,
+/// e.g. `identifier` rather than `r#"[a-z][a-zA-Z0-9_]*"#`. Keywords and punctuation are kept.
+fn terminal_name(terminal: String) -> String {
+    let Some(pattern) = terminal
+        .strip_prefix("r#\"")
+        .and_then(|terminal| terminal.strip_suffix("\"#"))
+    else {
+        return terminal;
+    };
+    let name = match pattern {
+        "[a-z][a-zA-Z0-9_]*" => "identifier",
+        "[A-Z][a-zA-Z0-9_]*" => "type name",
+        "['][A-Z][a-zA-Z0-9_]*" => "type variable",
+        "[_][a-zA-Z0-9_]*" => "special identifier",
+        "[+-]?[0-9]+" => "number",
+        "0(x|X)([a-fA-F0-9][a-fA-F0-9])*" => "hex literal",
+        "ByStr[0-9]+" => "ByStrX type",
+        _ if pattern.starts_with("\\\"") => "string literal",
+        _ => return terminal,
+    };
+    name.to_string()
+}
+
+impl<'input> From<ErrorRecovery<SourcePosition, Token<&'input str>, lexer::ParseError>>
+    for ParserError
+{
+    fn from(error: ErrorRecovery<SourcePosition, Token<&'input str>, lexer::ParseError>) -> Self {
+        error.error.into()
     }
 }
 
+impl std::error::Error for ParserError {}
+
+/// Parses a program, recovering from syntax errors in statements, components and library entries.
+///
+/// Returns the program, without the parts that could not be parsed, along with every error found.
+/// The program is `None` if the parser could not recover. Comments are attached to the program
+/// as `Trivia`.
+pub fn parse_program(source: &str) -> (Option<NodeProgram>, Vec<ParserError>) {
+    parse_with_recovery(source, |recovered, lexer| {
+        parser::ProgramParser::new().parse(recovered, lexer)
+    })
+}
+
+/// Parses a library module, the content of a `.scillib` file, the same way as `parse_program`.
+pub fn parse_library_module(source: &str) -> (Option<NodeLibraryModule>, Vec<ParserError>) {
+    parse_with_recovery(source, |recovered, lexer| {
+        parser::LibraryModuleParser::new().parse(recovered, lexer)
+    })
+}
+
+/// Parses a type on its own, e.g. `Map ByStr20 (List Uint128)`.
+pub fn parse_type(source: &str) -> (Option<WithMetaData<NodeScillaType>>, Vec<ParserError>) {
+    parse_with_recovery(source, |recovered, lexer| {
+        parser::ScillaTypeParser::new().parse(recovered, lexer)
+    })
+}
+
+type Recovered<'input> = Vec<ErrorRecovery<SourcePosition, Token<&'input str>, lexer::ParseError>>;
+
+fn parse_with_recovery<'input, T: WalkMetaData>(
+    source: &'input str,
+    parse: impl FnOnce(
+        &mut Recovered<'input>,
+        &mut Lexer<'input>,
+    ) -> Result<
+        T,
+        lalrpop_util::ParseError<SourcePosition, Token<&'input str>, lexer::ParseError>,
+    >,
+) -> (Option<T>, Vec<ParserError>) {
+    let mut recovered = vec![];
+    let mut lexer = Lexer::new(source);
+    let result = parse(&mut recovered, &mut lexer);
+    let mut errors: Vec<ParserError> = recovered.into_iter().map(ParserError::from).collect();
+    let node = match result {
+        Ok(mut node) => {
+            attach_comments(&mut node, lexer.into_comments());
+            Some(node)
+        }
+        Err(error) => {
+            errors.push(error.into());
+            None
+        }
+    };
+    let errors = errors
+        .into_iter()
+        .map(|error| error.with_source(source))
+        .collect();
+    (node, errors)
+}
+
+/// Prints the error followed by the offending source line with the token underlined:
+///
+/// ```text
+/// Unexpected token `end`, expected one of ";", "|"
+///  --> 12:5
+///    |
+/// 12 |     end
+///    |     ^^^
+/// ```
 impl std::fmt::Display for ParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{} (line {}, column {})",
-            self.message, self.line, self.column
-        )
+        write!(f, "{}", self.message)?;
+        match self.expected.as_slice() {
+            [] => {}
+            [expected] => write!(f, ", expected {expected}")?,
+            expected => write!(f, ", expected one of {}", expected.join(", "))?,
+        }
+
+        let line = (self.start.line + 1).to_string();
+        let gutter = " ".repeat(line.len());
+        write!(f, "\n{gutter}--> {}:{}", line, self.start.column + 1)?;
+
+        if let Some(source_line) = &self.source_line {
+            let underlined = if self.end.line == self.start.line {
+                self.end.position.saturating_sub(self.start.position)
+            } else {
+                source_line.len().saturating_sub(self.start.column)
+            };
+            // Keep tabs so the carets line up with the excerpt.
+            let indent: String = source_line
+                .get(..self.start.column)
+                .unwrap_or_default()
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            write!(
+                f,
+                "\n{gutter} |\n{line} | {source_line}\n{gutter} | {indent}{}",
+                "^".repeat(underlined.max(1))
+            )?;
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,44 @@
+use crate::FieldList;
+
+#[derive(Debug, PartialEq)]
+pub struct Procedure {
+    pub name: String,
+    pub params: FieldList,
+    /// The `(* @doc ... *)` comments above the procedure.
+    pub doc: Option<String>,
+}
+
+impl Procedure {
+    pub fn new(name: &str, params: FieldList) -> Self {
+        Self {
+            name: name.to_string(),
+            params,
+            doc: None,
+        }
+    }
+
+    pub fn new_without_param(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            params: FieldList::default(),
+            doc: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct ProcedureList(pub Vec<Procedure>);
+
+impl std::ops::Deref for ProcedureList {
+    type Target = Vec<Procedure>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for ProcedureList {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ast::nodes::{
+        NodeImportDeclarations, NodeImportedName, NodeLibraryModule, NodeProgram, WithMetaData,
+    },
+    parser, Contract, Error,
+};
+
+/// The `Workspace` struct holds the directories searched for the `.scillib` files imported by
+/// contracts and libraries.
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    /// Directories searched, in order, for `<Name>.scillib` when a library `Name` is imported.
+    pub search_paths: Vec<PathBuf>,
+}
+
+/// A library file imported, directly or indirectly, by the contract of a `Project`.
+#[derive(Debug, PartialEq)]
+pub struct LibraryFile {
+    /// Name of the library, as used in import declarations.
+    pub name: String,
+    /// Path of the `.scillib` file.
+    pub path: PathBuf,
+    /// The parsed library module.
+    pub module: NodeLibraryModule,
+}
+
+/// The `Project` struct represents a contract together with every library it imports.
+#[derive(Debug, PartialEq)]
+pub struct Project {
+    /// Path of the contract.
+    pub contract_path: PathBuf,
+    /// The parsed contract.
+    pub program: NodeProgram,
+    /// The simplified representation of the contract.
+    pub contract: Contract,
+    /// The imported libraries, each one after the libraries it imports.
+    pub libraries: Vec<LibraryFile>,
+}
+
+impl Workspace {
+    /// Creates a workspace searching `search_paths` for libraries.
+    pub fn new(search_paths: Vec<PathBuf>) -> Self {
+        Self { search_paths }
+    }
+
+    /// Returns the path of the file defining library `name`, from the first search path having one.
+    pub fn find_library(&self, name: &str) -> Option<PathBuf> {
+        self.search_paths
+            .iter()
+            .map(|directory| directory.join(format!("{name}.scillib")))
+            .find(|path| path.is_file())
+    }
+
+    /// Parses the contract at `contract_path` along with the libraries it imports, directly or
+    /// indirectly.
+    pub fn load(&self, contract_path: &Path) -> Result<Project, Error> {
+        let source = fs::read_to_string(contract_path)?;
+        let (program, errors) = parser::parse_program(&source);
+        let program = match program {
+            Some(program) if errors.is_empty() => program,
+            _ => return Err(Error::ParseError(errors)),
+        };
+        let contract = Contract::from_program(&program, &source)?;
+
+        let mut loader = Loader {
+            workspace: self,
+            libraries: vec![],
+            importing: vec![],
+        };
+        loader.load_imports(program.import_declarations.as_ref(), contract_path)?;
+
+        Ok(Project {
+            contract_path: contract_path.to_path_buf(),
+            program,
+            contract,
+            libraries: loader.libraries,
+        })
+    }
+}
+
+impl Project {
+    /// Returns the library an import declaration refers to.
+    pub fn resolve_import(&self, import: &NodeImportedName) -> Option<&LibraryFile> {
+        self.library(&library_name(import))
+    }
+
+    /// Returns the library the contract refers to as `name`: the alias the library is imported
+    /// under, or its name if it is imported without one.
+    pub fn resolve(&self, name: &str) -> Option<&LibraryFile> {
+        self.contract
+            .imports
+            .iter()
+            .find(|import| import.alias.as_ref().unwrap_or(&import.name) == name)
+            .and_then(|import| self.library(&import.name))
+    }
+
+    /// Returns the imported library named `name`.
+    pub fn library(&self, name: &str) -> Option<&LibraryFile> {
+        self.libraries.iter().find(|library| library.name == name)
+    }
+}
+
+/// Loads libraries depth first, so that a library is only added once its imports are.
+struct Loader<'a> {
+    workspace: &'a Workspace,
+    libraries: Vec<LibraryFile>,
+    /// The libraries being loaded, each one imported by the one before it.
+    importing: Vec<String>,
+}
+
+impl Loader<'_> {
+    fn load_imports(
+        &mut self,
+        imports: Option<&WithMetaData<NodeImportDeclarations>>,
+        importer: &Path,
+    ) -> Result<(), Error> {
+        let Some(imports) = imports else {
+            return Ok(());
+        };
+        for import in &imports.node.import_list {
+            let name = library_name(&import.node);
+            if let Some(start) = self.importing.iter().position(|library| *library == name) {
+                let mut cycle = self.importing[start..].to_vec();
+                cycle.push(name);
+                return Err(Error::CyclicImport(cycle));
+            }
+            if self.libraries.iter().any(|library| library.name == name) {
+                continue;
+            }
+
+            let path = self
+                .workspace
+                .find_library(&name)
+                .ok_or_else(|| Error::MissingImport {
+                    name: name.clone(),
+                    importer: importer.to_path_buf(),
+                })?;
+            let source = fs::read_to_string(&path)?;
+            let (module, errors) = parser::parse_library_module(&source);
+            let module = match module {
+                Some(module) if errors.is_empty() => module,
+                _ => return Err(Error::LibraryParseError { path, errors }),
+            };
+            let found = module.library_definition.node.name.to_string();
+            if found != name {
+                return Err(Error::LibraryNameMismatch { name, found, path });
+            }
+
+            self.importing.push(name.clone());
+            self.load_imports(module.import_declarations.as_ref(), &path)?;
+            self.importing.pop();
+
+            self.libraries.push(LibraryFile { name, path, module });
+        }
+        Ok(())
+    }
+}
+
+/// The name of the imported library, whether or not it is imported under an alias.
+fn library_name(import: &NodeImportedName) -> String {
+    match import {
+        NodeImportedName::RegularImport(name) | NodeImportedName::AliasedImport(name, _) => {
+            name.to_string()
+        }
+    }
+}
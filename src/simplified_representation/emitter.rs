@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+
 use crate::{
     ast::{converting::AstConverting, nodes::*, visitor::AstVisitor},
     parser::lexer::SourcePosition,
-    simplified_representation::primitives::*,
-    Contract, Field, FieldList, Transition,
+    simplified_representation::{
+        message_collector::{Binding, CollectedMessages, MessageCollector},
+        primitives::*,
+    },
+    typecheck, Constructor, Contract, Field, FieldList, Import, LetDefinition, Library,
+    LibraryModule, Procedure, Transition, Type, TypeDefinition,
 };
 
 use crate::ast::{TraversalResult, TreeTraversalMode};
@@ -12,6 +18,7 @@ enum StackObject {
     IrIdentifier(SrIdentifier),
     VariableDeclaration(Field),
     TypeDefinition(SrType),
+    Constructor(Constructor),
 }
 
 /// The `SrEmitter` struct is used for bookkeeping during the conversion of a Scilla AST to a simplified representation.
@@ -20,6 +27,14 @@ enum StackObject {
 pub struct SrEmitter {
     stack: Vec<StackObject>,
     contract: Contract,
+    /// Library constants, visible in every component.
+    constants: HashMap<String, Binding>,
+    /// Events, messages and exceptions of each procedure declared so far.
+    procedure_messages: HashMap<String, CollectedMessages>,
+    /// The `@doc` comments of the field or component being converted.
+    doc: Option<String>,
+    /// Types of the message entries, worked out by the type checker.
+    entry_types: HashMap<usize, Type>,
 }
 
 impl SrEmitter {
@@ -73,10 +88,154 @@ impl SrEmitter {
 
         Ok(ret)
     }
+
+    /// Pops a type definition and converts it into a `Type`.
+    fn pop_type(&mut self) -> Result<Type, String> {
+        Type::try_from(self.pop_type_definition()?).map_err(|e| e.to_string())
+    }
+
+    fn pop_constructor(&mut self) -> Result<Constructor, String> {
+        let ret = if let Some(candidate) = self.stack.pop() {
+            match candidate {
+                StackObject::Constructor(n) => n,
+                _ => {
+                    return Err(format!("Expected constructor, but found {:?}.", candidate));
+                }
+            }
+        } else {
+            return Err("Expected constructor, but found nothing.".to_string());
+        };
+
+        Ok(ret)
+    }
+
+    /// Collects the events, messages and exceptions of a component with the given parameters.
+    fn collect_messages(&self, params: &[Field], body: &NodeComponentBody) -> CollectedMessages {
+        let mut scope = self.constants.clone();
+        for param in self.contract.init_params.iter().chain(params) {
+            scope.insert(param.name.clone(), Binding::with_type(param.r#type.clone()));
+        }
+        let type_definitions = self
+            .contract
+            .library
+            .as_ref()
+            .map_or(&[][..], |library| &library.type_definitions[..]);
+
+        MessageCollector::new(
+            scope,
+            &self.contract.fields,
+            type_definitions,
+            &self.procedure_messages,
+            &self.entry_types,
+        )
+        .collect(body)
+    }
+
+    /// Emits the version and imports shared by programs and library modules.
+    fn emit_header(
+        &mut self,
+        version: &WithMetaData<String>,
+        import_declarations: Option<&WithMetaData<NodeImportDeclarations>>,
+    ) -> Result<(), String> {
+        self.contract.scilla_version = version
+            .node
+            .parse()
+            .map_err(|_| format!("Invalid Scilla version {}.", version.node))?;
+        if let Some(import_declarations) = import_declarations {
+            import_declarations.visit(self)?;
+        }
+        Ok(())
+    }
+
     pub fn emit(mut self, node: &NodeProgram) -> Result<Contract, String> {
+        self.entry_types = typecheck::message_entry_types(node);
+        self.emit_header(&node.version, node.import_declarations.as_ref())?;
+        if let Some(library) = &node.library_definition {
+            library.visit(&mut self)?;
+        }
         node.contract_definition.visit(&mut self)?;
+        self.resolve_adts();
         Ok(self.contract)
     }
+
+    /// Emits a library module, the content of a `.scillib` file.
+    pub fn emit_library_module(
+        mut self,
+        node: &NodeLibraryModule,
+    ) -> Result<LibraryModule, String> {
+        self.emit_header(&node.version, node.import_declarations.as_ref())?;
+        node.library_definition.visit(&mut self)?;
+        self.resolve_adts();
+        Ok(LibraryModule {
+            scilla_version: self.contract.scilla_version,
+            imports: self.contract.imports,
+            library: self.contract.library.unwrap_or_default(),
+        })
+    }
+
+    /// Emits a type on its own. User-defined ADTs are left without constructors.
+    pub fn emit_type(mut self, node: &WithMetaData<NodeScillaType>) -> Result<Type, String> {
+        node.visit(&mut self)?;
+        self.pop_type()
+    }
+
+    /// Resolves user-defined ADTs used in the contract against the library's type definitions.
+    fn resolve_adts(&mut self) {
+        let Some(library) = self.contract.library.as_mut() else {
+            return;
+        };
+
+        // A type definition can only refer to types declared before it.
+        for i in 0..library.type_definitions.len() {
+            let (declared, rest) = library.type_definitions.split_at_mut(i);
+            for constructor in rest[0].constructors.iter_mut() {
+                for arg_type in constructor.arg_types.iter_mut() {
+                    arg_type.resolve_adts(declared);
+                }
+            }
+        }
+
+        let type_definitions = library.type_definitions.clone();
+        for definition in library.let_definitions.iter_mut() {
+            if let Some(r#type) = definition.r#type.as_mut() {
+                r#type.resolve_adts(&type_definitions);
+            }
+        }
+
+        let field_lists = [&mut self.contract.init_params, &mut self.contract.fields]
+            .into_iter()
+            .chain(self.contract.transitions.iter_mut().map(|t| &mut t.params))
+            .chain(self.contract.procedures.iter_mut().map(|p| &mut p.params));
+        for field_list in field_lists {
+            for field in field_list.0.iter_mut() {
+                field.r#type.resolve_adts(&type_definitions);
+            }
+        }
+
+        for transition in self.contract.transitions.iter_mut() {
+            let params = transition
+                .events
+                .iter_mut()
+                .flat_map(|e| e.params.iter_mut())
+                .chain(
+                    transition
+                        .messages
+                        .iter_mut()
+                        .flat_map(|m| m.params.iter_mut()),
+                )
+                .chain(
+                    transition
+                        .exceptions
+                        .iter_mut()
+                        .flat_map(|e| e.params.iter_mut()),
+                );
+            for param in params {
+                if let Some(r#type) = param.r#type.as_mut() {
+                    r#type.resolve_adts(&type_definitions);
+                }
+            }
+        }
+    }
 }
 
 impl AstConverting for SrEmitter {
@@ -99,7 +258,10 @@ impl AstConverting for SrEmitter {
         match mode {
             TreeTraversalMode::Enter => match node {
                 NodeTypeNameIdentifier::ByteStringType(bytestr) => {
-                    let symbol = SrIdentifier::new(bytestr.to_string(), SrIdentifierKind::Unknown);
+                    let mut symbol =
+                        SrIdentifier::new(bytestr.to_string(), SrIdentifierKind::Unknown);
+                    // Byte string types are builtin, so they resolve to themselves.
+                    symbol.resolved = Some(symbol.unresolved.clone());
 
                     self.stack.push(StackObject::IrIdentifier(symbol));
                 }
@@ -117,16 +279,23 @@ impl AstConverting for SrEmitter {
     fn emit_imported_name(
         &mut self,
         _mode: TreeTraversalMode,
-        _node: &NodeImportedName,
+        node: &NodeImportedName,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        let import = match node {
+            NodeImportedName::RegularImport(name) => Import::new(&name.to_string()),
+            NodeImportedName::AliasedImport(name, alias) => {
+                Import::with_alias(&name.to_string(), &alias.to_string())
+            }
+        };
+        self.contract.imports.push(import);
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_import_declarations(
         &mut self,
         _mode: TreeTraversalMode,
         _node: &NodeImportDeclarations,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::Continue)
     }
     fn emit_meta_identifier(
         &mut self,
@@ -135,7 +304,8 @@ impl AstConverting for SrEmitter {
     ) -> Result<TraversalResult, String> {
         match node {
             NodeMetaIdentifier::ByteString => {
-                let symbol = SrIdentifier::new("ByStr".to_string(), SrIdentifierKind::Unknown);
+                let mut symbol = SrIdentifier::new("ByStr".to_string(), SrIdentifierKind::Unknown);
+                symbol.resolved = Some(symbol.unresolved.clone());
                 self.stack.push(StackObject::IrIdentifier(symbol));
                 Ok(TraversalResult::SkipChildren)
             }
@@ -154,7 +324,7 @@ impl AstConverting for SrEmitter {
         _mode: TreeTraversalMode,
         _node: &NodeBuiltinArguments,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_type_map_key(
         &mut self,
@@ -162,14 +332,18 @@ impl AstConverting for SrEmitter {
         node: &NodeTypeMapKey,
     ) -> Result<TraversalResult, String> {
         match node {
-            NodeTypeMapKey::GenericMapKey(key) => key.visit(self)?,
-            NodeTypeMapKey::EnclosedGenericId(key) => key.visit(self)?,
-            NodeTypeMapKey::EnclosedAddressMapKeyType(key) => key.visit(self)?,
-            NodeTypeMapKey::AddressMapKeyType(key) => key.visit(self)?,
+            NodeTypeMapKey::GenericMapKey(key) | NodeTypeMapKey::EnclosedGenericId(key) => {
+                key.visit(self)?;
+                let identifier = self.pop_ir_identifier()?;
+                self.stack
+                    .push(StackObject::TypeDefinition(identifier.into()));
+            }
+            // Address types push their own type definition.
+            NodeTypeMapKey::EnclosedAddressMapKeyType(key)
+            | NodeTypeMapKey::AddressMapKeyType(key) => {
+                key.visit(self)?;
+            }
         };
-        let identifier = self.pop_ir_identifier()?;
-        self.stack
-            .push(StackObject::TypeDefinition(identifier.into()));
         Ok(TraversalResult::SkipChildren)
     }
     fn emit_type_map_value(
@@ -224,8 +398,9 @@ impl AstConverting for SrEmitter {
                 self.stack
                     .push(StackObject::TypeDefinition(identifier.into()));
             }
-            NodeTypeArgument::TemplateTypeArgument(_) => {
-                unimplemented!();
+            NodeTypeArgument::TemplateTypeArgument(name) => {
+                let symbol = SrIdentifier::new(name.node.clone(), SrIdentifierKind::TypeName);
+                self.stack.push(StackObject::TypeDefinition(symbol.into()));
             }
             NodeTypeArgument::AddressTypeArgument(n) => {
                 n.visit(self)?;
@@ -278,12 +453,28 @@ impl AstConverting for SrEmitter {
                 };
                 self.stack.push(StackObject::TypeDefinition(map));
             }
-            NodeScillaType::FunctionType(_a, _b) => {
-                unimplemented!()
+            NodeScillaType::FunctionType(from, to) => {
+                let _ = from.visit(self)?;
+                let _ = to.visit(self)?;
+                let to = self.pop_type_definition()?;
+                let from = self.pop_type_definition()?;
+                let function = SrType {
+                    main_type: "->".to_string(),
+                    sub_types: vec![from, to],
+                    address_type: None,
+                };
+                self.stack.push(StackObject::TypeDefinition(function));
             }
-
-            NodeScillaType::PolyFunctionType(_name, _a) => {
-                unimplemented!()
+            NodeScillaType::PolyFunctionType(name, a) => {
+                let _ = (*a).visit(self)?;
+                let body = self.pop_type_definition()?;
+                let variable = SrIdentifier::new(name.node.clone(), SrIdentifierKind::TypeName);
+                let poly = SrType {
+                    main_type: "forall".to_string(),
+                    sub_types: vec![variable.into(), body],
+                    address_type: None,
+                };
+                self.stack.push(StackObject::TypeDefinition(poly));
             }
             NodeScillaType::EnclosedType(a) => {
                 let _ = (*a).visit(self)?;
@@ -291,8 +482,9 @@ impl AstConverting for SrEmitter {
             NodeScillaType::ScillaAddresseType(a) => {
                 let _ = (*a).visit(self)?;
             }
-            NodeScillaType::TypeVarType(_name) => {
-                unimplemented!()
+            NodeScillaType::TypeVarType(name) => {
+                let symbol = SrIdentifier::new(name.node.clone(), SrIdentifierKind::TypeName);
+                self.stack.push(StackObject::TypeDefinition(symbol.into()));
             }
         };
         Ok(TraversalResult::SkipChildren)
@@ -314,8 +506,8 @@ impl AstConverting for SrEmitter {
             TreeTraversalMode::Enter => {
                 if let NodeVariableIdentifier::VariableName(n) = &node.identifier.node {
                     node.type_name.visit(self)?;
-                    let typename = self.pop_type_definition()?;
-                    let s = StackObject::VariableDeclaration(Field::new(&n.node, typename.into()));
+                    let typename = self.pop_type()?;
+                    let s = StackObject::VariableDeclaration(Field::new(&n.node, typename));
                     self.stack.push(s);
                 }
             }
@@ -365,28 +557,28 @@ impl AstConverting for SrEmitter {
         _mode: TreeTraversalMode,
         _node: &NodeMessageEntry,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_pattern_match_expression_clause(
         &mut self,
         _mode: TreeTraversalMode,
         _node: &NodePatternMatchExpressionClause,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_atomic_expression(
         &mut self,
         _mode: TreeTraversalMode,
         _node: &NodeAtomicExpression,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_contract_type_arguments(
         &mut self,
         _mode: TreeTraversalMode,
         _node: &NodeContractTypeArguments,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_value_literal(
         &mut self,
@@ -400,7 +592,7 @@ impl AstConverting for SrEmitter {
         _mode: TreeTraversalMode,
         _node: &NodeMapAccess,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_pattern(
         &mut self,
@@ -414,21 +606,21 @@ impl AstConverting for SrEmitter {
         _mode: TreeTraversalMode,
         _node: &NodeArgumentPattern,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_pattern_match_clause(
         &mut self,
         _mode: TreeTraversalMode,
         _node: &NodePatternMatchClause,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_blockchain_fetch_arguments(
         &mut self,
         _mode: TreeTraversalMode,
         _node: &NodeBlockchainFetchArguments,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
 
     fn emit_statement(
@@ -444,33 +636,25 @@ impl AstConverting for SrEmitter {
         _mode: TreeTraversalMode,
         _node: &NodeRemoteFetchStatement,
     ) -> Result<TraversalResult, String> {
-        unimplemented!();
+        Ok(TraversalResult::SkipChildren)
     }
     fn emit_component_id(
         &mut self,
         _mode: TreeTraversalMode,
         node: &NodeComponentId,
     ) -> Result<TraversalResult, String> {
-        match node {
-            NodeComponentId::WithRegularId(name) => {
-                self.stack.push(StackObject::IrIdentifier(SrIdentifier {
-                    unresolved: name.to_string(),
-                    resolved: None,
-                    type_reference: None,
-                    kind: SrIdentifierKind::ComponentName,
-                    is_definition: false,
-                }));
-            }
-            NodeComponentId::WithTypeLikeName(name) => {
-                self.stack.push(StackObject::IrIdentifier(SrIdentifier {
-                    unresolved: name.to_string(),
-                    resolved: None,
-                    type_reference: None,
-                    kind: SrIdentifierKind::ComponentName,
-                    is_definition: false,
-                }));
-            }
-        }
+        let name = match node {
+            NodeComponentId::WithRegularId(name) => name.to_string(),
+            NodeComponentId::WithTypeLikeName(name) => name.to_string(),
+        };
+        // Components are defined in the contract, qualified the way `SymbolTable` names them.
+        self.stack.push(StackObject::IrIdentifier(SrIdentifier {
+            resolved: Some(format!("{}.{name}", self.contract.name)),
+            unresolved: name,
+            type_reference: None,
+            kind: SrIdentifierKind::ComponentName,
+            is_definition: false,
+        }));
 
         Ok(TraversalResult::SkipChildren)
     }
@@ -525,9 +709,9 @@ impl AstConverting for SrEmitter {
         let name = node.identifier_name.clone();
         let _ = node.annotation.visit(self)?;
 
-        let typename = self.pop_type_definition()?;
+        let typename = self.pop_type()?;
 
-        let s = StackObject::VariableDeclaration(Field::new(&name.node, typename.into()));
+        let s = StackObject::VariableDeclaration(Field::new(&name.node, typename));
         self.stack.push(s);
 
         Ok(TraversalResult::SkipChildren)
@@ -552,17 +736,63 @@ impl AstConverting for SrEmitter {
     fn emit_library_definition(
         &mut self,
         _mode: TreeTraversalMode,
-        _node: &NodeLibraryDefinition,
+        node: &NodeLibraryDefinition,
     ) -> Result<TraversalResult, String> {
-        unimplemented!()
+        self.contract.library = Some(Library {
+            name: node.name.to_string(),
+            ..Library::default()
+        });
+
+        for definition in node.definitions.iter() {
+            let _ = definition.visit(self)?;
+        }
+
+        Ok(TraversalResult::SkipChildren)
     }
 
     fn emit_library_single_definition(
         &mut self,
         _mode: TreeTraversalMode,
-        _node: &NodeLibrarySingleDefinition,
+        node: &NodeLibrarySingleDefinition,
     ) -> Result<TraversalResult, String> {
-        unimplemented!()
+        match node {
+            NodeLibrarySingleDefinition::LetDefinition {
+                variable_name,
+                type_annotation,
+                expression,
+            } => {
+                let r#type = match type_annotation {
+                    Some(annotation) => {
+                        let _ = annotation.visit(self)?;
+                        Some(self.pop_type()?)
+                    }
+                    None => None,
+                };
+                let mut constant = Binding::from_expression(&expression.node);
+                if r#type.is_some() {
+                    constant.r#type.clone_from(&r#type);
+                }
+                self.constants.insert(variable_name.node.clone(), constant);
+
+                let definition = LetDefinition::new(&variable_name.node, r#type);
+                if let Some(library) = self.contract.library.as_mut() {
+                    library.let_definitions.push(definition);
+                }
+            }
+            NodeLibrarySingleDefinition::TypeDefinition(name, clauses) => {
+                let mut constructors = vec![];
+                for clause in clauses.iter().flatten() {
+                    let _ = clause.visit(self)?;
+                    constructors.push(self.pop_constructor()?);
+                }
+                let definition = TypeDefinition::new(&name.to_string(), constructors);
+                if let Some(library) = self.contract.library.as_mut() {
+                    library.type_definitions.push(definition);
+                }
+            }
+        }
+
+        Ok(TraversalResult::SkipChildren)
     }
 
     fn emit_contract_definition(
@@ -580,10 +810,12 @@ impl AstConverting for SrEmitter {
         }
 
         for field in node.fields.iter() {
+            self.doc = field.trivia.doc();
             let _ = field.visit(self)?;
         }
 
         for component in node.components.iter() {
+            self.doc = component.trivia.doc();
             let _ = component.visit(self)?;
         }
 
@@ -597,7 +829,8 @@ impl AstConverting for SrEmitter {
     ) -> Result<TraversalResult, String> {
         let _ = node.typed_identifier.visit(self)?;
 
-        let field = self.pop_variable_declaration()?;
+        let mut field = self.pop_variable_declaration()?;
+        field.doc = self.doc.take();
         let _ = node.right_hand_side.visit(self)?;
 
         self.contract.fields.push(field);
@@ -621,8 +854,39 @@ impl AstConverting for SrEmitter {
     fn emit_procedure_definition(
         &mut self,
         _mode: TreeTraversalMode,
-        _node: &NodeProcedureDefinition,
+        node: &NodeProcedureDefinition,
     ) -> Result<TraversalResult, String> {
+        let _ = node.name.visit(self)?;
+
+        let arguments = node
+            .parameters
+            .node
+            .parameters
+            .iter()
+            .map(|arg| {
+                let _ = arg.visit(self)?;
+                self.pop_variable_declaration()
+            })
+            .collect::<Result<Vec<Field>, _>>()?;
+
+        let mut function_name = self.pop_ir_identifier()?;
+        if function_name.kind != SrIdentifierKind::ComponentName {
+            return Err(format!(
+                "Expected component name, but found {:?}.",
+                function_name
+            ));
+        }
+        function_name.kind = SrIdentifierKind::ProcedureName;
+        function_name.is_definition = true;
+
+        let collected = self.collect_messages(&arguments, &node.body.node);
+        self.procedure_messages
+            .insert(function_name.unresolved.clone(), collected);
+
+        let mut procedure = Procedure::new(&function_name.unresolved, FieldList(arguments));
+        procedure.doc = self.doc.take();
+        self.contract.procedures.push(procedure);
+
         Ok(TraversalResult::SkipChildren)
     }
 
@@ -646,14 +910,22 @@ impl AstConverting for SrEmitter {
             .collect::<Result<Vec<Field>, _>>()?;
 
         let mut function_name = self.pop_ir_identifier()?;
-        assert!(function_name.kind == SrIdentifierKind::ComponentName);
+        if function_name.kind != SrIdentifierKind::ComponentName {
+            return Err(format!(
+                "Expected component name, but found {:?}.",
+                function_name
+            ));
+        }
         function_name.kind = SrIdentifierKind::TransitionName;
         function_name.is_definition = true;
 
-        self.contract.transitions.push(Transition::new(
-            &function_name.unresolved,
-            FieldList(arguments),
-        ));
+        let collected = self.collect_messages(&arguments, &node.body.node);
+        let mut transition = Transition::new(&function_name.unresolved, FieldList(arguments));
+        transition.events = collected.events;
+        transition.messages = collected.messages;
+        transition.exceptions = collected.exceptions;
+        transition.doc = self.doc.take();
+        self.contract.transitions.push(transition);
 
         Ok(TraversalResult::SkipChildren)
     }
@@ -661,8 +933,23 @@ impl AstConverting for SrEmitter {
     fn emit_type_alternative_clause(
         &mut self,
         _mode: TreeTraversalMode,
-        _node: &NodeTypeAlternativeClause,
+        node: &NodeTypeAlternativeClause,
     ) -> Result<TraversalResult, String> {
+        let constructor = match node {
+            NodeTypeAlternativeClause::ClauseType(name) => {
+                Constructor::new(&name.to_string(), vec![])
+            }
+            NodeTypeAlternativeClause::ClauseTypeWithArgs(name, args) => {
+                let mut arg_types = vec![];
+                for arg in args {
+                    let _ = arg.visit(self)?;
+                    arg_types.push(self.pop_type()?);
+                }
+                Constructor::new(&name.to_string(), arg_types)
+            }
+        };
+        self.stack.push(StackObject::Constructor(constructor));
+
         Ok(TraversalResult::SkipChildren)
     }
     fn emit_type_map_value_arguments(
@@ -682,7 +969,18 @@ impl AstConverting for SrEmitter {
                         self.stack
                             .push(StackObject::TypeDefinition(identifier.into()));
                     }
-                    NodeTypeMapValueArguments::MapKeyValueType(_, _) => todo!(),
+                    NodeTypeMapValueArguments::MapKeyValueType(key, value) => {
+                        key.visit(self)?;
+                        value.visit(self)?;
+                        let value = self.pop_type_definition()?;
+                        let key = self.pop_type_definition()?;
+                        let map = SrType {
+                            main_type: "Map".to_string(),
+                            sub_types: vec![key, value],
+                            address_type: None,
+                        };
+                        self.stack.push(StackObject::TypeDefinition(map));
+                    }
                 };
             }
             TreeTraversalMode::Exit => (),
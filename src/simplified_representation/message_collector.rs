@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::nodes::*, simplified_representation::primitives::SrType, Event, Exception, Field, Message,
+    MessageParam, Type, TypeDefinition,
+};
+
+/// What is known about a variable while walking a component body.
+#[derive(Debug, Clone, Default)]
+pub struct Binding {
+    pub r#type: Option<Type>,
+    /// The value of the variable, if it is a string constant.
+    pub string: Option<String>,
+}
+
+impl Binding {
+    pub fn with_type(r#type: Type) -> Self {
+        Self {
+            r#type: Some(r#type),
+            string: None,
+        }
+    }
+
+    /// Creates the binding of a constant expression, e.g. `Uint128 0` or `"AddFunds"`.
+    pub fn from_expression(expression: &NodeFullExpression) -> Self {
+        match expression {
+            NodeFullExpression::ExpressionAtomic(atomic) => match &atomic.node {
+                NodeAtomicExpression::AtomicLit(literal) => Self::from_literal(&literal.node),
+                NodeAtomicExpression::AtomicSid(_) => Self::default(),
+            },
+            _ => Self::default(),
+        }
+    }
+
+    fn from_literal(literal: &NodeValueLiteral) -> Self {
+        match literal {
+            NodeValueLiteral::LiteralInt(type_name, _) => Self {
+                r#type: type_from_name(&type_name.to_string()),
+                string: None,
+            },
+            NodeValueLiteral::LiteralHex(value) => {
+                let size = value.node.len().saturating_sub(2) / 2;
+                Self {
+                    r#type: type_from_name(&format!("ByStr{size}")),
+                    string: None,
+                }
+            }
+            NodeValueLiteral::LiteralString(value) => Self {
+                r#type: Some(Type::String),
+                string: Some(value.node.trim_matches('"').to_string()),
+            },
+            NodeValueLiteral::LiteralEmptyMap(..) => Self::default(),
+        }
+    }
+}
+
+/// The events, messages and exceptions found in a component body.
+#[derive(Debug, Clone, Default)]
+pub struct CollectedMessages {
+    pub events: Vec<Event>,
+    pub messages: Vec<Message>,
+    pub exceptions: Vec<Exception>,
+}
+
+impl CollectedMessages {
+    /// Adds everything in `other` that is not collected yet.
+    pub fn merge(&mut self, other: &CollectedMessages) {
+        push_unique(&mut self.events, &other.events);
+        push_unique(&mut self.messages, &other.messages);
+        push_unique(&mut self.exceptions, &other.exceptions);
+    }
+}
+
+fn push_unique<T: PartialEq + Clone>(target: &mut Vec<T>, items: &[T]) {
+    for item in items {
+        if !target.contains(item) {
+            target.push(item.clone());
+        }
+    }
+}
+
+pub(crate) fn type_from_name(name: &str) -> Option<Type> {
+    SrType {
+        main_type: name.to_string(),
+        sub_types: vec![],
+        address_type: None,
+    }
+    .try_into()
+    .ok()
+}
+
+fn variable_name(variable: &NodeVariableIdentifier) -> Option<&str> {
+    match variable {
+        NodeVariableIdentifier::VariableName(name) => Some(&name.node),
+        NodeVariableIdentifier::SpecialIdentifier(name) => Some(&name.node),
+        NodeVariableIdentifier::VariableInNamespace(..) => None,
+    }
+}
+
+pub(crate) fn component_name(component_id: &NodeComponentId) -> String {
+    match component_id {
+        NodeComponentId::WithRegularId(name) => name.to_string(),
+        NodeComponentId::WithTypeLikeName(name) => name.to_string(),
+    }
+}
+
+fn pattern_binders(pattern: &NodePattern, binders: &mut Vec<String>) {
+    match pattern {
+        NodePattern::Wildcard => {}
+        NodePattern::Binder(name) => binders.push(name.node.clone()),
+        NodePattern::Constructor(_, arguments) => {
+            for argument in arguments {
+                match &argument.node {
+                    NodeArgumentPattern::BinderArgument(name) => binders.push(name.node.clone()),
+                    NodeArgumentPattern::PatternArgument(pattern) => {
+                        pattern_binders(&pattern.node, binders)
+                    }
+                    NodeArgumentPattern::WildcardArgument
+                    | NodeArgumentPattern::ConstructorArgument(_) => {}
+                }
+            }
+        }
+    }
+}
+
+/// Walks the statements of a component body and collects the message literals built in it.
+/// Literals are classified by their `_eventname`, `_tag` or `_exception` entry. Calls to
+/// procedures include whatever was collected for the called procedure.
+pub struct MessageCollector<'a> {
+    scope: HashMap<String, Binding>,
+    fields: &'a [Field],
+    type_definitions: &'a [TypeDefinition],
+    procedures: &'a HashMap<String, CollectedMessages>,
+    entry_types: &'a HashMap<usize, Type>,
+    collected: CollectedMessages,
+}
+
+impl<'a> MessageCollector<'a> {
+    /// Creates a new collector.
+    ///
+    /// Arguments:
+    ///
+    /// * `scope`: Variables visible in the component, i.e. library constants and parameters.
+    /// * `fields`: Mutable fields of the contract.
+    /// * `type_definitions`: User-defined ADTs of the contract library.
+    /// * `procedures`: What was collected for each procedure declared so far.
+    /// * `entry_types`: Types of message entries worked out by the type checker, by the position
+    ///   of the entry. They take precedence over what the collector works out itself.
+    pub fn new(
+        mut scope: HashMap<String, Binding>,
+        fields: &'a [Field],
+        type_definitions: &'a [TypeDefinition],
+        procedures: &'a HashMap<String, CollectedMessages>,
+        entry_types: &'a HashMap<usize, Type>,
+    ) -> Self {
+        for implicit in ["_sender", "_origin", "_this_address"] {
+            scope.insert(implicit.to_string(), Binding::with_type(Type::ByStr20));
+        }
+        scope.insert("_amount".to_string(), Binding::with_type(Type::Uint128));
+        scope.insert(
+            "_creation_block".to_string(),
+            Binding::with_type(Type::BNum),
+        );
+
+        Self {
+            scope,
+            fields,
+            type_definitions,
+            procedures,
+            entry_types,
+            collected: CollectedMessages::default(),
+        }
+    }
+
+    pub fn collect(mut self, body: &NodeComponentBody) -> CollectedMessages {
+        if let Some(block) = &body.statement_block {
+            self.visit_block(&block.node);
+        }
+        self.collected
+    }
+
+    fn lookup(&self, variable: &NodeVariableIdentifier) -> Binding {
+        variable_name(variable)
+            .and_then(|name| self.scope.get(name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn field_type(&self, name: &str) -> Option<&Type> {
+        if name == "_balance" {
+            return Some(&Type::Uint128);
+        }
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| &field.r#type)
+    }
+
+    fn visit_block(&mut self, block: &NodeStatementBlock) {
+        for statement in block.statements.iter() {
+            self.visit_statement(statement);
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &NodeStatement) {
+        match statement {
+            NodeStatement::Load {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                let r#type = variable_name(&right_hand_side.node)
+                    .and_then(|name| self.field_type(name))
+                    .cloned();
+                let binding = Binding {
+                    r#type,
+                    string: None,
+                };
+                self.scope.insert(left_hand_side.node.clone(), binding);
+            }
+            NodeStatement::RemoteFetch(fetch) => {
+                let left_hand_side = match fetch.as_ref() {
+                    NodeRemoteFetchStatement::ReadStateMutable(lhs, ..)
+                    | NodeRemoteFetchStatement::ReadStateMutableSpecialId(lhs, ..)
+                    | NodeRemoteFetchStatement::ReadStateMutableMapAccess(lhs, ..)
+                    | NodeRemoteFetchStatement::ReadStateMutableMapAccessExists(lhs, ..)
+                    | NodeRemoteFetchStatement::ReadStateMutableCastAddress(lhs, ..) => lhs,
+                };
+                self.scope
+                    .insert(left_hand_side.node.clone(), Binding::default());
+            }
+            NodeStatement::Bind {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                let binding = self.visit_expression(&right_hand_side.node);
+                self.scope.insert(left_hand_side.node.clone(), binding);
+            }
+            NodeStatement::ReadFromBC {
+                left_hand_side,
+                type_name,
+                ..
+            } => {
+                let r#type = match type_name.to_string().as_str() {
+                    "BLOCKNUMBER" => Some(Type::BNum),
+                    "CHAINID" => Some(Type::Uint32),
+                    "TIMESTAMP" => Some(Type::Option(Box::new(Type::Uint64))),
+                    _ => None,
+                };
+                let binding = Binding {
+                    r#type,
+                    string: None,
+                };
+                self.scope.insert(left_hand_side.node.clone(), binding);
+            }
+            NodeStatement::MapGet {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                let mut r#type = self.field_type(&right_hand_side.node);
+                for _ in keys {
+                    r#type = match r#type {
+                        Some(Type::Map(_, value)) => Some(value.as_ref()),
+                        _ => None,
+                    };
+                }
+                let binding = Binding {
+                    r#type: r#type.map(|t| Type::Option(Box::new(t.clone()))),
+                    string: None,
+                };
+                self.scope.insert(left_hand_side.node.clone(), binding);
+            }
+            NodeStatement::MapGetExists { left_hand_side, .. } => {
+                self.scope
+                    .insert(left_hand_side.node.clone(), Binding::with_type(Type::Bool));
+            }
+            NodeStatement::MatchStmt { clauses, .. } => {
+                for clause in clauses {
+                    let mut binders = vec![];
+                    pattern_binders(&clause.node.pattern_expression.node, &mut binders);
+                    let scope = self.scope.clone();
+                    for binder in binders {
+                        self.scope.insert(binder, Binding::default());
+                    }
+                    if let Some(block) = &clause.node.statement_block {
+                        self.visit_block(&block.node);
+                    }
+                    self.scope = scope;
+                }
+            }
+            NodeStatement::CallProc { component_id, .. }
+            | NodeStatement::Iterate { component_id, .. } => {
+                if let Some(procedure) = self.procedures.get(&component_name(&component_id.node)) {
+                    self.collected.merge(procedure);
+                }
+            }
+            NodeStatement::Store { .. }
+            | NodeStatement::MapUpdate { .. }
+            | NodeStatement::MapUpdateDelete { .. }
+            | NodeStatement::Accept
+            | NodeStatement::Send { .. }
+            | NodeStatement::CreateEvnt { .. }
+            | NodeStatement::Throw { .. } => {}
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &NodeFullExpression) -> Binding {
+        match expression {
+            NodeFullExpression::LocalVariableDeclaration {
+                identifier_name,
+                expression,
+                containing_expression,
+                ..
+            } => {
+                let binding = self.visit_expression(&expression.node);
+                let shadowed = self.scope.insert(identifier_name.node.clone(), binding);
+                let result = self.visit_expression(&containing_expression.node);
+                match shadowed {
+                    Some(shadowed) => self.scope.insert(identifier_name.node.clone(), shadowed),
+                    None => self.scope.remove(&identifier_name.node),
+                };
+                result
+            }
+            NodeFullExpression::FunctionDeclaration {
+                identier_value,
+                expression,
+                ..
+            } => {
+                let shadowed = self
+                    .scope
+                    .insert(identier_value.node.clone(), Binding::default());
+                let _ = self.visit_expression(&expression.node);
+                match shadowed {
+                    Some(shadowed) => self.scope.insert(identier_value.node.clone(), shadowed),
+                    None => self.scope.remove(&identier_value.node),
+                };
+                Binding::default()
+            }
+            NodeFullExpression::ExpressionAtomic(atomic) => match &atomic.node {
+                NodeAtomicExpression::AtomicSid(variable) => self.lookup(&variable.node),
+                NodeAtomicExpression::AtomicLit(literal) => Binding::from_literal(&literal.node),
+            },
+            NodeFullExpression::ExpressionBuiltin { b, xs, .. } => {
+                let first_argument = xs
+                    .node
+                    .arguments
+                    .first()
+                    .and_then(|argument| self.lookup(&argument.node).r#type);
+                let r#type = match b.node.as_str() {
+                    "eq" | "lt" | "blt" | "contains" => Some(Type::Bool),
+                    "add" | "sub" | "mul" | "div" | "rem" | "concat" => first_argument,
+                    "badd" => Some(Type::BNum),
+                    "to_bystr" => Some(Type::ByStr),
+                    "sha256hash" | "keccak256hash" => Some(Type::ByStrX(32)),
+                    _ => None,
+                };
+                Binding {
+                    r#type,
+                    string: None,
+                }
+            }
+            NodeFullExpression::Message(entries) => {
+                self.visit_message(entries);
+                Binding::default()
+            }
+            NodeFullExpression::Match { clauses, .. } => {
+                for clause in clauses {
+                    let mut binders = vec![];
+                    pattern_binders(&clause.node.pattern.node, &mut binders);
+                    let scope = self.scope.clone();
+                    for binder in binders {
+                        self.scope.insert(binder, Binding::default());
+                    }
+                    let _ = self.visit_expression(&clause.node.expression.node);
+                    self.scope = scope;
+                }
+                Binding::default()
+            }
+            NodeFullExpression::ConstructorCall {
+                identifier_name, ..
+            } => {
+                let name = identifier_name.to_string();
+                let r#type = match name.as_str() {
+                    "True" | "False" => Some(Type::Bool),
+                    _ => self
+                        .type_definitions
+                        .iter()
+                        .find(|definition| {
+                            definition
+                                .constructors
+                                .iter()
+                                .any(|constructor| constructor.name == name)
+                        })
+                        .map(|definition| Type::Adt {
+                            name: definition.name.clone(),
+                            args: vec![],
+                            constructors: definition.constructors.clone(),
+                        }),
+                };
+                Binding {
+                    r#type,
+                    string: None,
+                }
+            }
+            NodeFullExpression::TemplateFunction { expression, .. } => {
+                let _ = self.visit_expression(&expression.node);
+                Binding::default()
+            }
+            NodeFullExpression::FunctionCall { .. } | NodeFullExpression::TApp { .. } => {
+                Binding::default()
+            }
+        }
+    }
+
+    fn visit_message(&mut self, entries: &[WithMetaData<NodeMessageEntry>]) {
+        let mut kind = None;
+        let mut params = vec![];
+        for entry in entries {
+            let (key, binding) = match &entry.node {
+                NodeMessageEntry::MessageLiteral(key, literal) => {
+                    (key.to_string(), Binding::from_literal(&literal.node))
+                }
+                NodeMessageEntry::MessageVariable(key, variable) => {
+                    (key.to_string(), self.lookup(&variable.node))
+                }
+            };
+            match key.as_str() {
+                "_eventname" | "_tag" | "_exception" => kind = Some((key, binding.string)),
+                _ => {
+                    let r#type = self.entry_types.get(&entry.start.position).cloned();
+                    params.push(MessageParam::new(&key, r#type.or(binding.r#type)));
+                }
+            }
+        }
+
+        let collected = &mut self.collected;
+        match kind {
+            Some((key, name)) if key == "_eventname" => {
+                push_unique(&mut collected.events, &[Event { name, params }])
+            }
+            Some((key, tag)) if key == "_tag" => {
+                push_unique(&mut collected.messages, &[Message { tag, params }])
+            }
+            Some((_, name)) => {
+                push_unique(&mut collected.exceptions, &[Exception { name, params }])
+            }
+            None => {}
+        }
+    }
+}
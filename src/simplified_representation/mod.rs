@@ -1,2 +1,3 @@
 pub mod emitter;
+pub mod message_collector;
 pub mod primitives;
@@ -44,6 +44,9 @@ pub struct AddressType {
     pub fields: FieldList,
 }
 
+/// A type, e.g. `Map` with the sub types of its key and value. Function types have the main type
+/// `->` with their argument and result as sub types, and `forall 'A. T` has the main type `forall`
+/// with `'A` and `T` as sub types.
 #[derive(Debug, Clone)]
 pub struct SrType {
     pub main_type: String,
@@ -0,0 +1,1026 @@
+//! Name resolution: every variable, field, procedure, constructor and type name a program
+//! mentions is bound to the symbol it refers to. The resulting `SymbolTable` can be queried by
+//! position in the source.
+//!
+//! Names follow the scoping rules of Scilla: a procedure can only be called by the components
+//! declared after it, fields are only known to statements, and a library imported under an
+//! alias is only known by its alias.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    ast::nodes::*,
+    parser::lexer::SourcePosition,
+    simplified_representation::{
+        message_collector::{component_name, type_from_name},
+        primitives::{SrIdentifier, SrIdentifierKind},
+    },
+    typecheck::{annotation_type, argument_type},
+    Project, Type,
+};
+
+/// What a symbol names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// A parameter of the contract.
+    ContractParameter,
+    Field,
+    Transition,
+    Procedure,
+    /// A parameter of a transition, procedure or function.
+    Parameter,
+    /// A name bound by a statement, a `let ... in` expression or a pattern.
+    Local,
+    /// A `let` definition of a library.
+    LibraryLet,
+    /// A type defined by a library, or a builtin one such as `Option` or `Uint128`.
+    TypeName,
+    Constructor,
+    /// A name provided by the blockchain, e.g. `_sender` or `_balance`.
+    Implicit,
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SymbolKind::ContractParameter => "contract parameter",
+            SymbolKind::Field => "field",
+            SymbolKind::Transition => "transition",
+            SymbolKind::Procedure => "procedure",
+            SymbolKind::Parameter => "parameter",
+            SymbolKind::Local => "local",
+            SymbolKind::LibraryLet => "library value",
+            SymbolKind::TypeName => "type",
+            SymbolKind::Constructor => "constructor",
+            SymbolKind::Implicit => "implicit",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A range of the source, from `start` up to but not including `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+impl Span {
+    pub fn of<T>(node: &WithMetaData<T>) -> Self {
+        Self {
+            start: node.start,
+            end: node.end,
+        }
+    }
+
+    /// Returns true if the byte at `position` is in the span.
+    pub fn contains(&self, position: usize) -> bool {
+        self.start.position <= position && position < self.end.position
+    }
+}
+
+/// Something a name can refer to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The definitions the symbol is nested in, outermost first and separated by dots, e.g.
+    /// `FungibleToken.Transfer` for a parameter of transition `Transfer`. `None` for builtin
+    /// symbols.
+    pub container: Option<String>,
+    /// The library the symbol is imported from, `None` if it is defined in the resolved file.
+    pub library: Option<String>,
+    /// Where the symbol is defined, in the file of `library` if it is imported. `None` for
+    /// builtin symbols.
+    pub definition: Option<Span>,
+    /// The declared type of the symbol, if it has one.
+    pub r#type: Option<Type>,
+}
+
+impl Symbol {
+    /// The name of the symbol qualified with its container, e.g. `FungibleToken.Transfer.to`.
+    pub fn qualified_name(&self) -> String {
+        match &self.container {
+            Some(container) => format!("{container}.{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The kind of identifier the symbol is in the simplified representation.
+    pub fn identifier_kind(&self) -> SrIdentifierKind {
+        match self.kind {
+            SymbolKind::ContractParameter | SymbolKind::Field => SrIdentifierKind::State,
+            SymbolKind::Transition => SrIdentifierKind::TransitionName,
+            SymbolKind::Procedure => SrIdentifierKind::ProcedureName,
+            SymbolKind::Parameter | SymbolKind::Local => SrIdentifierKind::Memory,
+            SymbolKind::LibraryLet => match (&self.r#type, &self.library) {
+                (Some(Type::Function(..) | Type::Forall(..)), Some(_)) => {
+                    SrIdentifierKind::ExternalFunctionName
+                }
+                (Some(Type::Function(..) | Type::Forall(..)), None) => {
+                    SrIdentifierKind::StaticFunctionName
+                }
+                _ => SrIdentifierKind::Memory,
+            },
+            SymbolKind::TypeName | SymbolKind::Constructor => SrIdentifierKind::TypeName,
+            SymbolKind::Implicit if self.name == "_balance" => SrIdentifierKind::State,
+            SymbolKind::Implicit => SrIdentifierKind::ContextResource,
+        }
+    }
+}
+
+/// An occurrence of a name in the source, either where it is defined or where it is used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    /// The name as written, e.g. `Lib.f` for a name qualified with its library.
+    pub name: String,
+    pub span: Span,
+    /// Index of the symbol in `SymbolTable::symbols`, `None` if the name could not be resolved.
+    pub symbol: Option<usize>,
+    pub is_definition: bool,
+}
+
+/// The symbols of a program and every occurrence of their names.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolTable {
+    pub symbols: Vec<Symbol>,
+    /// The occurrences of names, definitions included, in source order.
+    pub references: Vec<Reference>,
+}
+
+impl SymbolTable {
+    /// Resolves the names of a program on its own. The names it imports are left unresolved; use
+    /// `Project::symbols` to resolve them.
+    pub fn resolve(program: &NodeProgram) -> Self {
+        let mut resolver = Resolver::new();
+        resolver.resolve_program(program);
+        resolver.into_table()
+    }
+
+    /// Resolves the names of a library module on its own.
+    pub fn resolve_library(module: &NodeLibraryModule) -> Self {
+        let mut resolver = Resolver::new();
+        resolver.resolve_library(&module.library_definition);
+        resolver.into_table()
+    }
+
+    /// Returns the occurrence of a name at byte offset `position`.
+    pub fn reference_at(&self, position: usize) -> Option<&Reference> {
+        self.references
+            .iter()
+            .find(|reference| reference.span.contains(position))
+    }
+
+    /// Returns the symbol whose name is at byte offset `position`.
+    pub fn symbol_at(&self, position: usize) -> Option<&Symbol> {
+        self.reference_at(position)?
+            .symbol
+            .map(|symbol| &self.symbols[symbol])
+    }
+
+    /// Returns the occurrences of the symbol with index `symbol`, its definition included.
+    pub fn references_to(&self, symbol: usize) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(move |reference| reference.symbol == Some(symbol))
+    }
+
+    /// Returns the occurrences of names that could not be resolved.
+    pub fn unresolved(&self) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(|reference| reference.symbol.is_none())
+    }
+
+    /// Returns the identifier of the simplified representation for an occurrence of a name,
+    /// with the qualified name and type of its symbol if it is resolved.
+    pub fn identifier(&self, reference: &Reference) -> SrIdentifier {
+        let Some(symbol) = reference.symbol.map(|symbol| &self.symbols[symbol]) else {
+            let mut identifier =
+                SrIdentifier::new(reference.name.clone(), SrIdentifierKind::Unknown);
+            identifier.is_definition = reference.is_definition;
+            return identifier;
+        };
+        SrIdentifier {
+            unresolved: reference.name.clone(),
+            resolved: Some(symbol.qualified_name()),
+            type_reference: symbol.r#type.as_ref().map(Type::to_string),
+            kind: symbol.identifier_kind(),
+            is_definition: reference.is_definition,
+        }
+    }
+}
+
+impl Project {
+    /// Resolves the names of the contract, including the ones it imports from its libraries.
+    pub fn symbols(&self) -> SymbolTable {
+        let mut exports: HashMap<String, Exports> = HashMap::new();
+        for library in &self.libraries {
+            let mut resolver = Resolver::new();
+            resolver.import(library.module.import_declarations.as_ref(), &exports);
+            let mut library_exports = resolver.resolve_library(&library.module.library_definition);
+            for symbol in &mut library_exports.symbols {
+                symbol.library = Some(library.name.clone());
+            }
+            exports.insert(library.name.clone(), library_exports);
+        }
+
+        let mut resolver = Resolver::new();
+        resolver.import(self.program.import_declarations.as_ref(), &exports);
+        resolver.resolve_program(&self.program);
+        resolver.into_table()
+    }
+}
+
+/// The symbols a library defines at its top level.
+#[derive(Debug, Clone, Default)]
+struct Exports {
+    symbols: Vec<Symbol>,
+}
+
+/// Names bound to the index of their symbol.
+type Scope = HashMap<String, usize>;
+
+struct Resolver {
+    table: SymbolTable,
+    scopes: Vec<Scope>,
+    /// Values brought in by imports, qualified by the alias of their library if it has one.
+    imported: Scope,
+    constructors: Scope,
+    types: Scope,
+    fields: Scope,
+    /// The procedures declared so far.
+    procedures: Scope,
+    /// The names of the definitions being resolved, outermost first.
+    containers: Vec<String>,
+}
+
+impl Resolver {
+    fn new() -> Self {
+        let mut resolver = Self {
+            table: SymbolTable::default(),
+            scopes: vec![Scope::new()],
+            imported: Scope::new(),
+            constructors: Scope::new(),
+            types: Scope::new(),
+            fields: Scope::new(),
+            procedures: Scope::new(),
+            containers: vec![],
+        };
+        let constructors = [
+            ("True", "Bool"),
+            ("False", "Bool"),
+            ("Some", "forall 'A. 'A -> Option 'A"),
+            ("None", "forall 'A. Option 'A"),
+            ("Cons", "forall 'A. 'A -> List 'A -> List 'A"),
+            ("Nil", "forall 'A. List 'A"),
+            ("Pair", "forall 'A. forall 'B. 'A -> 'B -> Pair 'A 'B"),
+            ("Zero", "Nat"),
+            ("Succ", "Nat -> Nat"),
+        ];
+        for (name, r#type) in constructors {
+            let symbol = resolver.builtin(name, SymbolKind::Constructor, r#type.parse().ok());
+            resolver.constructors.insert(name.to_string(), symbol);
+        }
+        resolver
+    }
+
+    fn into_table(mut self) -> SymbolTable {
+        self.table
+            .references
+            .sort_by_key(|reference| reference.span.start.position);
+        self.table
+    }
+
+    /// Adds a symbol without a definition in the source.
+    fn builtin(&mut self, name: &str, kind: SymbolKind, r#type: Option<Type>) -> usize {
+        self.table.symbols.push(Symbol {
+            name: name.to_string(),
+            kind,
+            container: None,
+            library: None,
+            definition: None,
+            r#type,
+        });
+        self.table.symbols.len() - 1
+    }
+
+    /// Adds the symbol defined by `node`, returning its index.
+    fn define<T: fmt::Display>(
+        &mut self,
+        node: &WithMetaData<T>,
+        kind: SymbolKind,
+        r#type: Option<Type>,
+    ) -> usize {
+        let name = node.to_string();
+        self.table.symbols.push(Symbol {
+            name: name.clone(),
+            kind,
+            container: (!self.containers.is_empty()).then(|| self.containers.join(".")),
+            library: None,
+            definition: Some(Span::of(node)),
+            r#type,
+        });
+        let symbol = self.table.symbols.len() - 1;
+        self.table.references.push(Reference {
+            name,
+            span: Span::of(node),
+            symbol: Some(symbol),
+            is_definition: true,
+        });
+        symbol
+    }
+
+    /// Adds a symbol defined by `node` and binds it in the innermost scope.
+    fn bind<T: fmt::Display>(
+        &mut self,
+        node: &WithMetaData<T>,
+        kind: SymbolKind,
+        r#type: Option<Type>,
+    ) {
+        let symbol = self.define(node, kind, r#type);
+        self.bind_symbol(&node.to_string(), symbol);
+    }
+
+    fn bind_symbol(&mut self, name: &str, symbol: usize) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), symbol);
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(Scope::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Records an occurrence of the name `node` refers to.
+    fn reference<T: fmt::Display>(&mut self, node: &WithMetaData<T>, symbol: Option<usize>) {
+        self.table.references.push(Reference {
+            name: node.to_string(),
+            span: Span::of(node),
+            symbol,
+            is_definition: false,
+        });
+    }
+
+    /// Brings the top level symbols of the imported libraries into scope.
+    fn import(
+        &mut self,
+        imports: Option<&WithMetaData<NodeImportDeclarations>>,
+        exports: &HashMap<String, Exports>,
+    ) {
+        let Some(imports) = imports else {
+            return;
+        };
+        for import in &imports.node.import_list {
+            let (name, prefixes) = match &import.node {
+                NodeImportedName::RegularImport(name) => {
+                    (name.to_string(), vec![String::new(), format!("{name}.")])
+                }
+                NodeImportedName::AliasedImport(name, alias) => {
+                    (name.to_string(), vec![format!("{alias}.")])
+                }
+            };
+            let Some(exports) = exports.get(&name) else {
+                continue;
+            };
+            for symbol in &exports.symbols {
+                self.table.symbols.push(symbol.clone());
+                let index = self.table.symbols.len() - 1;
+                let namespace = match symbol.kind {
+                    SymbolKind::Constructor => &mut self.constructors,
+                    SymbolKind::TypeName => &mut self.types,
+                    _ => &mut self.imported,
+                };
+                for prefix in &prefixes {
+                    namespace.insert(format!("{prefix}{}", symbol.name), index);
+                }
+            }
+        }
+    }
+
+    /// Resolves a variable.
+    fn variable<T: fmt::Display>(&mut self, variable: &WithMetaData<T>) {
+        let name = variable.to_string();
+        let symbol = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name))
+            .or_else(|| self.imported.get(&name))
+            .copied();
+        self.reference(variable, symbol);
+    }
+
+    fn field<T: fmt::Display>(&mut self, field: &WithMetaData<T>) {
+        let symbol = self.fields.get(&field.to_string()).copied();
+        self.reference(field, symbol);
+    }
+
+    fn constructor(&mut self, constructor: &WithMetaData<NodeMetaIdentifier>) {
+        let symbol = self.constructors.get(&constructor.to_string()).copied();
+        self.reference(constructor, symbol);
+    }
+
+    /// Resolves a type name, adding a symbol for the builtin types on their first use.
+    fn type_name<T: fmt::Display>(&mut self, type_name: &WithMetaData<T>) {
+        let name = type_name.to_string();
+        let symbol = match self.types.get(&name) {
+            Some(symbol) => Some(*symbol),
+            None if is_builtin_type(&name) => {
+                let symbol = self.builtin(&name, SymbolKind::TypeName, None);
+                self.types.insert(name, symbol);
+                Some(symbol)
+            }
+            None => None,
+        };
+        self.reference(type_name, symbol);
+    }
+
+    fn resolve_program(&mut self, program: &NodeProgram) {
+        if let Some(library) = &program.library_definition {
+            self.resolve_library(library);
+        }
+        self.resolve_contract(&program.contract_definition.node);
+    }
+
+    /// Resolves the definitions of a library, returning the symbols it defines.
+    fn resolve_library(&mut self, library: &WithMetaData<NodeLibraryDefinition>) -> Exports {
+        let first_symbol = self.table.symbols.len();
+        self.containers.push(library.node.name.to_string());
+        for definition in &library.node.definitions {
+            match &definition.node {
+                NodeLibrarySingleDefinition::LetDefinition {
+                    variable_name,
+                    type_annotation,
+                    expression,
+                } => {
+                    self.containers.push(variable_name.node.clone());
+                    if let Some(annotation) = type_annotation {
+                        self.resolve_type(&annotation.node.type_name);
+                    }
+                    self.resolve_expression(expression);
+                    self.containers.pop();
+                    let r#type = type_annotation
+                        .as_ref()
+                        .and_then(|annotation| annotation_type(&annotation.node));
+                    self.bind(variable_name, SymbolKind::LibraryLet, r#type);
+                }
+                NodeLibrarySingleDefinition::TypeDefinition(name, clauses) => {
+                    let symbol = self.define(name, SymbolKind::TypeName, None);
+                    self.types.insert(name.to_string(), symbol);
+                    for clause in clauses.iter().flatten() {
+                        self.resolve_constructor_definition(&name.to_string(), clause);
+                    }
+                }
+            }
+        }
+        self.containers.pop();
+
+        // Only the top level definitions of the library are visible to its importers.
+        let mut exported: Vec<usize> = vec![];
+        for scope in [&self.scopes[0], &self.types, &self.constructors] {
+            exported.extend(scope.values().filter(|symbol| {
+                **symbol >= first_symbol && self.table.symbols[**symbol].definition.is_some()
+            }));
+        }
+        exported.sort();
+        Exports {
+            symbols: exported
+                .into_iter()
+                .map(|symbol| self.table.symbols[symbol].clone())
+                .collect(),
+        }
+    }
+
+    fn resolve_constructor_definition(
+        &mut self,
+        adt: &str,
+        clause: &WithMetaData<NodeTypeAlternativeClause>,
+    ) {
+        let (constructor, arguments) = match &clause.node {
+            NodeTypeAlternativeClause::ClauseType(constructor) => (constructor, &[][..]),
+            NodeTypeAlternativeClause::ClauseTypeWithArgs(constructor, arguments) => {
+                (constructor, &arguments[..])
+            }
+        };
+        for argument in arguments {
+            self.resolve_type_argument(argument);
+        }
+        // The constructor has the type of a function from its arguments to its ADT.
+        let r#type = arguments.iter().rev().try_fold(
+            Type::Adt {
+                name: adt.to_string(),
+                args: vec![],
+                constructors: vec![],
+            },
+            |result, argument| {
+                argument_type(argument).map(|t| Type::Function(Box::new(t), Box::new(result)))
+            },
+        );
+        let symbol = self.define(constructor, SymbolKind::Constructor, r#type);
+        self.constructors.insert(constructor.to_string(), symbol);
+    }
+
+    fn resolve_contract(&mut self, contract: &NodeContractDefinition) {
+        self.containers.push(contract.contract_name.to_string());
+        self.push_scope();
+        for (name, r#type) in [
+            ("_this_address", Type::ByStr20),
+            ("_creation_block", Type::BNum),
+            ("_scilla_version", Type::Uint32),
+        ] {
+            let symbol = self.builtin(name, SymbolKind::Implicit, Some(r#type));
+            self.bind_symbol(name, symbol);
+        }
+        self.resolve_parameters(&contract.parameters.node, SymbolKind::ContractParameter);
+
+        if let Some(constraint) = &contract.constraint {
+            self.resolve_expression(&constraint.node.expression);
+        }
+
+        let balance = self.builtin("_balance", SymbolKind::Implicit, Some(Type::Uint128));
+        self.fields.insert("_balance".to_string(), balance);
+        for field in &contract.fields {
+            let typed_identifier = &field.node.typed_identifier.node;
+            self.resolve_type(&typed_identifier.annotation.node.type_name);
+            self.resolve_expression(&field.node.right_hand_side);
+            let r#type = annotation_type(&typed_identifier.annotation.node);
+            let symbol = self.define(&typed_identifier.identifier_name, SymbolKind::Field, r#type);
+            self.fields
+                .insert(typed_identifier.identifier_name.node.clone(), symbol);
+        }
+
+        let implicit = [
+            ("_sender", Type::ByStr20),
+            ("_origin", Type::ByStr20),
+            ("_amount", Type::Uint128),
+        ]
+        .map(|(name, r#type)| (name, self.builtin(name, SymbolKind::Implicit, Some(r#type))));
+        for component in &contract.components {
+            let (name, parameters, body, kind) = match &component.node {
+                NodeComponentDefinition::TransitionComponent(transition) => {
+                    let transition = &transition.node;
+                    (
+                        &transition.name,
+                        &transition.parameters,
+                        &transition.body,
+                        SymbolKind::Transition,
+                    )
+                }
+                NodeComponentDefinition::ProcedureComponent(procedure) => {
+                    let procedure = &procedure.node;
+                    (
+                        &procedure.name,
+                        &procedure.parameters,
+                        &procedure.body,
+                        SymbolKind::Procedure,
+                    )
+                }
+            };
+
+            let symbol = self.define(name, kind, None);
+            self.containers.push(component_name(&name.node));
+            self.push_scope();
+            for (name, symbol) in implicit {
+                self.bind_symbol(name, symbol);
+            }
+            self.resolve_parameters(&parameters.node, SymbolKind::Parameter);
+            if let Some(block) = &body.node.statement_block {
+                self.resolve_block(&block.node);
+            }
+            self.pop_scope();
+            self.containers.pop();
+
+            // A procedure can only be called by the components declared after it.
+            if kind == SymbolKind::Procedure {
+                self.procedures.insert(component_name(&name.node), symbol);
+            }
+        }
+        self.pop_scope();
+        self.containers.pop();
+    }
+
+    fn resolve_parameters(&mut self, parameters: &NodeComponentParameters, kind: SymbolKind) {
+        for parameter in &parameters.parameters {
+            let identifier = &parameter.node.identifier_with_type.node;
+            self.resolve_type(&identifier.annotation.node.type_name);
+            let r#type = annotation_type(&identifier.annotation.node);
+            self.bind(&identifier.identifier_name, kind, r#type);
+        }
+    }
+
+    fn resolve_block(&mut self, block: &NodeStatementBlock) {
+        for statement in &block.statements {
+            self.resolve_statement(statement);
+        }
+    }
+
+    fn resolve_map_keys(&mut self, keys: &[WithMetaData<NodeMapAccess>]) {
+        for key in keys {
+            self.variable(&key.node.identifier_name);
+        }
+    }
+
+    fn resolve_statement(&mut self, statement: &NodeStatement) {
+        match statement {
+            NodeStatement::Load {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                self.field(right_hand_side);
+                let r#type = self
+                    .fields
+                    .get(&right_hand_side.to_string())
+                    .and_then(|field| self.table.symbols[*field].r#type.clone());
+                self.bind(left_hand_side, SymbolKind::Local, r#type);
+            }
+            NodeStatement::Store {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                self.variable(right_hand_side);
+                self.field(left_hand_side);
+            }
+            NodeStatement::RemoteFetch(fetch) => self.resolve_remote_fetch(fetch),
+            NodeStatement::Bind {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                self.resolve_expression(right_hand_side);
+                self.bind(left_hand_side, SymbolKind::Local, None);
+            }
+            NodeStatement::ReadFromBC {
+                left_hand_side,
+                arguments,
+                ..
+            } => {
+                for argument in arguments.iter().flat_map(|a| &a.arguments) {
+                    self.variable(argument);
+                }
+                self.bind(left_hand_side, SymbolKind::Local, None);
+            }
+            NodeStatement::MapGet {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            }
+            | NodeStatement::MapGetExists {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                self.field(right_hand_side);
+                self.resolve_map_keys(keys);
+                self.bind(left_hand_side, SymbolKind::Local, None);
+            }
+            NodeStatement::MapUpdate {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                self.field(left_hand_side);
+                self.resolve_map_keys(keys);
+                self.variable(right_hand_side);
+            }
+            NodeStatement::MapUpdateDelete {
+                left_hand_side,
+                keys,
+            } => {
+                self.field(left_hand_side);
+                self.resolve_map_keys(keys);
+            }
+            NodeStatement::Accept => {}
+            NodeStatement::Send { identifier_name }
+            | NodeStatement::CreateEvnt { identifier_name } => self.variable(identifier_name),
+            NodeStatement::Throw { error_variable } => {
+                if let Some(error_variable) = error_variable {
+                    self.variable(error_variable);
+                }
+            }
+            NodeStatement::MatchStmt { variable, clauses } => {
+                self.variable(variable);
+                for clause in clauses {
+                    self.push_scope();
+                    self.resolve_pattern(&clause.node.pattern_expression);
+                    if let Some(block) = &clause.node.statement_block {
+                        self.resolve_block(&block.node);
+                    }
+                    self.pop_scope();
+                }
+            }
+            NodeStatement::CallProc {
+                component_id,
+                arguments,
+            } => {
+                self.procedure(component_id);
+                for argument in arguments {
+                    self.variable(argument);
+                }
+            }
+            NodeStatement::Iterate {
+                identifier_name,
+                component_id,
+            } => {
+                self.variable(identifier_name);
+                self.procedure(component_id);
+            }
+        }
+    }
+
+    fn procedure(&mut self, component_id: &WithMetaData<NodeComponentId>) {
+        let symbol = self
+            .procedures
+            .get(&component_name(&component_id.node))
+            .copied();
+        self.reference(component_id, symbol);
+    }
+
+    fn resolve_remote_fetch(&mut self, fetch: &NodeRemoteFetchStatement) {
+        // The fields read belong to another contract, so only the address is resolved.
+        let left_hand_side = match fetch {
+            NodeRemoteFetchStatement::ReadStateMutable(left_hand_side, address, _)
+            | NodeRemoteFetchStatement::ReadStateMutableSpecialId(left_hand_side, address, _) => {
+                self.variable(address);
+                left_hand_side
+            }
+            NodeRemoteFetchStatement::ReadStateMutableMapAccess(
+                left_hand_side,
+                address,
+                _,
+                keys,
+            )
+            | NodeRemoteFetchStatement::ReadStateMutableMapAccessExists(
+                left_hand_side,
+                address,
+                _,
+                keys,
+            ) => {
+                self.variable(address);
+                self.resolve_map_keys(keys);
+                left_hand_side
+            }
+            NodeRemoteFetchStatement::ReadStateMutableCastAddress(
+                left_hand_side,
+                address,
+                address_type,
+            ) => {
+                self.variable(address);
+                self.resolve_address_type(address_type);
+                left_hand_side
+            }
+        };
+        self.bind(left_hand_side, SymbolKind::Local, None);
+    }
+
+    /// Resolves the constructors of a pattern and binds the names it introduces.
+    fn resolve_pattern(&mut self, pattern: &WithMetaData<NodePattern>) {
+        match &pattern.node {
+            NodePattern::Wildcard => {}
+            NodePattern::Binder(name) => self.bind(name, SymbolKind::Local, None),
+            NodePattern::Constructor(constructor, arguments) => {
+                self.constructor(constructor);
+                for argument in arguments {
+                    match &argument.node {
+                        NodeArgumentPattern::WildcardArgument => {}
+                        NodeArgumentPattern::BinderArgument(name) => {
+                            self.bind(name, SymbolKind::Local, None)
+                        }
+                        NodeArgumentPattern::ConstructorArgument(constructor) => {
+                            self.constructor(constructor)
+                        }
+                        NodeArgumentPattern::PatternArgument(pattern) => {
+                            self.resolve_pattern(pattern)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn resolve_expression(&mut self, expression: &WithMetaData<NodeFullExpression>) {
+        match &expression.node {
+            NodeFullExpression::LocalVariableDeclaration {
+                identifier_name,
+                expression,
+                type_annotation,
+                containing_expression,
+            } => {
+                if let Some(annotation) = type_annotation {
+                    self.resolve_type(&annotation.node.type_name);
+                }
+                self.resolve_expression(expression);
+                let r#type = type_annotation
+                    .as_ref()
+                    .and_then(|annotation| annotation_type(&annotation.node));
+                self.push_scope();
+                self.bind(identifier_name, SymbolKind::Local, r#type);
+                self.resolve_expression(containing_expression);
+                self.pop_scope();
+            }
+            NodeFullExpression::FunctionDeclaration {
+                identier_value,
+                type_annotation,
+                expression,
+            } => {
+                self.resolve_type(&type_annotation.node.type_name);
+                let r#type = annotation_type(&type_annotation.node);
+                self.push_scope();
+                self.bind(identier_value, SymbolKind::Parameter, r#type);
+                self.resolve_expression(expression);
+                self.pop_scope();
+            }
+            NodeFullExpression::FunctionCall {
+                function_name,
+                argument_list,
+            } => {
+                self.variable(function_name);
+                for argument in argument_list {
+                    self.variable(argument);
+                }
+            }
+            NodeFullExpression::ExpressionAtomic(atomic) => match &atomic.node {
+                NodeAtomicExpression::AtomicSid(variable) => self.variable(variable),
+                NodeAtomicExpression::AtomicLit(literal) => self.resolve_literal(&literal.node),
+            },
+            NodeFullExpression::ExpressionBuiltin { targs, xs, .. } => {
+                if let Some(targs) = targs {
+                    for argument in &targs.node.type_arguments {
+                        self.resolve_type_argument(argument);
+                    }
+                }
+                for argument in &xs.node.arguments {
+                    self.variable(argument);
+                }
+            }
+            NodeFullExpression::Message(entries) => {
+                for entry in entries {
+                    match &entry.node {
+                        NodeMessageEntry::MessageLiteral(_, literal) => {
+                            self.resolve_literal(&literal.node)
+                        }
+                        NodeMessageEntry::MessageVariable(_, variable) => self.variable(variable),
+                    }
+                }
+            }
+            NodeFullExpression::Match {
+                match_expression,
+                clauses,
+            } => {
+                self.variable(match_expression);
+                for clause in clauses {
+                    self.push_scope();
+                    self.resolve_pattern(&clause.node.pattern);
+                    self.resolve_expression(&clause.node.expression);
+                    self.pop_scope();
+                }
+            }
+            NodeFullExpression::ConstructorCall {
+                identifier_name,
+                contract_type_arguments,
+                argument_list,
+            } => {
+                self.constructor(identifier_name);
+                if let Some(arguments) = contract_type_arguments {
+                    for argument in &arguments.node.type_arguments {
+                        self.resolve_type_argument(argument);
+                    }
+                }
+                for argument in argument_list {
+                    self.variable(argument);
+                }
+            }
+            NodeFullExpression::TemplateFunction { expression, .. } => {
+                self.resolve_expression(expression)
+            }
+            NodeFullExpression::TApp {
+                identifier_name,
+                type_arguments,
+            } => {
+                self.variable(identifier_name);
+                for argument in type_arguments {
+                    self.resolve_type_argument(argument);
+                }
+            }
+        }
+    }
+
+    fn resolve_literal(&mut self, literal: &NodeValueLiteral) {
+        match literal {
+            NodeValueLiteral::LiteralInt(type_name, _) => self.type_name(type_name),
+            NodeValueLiteral::LiteralEmptyMap(key, value) => {
+                self.resolve_map_key(key);
+                self.resolve_map_value(value);
+            }
+            NodeValueLiteral::LiteralHex(_) | NodeValueLiteral::LiteralString(_) => {}
+        }
+    }
+
+    fn resolve_type(&mut self, r#type: &WithMetaData<NodeScillaType>) {
+        match &r#type.node {
+            NodeScillaType::GenericTypeWithArgs(name, arguments) => {
+                self.type_name(name);
+                for argument in arguments {
+                    self.resolve_type_argument(argument);
+                }
+            }
+            NodeScillaType::MapType(key, value) => {
+                self.resolve_map_key(key);
+                self.resolve_map_value(value);
+            }
+            NodeScillaType::FunctionType(from, to) => {
+                self.resolve_type(from);
+                self.resolve_type(to);
+            }
+            NodeScillaType::EnclosedType(r#type) | NodeScillaType::PolyFunctionType(_, r#type) => {
+                self.resolve_type(r#type)
+            }
+            NodeScillaType::ScillaAddresseType(address) => self.resolve_address_type(address),
+            NodeScillaType::TypeVarType(_) => {}
+        }
+    }
+
+    fn resolve_type_argument(&mut self, argument: &WithMetaData<NodeTypeArgument>) {
+        match &argument.node {
+            NodeTypeArgument::EnclosedTypeArgument(r#type) => self.resolve_type(r#type),
+            NodeTypeArgument::GenericTypeArgument(name) => self.type_name(name),
+            NodeTypeArgument::TemplateTypeArgument(_) => {}
+            NodeTypeArgument::AddressTypeArgument(address) => self.resolve_address_type(address),
+            NodeTypeArgument::MapTypeArgument(key, value) => {
+                self.resolve_map_key(key);
+                self.resolve_map_value(value);
+            }
+        }
+    }
+
+    fn resolve_map_key(&mut self, key: &WithMetaData<NodeTypeMapKey>) {
+        match &key.node {
+            NodeTypeMapKey::GenericMapKey(name) | NodeTypeMapKey::EnclosedGenericId(name) => {
+                self.type_name(name)
+            }
+            NodeTypeMapKey::EnclosedAddressMapKeyType(address)
+            | NodeTypeMapKey::AddressMapKeyType(address) => self.resolve_address_type(address),
+        }
+    }
+
+    fn resolve_map_value(&mut self, value: &WithMetaData<NodeTypeMapValue>) {
+        match &value.node {
+            NodeTypeMapValue::MapValueTypeOrEnumLikeIdentifier(name) => self.type_name(name),
+            NodeTypeMapValue::MapKeyValue(entry) => {
+                self.resolve_map_key(&entry.node.key);
+                self.resolve_map_value(&entry.node.value);
+            }
+            NodeTypeMapValue::MapValueParenthesizedType(value) => {
+                self.resolve_map_value_with_arguments(value)
+            }
+            NodeTypeMapValue::MapValueAddressType(address) => self.resolve_address_type(address),
+        }
+    }
+
+    fn resolve_map_value_with_arguments(
+        &mut self,
+        value: &WithMetaData<NodeTypeMapValueAllowingTypeArguments>,
+    ) {
+        match &value.node {
+            NodeTypeMapValueAllowingTypeArguments::TypeMapValueNoArgs(value) => {
+                self.resolve_map_value(value)
+            }
+            NodeTypeMapValueAllowingTypeArguments::TypeMapValueWithArgs(name, arguments) => {
+                self.type_name(name);
+                for argument in arguments {
+                    match &argument.node {
+                        NodeTypeMapValueArguments::EnclosedTypeMapValue(value) => {
+                            self.resolve_map_value_with_arguments(value)
+                        }
+                        NodeTypeMapValueArguments::GenericMapValueArgument(name) => {
+                            self.type_name(name)
+                        }
+                        NodeTypeMapValueArguments::MapKeyValueType(key, value) => {
+                            self.resolve_map_key(key);
+                            self.resolve_map_value(value);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resolves the types of the fields of an address type. The field names belong to another
+    /// contract.
+    fn resolve_address_type(&mut self, address: &WithMetaData<NodeAddressType>) {
+        for field in &address.node.address_fields {
+            self.resolve_type(&field.node.type_name);
+        }
+    }
+}
+
+/// Returns true for the types every program knows, e.g. `Uint128` or `Option`.
+fn is_builtin_type(name: &str) -> bool {
+    matches!(
+        name,
+        "Bool" | "Option" | "List" | "Pair" | "Nat" | "Message" | "Event"
+    ) || type_from_name(name).is_some()
+}
@@ -1,9 +1,17 @@
-use crate::FieldList;
+use crate::{Event, Exception, FieldList, Message};
 
 #[derive(Debug, PartialEq)]
 pub struct Transition {
     pub name: String,
     pub params: FieldList,
+    /// Events the transition may emit, including those of the procedures it calls.
+    pub events: Vec<Event>,
+    /// Messages the transition may send, including those of the procedures it calls.
+    pub messages: Vec<Message>,
+    /// Exceptions the transition may throw, including those of the procedures it calls.
+    pub exceptions: Vec<Exception>,
+    /// The `(* @doc ... *)` comments above the transition.
+    pub doc: Option<String>,
 }
 
 impl Transition {
@@ -11,6 +19,10 @@ impl Transition {
         Self {
             name: name.to_string(),
             params,
+            events: vec![],
+            messages: vec![],
+            exceptions: vec![],
+            doc: None,
         }
     }
 
@@ -18,6 +30,10 @@ impl Transition {
         Self {
             name: name.to_string(),
             params: FieldList::default(),
+            events: vec![],
+            messages: vec![],
+            exceptions: vec![],
+            doc: None,
         }
     }
 }
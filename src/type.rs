@@ -1,6 +1,10 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
-use crate::{simplified_representation::primitives::SrType, FieldList};
+use crate::{
+    parser,
+    simplified_representation::{emitter::SrEmitter, primitives::SrType},
+    Constructor, Error, FieldList, TypeDefinition,
+};
 
 /// Represents all different scilla types.
 #[derive(Debug, PartialEq, Clone)]
@@ -23,11 +27,9 @@ pub enum Type {
     ByStr,
     ByStrX(usize),
     ByStr20,
+    /// An address type, e.g. `ByStr20 with contract field owner : ByStr20 end`.
     /// See https://scilla.readthedocs.io/en/latest/scilla-in-depth.html#addresses-1
-    ByStr20With {
-        type_name: String, // contract, library, ...
-        fields: FieldList,
-    },
+    ByStr20With(AddressKind),
 
     // ADT
     Bool,
@@ -35,9 +37,37 @@ pub enum Type {
     Pair(Box<Type>, Box<Type>),
     List(Box<Type>),
 
+    /// A user-defined ADT, e.g. `type Denom = | Zil | Token of ByStr20`.
+    /// `constructors` is empty if the type's declaration is not known, e.g. it is imported.
+    Adt {
+        name: String,
+        args: Vec<Type>,
+        constructors: Vec<Constructor>,
+    },
+
+    /// A function type, e.g. `Uint128 -> Bool`.
+    Function(Box<Type>, Box<Type>),
+    /// A polymorphic type over a type variable, e.g. `forall 'A. List ('A) -> Uint32`.
+    Forall(String, Box<Type>),
+    /// A type variable, e.g. `'A`.
+    TypeVar(String),
+
     Other(String),
 }
 
+/// What an address type requires of the account at the address. A raw address is a `Type::ByStr20`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AddressKind {
+    /// `ByStr20 with end`: any account in use.
+    Any,
+    /// `ByStr20 with library end`: a library.
+    Library,
+    /// `ByStr20 with contract ... end`: a contract with at least the given fields.
+    Contract(FieldList),
+}
+
+/// Prints a type the way scilla-checker does, with every type argument parenthesized,
+/// e.g. `Map (ByStr20) (List (Pair (ByStr20) (Uint128)))`.
 impl Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -52,29 +82,81 @@ impl Display for Type {
             Type::String => write!(f, "String"),
             Type::BNum => write!(f, "BNum"),
             Type::Bool => write!(f, "Bool"),
-            Type::Map(ref k, ref v) => write!(f, "(Map {}, {})", k, v),
-            Type::Option(ref k) => write!(f, "(Option {})", k),
-            Type::List(ref k) => write!(f, "(List {})", k),
-            Type::Pair(ref k, ref v) => write!(f, "(Pair {} {})", k, v),
+            Type::Map(ref k, ref v) => write!(f, "Map {} {}", TypeArgument(k), TypeArgument(v)),
+            Type::Option(ref k) => write!(f, "Option {}", TypeArgument(k)),
+            Type::List(ref k) => write!(f, "List {}", TypeArgument(k)),
+            Type::Pair(ref k, ref v) => write!(f, "Pair {} {}", TypeArgument(k), TypeArgument(v)),
             Type::ByStr => write!(f, "ByStr"),
             Type::ByStrX(n) => write!(f, "ByStr{}", n),
             Type::ByStr20 => write!(f, "ByStr20"),
-            Type::ByStr20With { type_name, fields } => {
+            Type::ByStr20With(AddressKind::Any) => write!(f, "ByStr20 with end"),
+            Type::ByStr20With(AddressKind::Library) => write!(f, "ByStr20 with library end"),
+            Type::ByStr20With(AddressKind::Contract(fields)) => {
                 let fields = fields
                     .iter()
-                    .map(|field| format!("field {}: {}", field.name, field.r#type))
+                    .map(|field| format!(" field {} : {}", field.name, field.r#type))
                     .collect::<Vec<String>>()
-                    .join(", ");
-                write!(f, "ByStr20 with {type_name}{fields} end")
+                    .join(",");
+                write!(f, "ByStr20 with contract{fields} end")
             }
+            Type::Adt { name, args, .. } => {
+                write!(f, "{name}")?;
+                for arg in args {
+                    write!(f, " {}", TypeArgument(arg))?;
+                }
+                Ok(())
+            }
+            Type::Function(ref from, ref to) => match **from {
+                Type::Function(..) | Type::Forall(..) => write!(f, "({from}) -> {to}"),
+                _ => write!(f, "{from} -> {to}"),
+            },
+            Type::Forall(ref variable, ref body) => write!(f, "forall {variable}. {body}"),
+            Type::TypeVar(ref variable) => write!(f, "{variable}"),
             Type::Other(ref s) => write!(f, "{}", s),
         }
     }
 }
 
-impl From<SrType> for Type {
-    fn from(mut type_definition: SrType) -> Self {
-        match type_definition.main_type.as_str() {
+/// A type argument, always parenthesized as scilla-checker prints it, e.g. `List (Uint128)`.
+struct TypeArgument<'a>(&'a Type);
+
+impl Display for TypeArgument<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({})", self.0)
+    }
+}
+
+impl FromStr for Type {
+    type Err = Error;
+
+    /// Parses a type as written in Scilla, e.g. `Map ByStr20 (List (Pair ByStr20 Uint128))`.
+    /// # Example
+    /// ```
+    /// use scilla_parser::Type;
+    /// let r#type: Type = "Option (List Uint32)".parse().unwrap();
+    /// assert_eq!(
+    ///     r#type,
+    ///     Type::Option(Box::new(Type::List(Box::new(Type::Uint32))))
+    /// );
+    /// ```
+    fn from_str(r#type: &str) -> Result<Self, Self::Err> {
+        let (parsed, errors) = parser::parse_type(r#type);
+        let parsed = match parsed {
+            Some(parsed) if errors.is_empty() => parsed,
+            _ => return Err(Error::TypeParseError(errors)),
+        };
+
+        SrEmitter::default()
+            .emit_type(&parsed)
+            .map_err(Error::AstVisitError)
+    }
+}
+
+impl TryFrom<SrType> for Type {
+    type Error = Error;
+
+    fn try_from(mut type_definition: SrType) -> Result<Self, Self::Error> {
+        let r#type = match type_definition.main_type.as_str() {
             "Int32" => Type::Int32,
             "Int64" => Type::Int64,
             "Int128" => Type::Int128,
@@ -87,25 +169,34 @@ impl From<SrType> for Type {
             "ByStr" => Type::ByStr,
             "BNum" => Type::BNum,
             "Bool" => Type::Bool,
-            // TODO: Remove unwrap
-            "Option" => Type::Option(Box::new(type_definition.sub_types.pop().unwrap().into())),
-            "List" => Type::List(Box::new(type_definition.sub_types.pop().unwrap().into())),
+            "Option" => Type::Option(Box::new(pop_type_argument(&mut type_definition)?)),
+            "List" => Type::List(Box::new(pop_type_argument(&mut type_definition)?)),
             "Pair" => {
-                let t2 = type_definition.sub_types.pop().unwrap();
-                let t1 = type_definition.sub_types.pop().unwrap();
-                Type::Pair(Box::new(t1.into()), Box::new(t2.into()))
+                let t2 = pop_type_argument(&mut type_definition)?;
+                let t1 = pop_type_argument(&mut type_definition)?;
+                Type::Pair(Box::new(t1), Box::new(t2))
             }
             "Map" => {
-                let value = type_definition.sub_types.pop().unwrap();
-                let key = type_definition.sub_types.pop().unwrap();
-                Type::Map(Box::new(key.into()), Box::new(value.into()))
+                let value = pop_type_argument(&mut type_definition)?;
+                let key = pop_type_argument(&mut type_definition)?;
+                Type::Map(Box::new(key), Box::new(value))
             }
-            "ByStr20" => type_definition
-                .address_type
-                .map_or(Type::ByStr20, |address_type| Type::ByStr20With {
-                    type_name: address_type.type_name,
-                    fields: address_type.fields,
-                }),
+            "ByStr20" => match type_definition.address_type {
+                None => Type::ByStr20,
+                Some(address_type) => {
+                    let kind = match address_type.type_name.as_str() {
+                        "" => AddressKind::Any,
+                        "library" => AddressKind::Library,
+                        "contract" => AddressKind::Contract(address_type.fields),
+                        kind => {
+                            return Err(Error::InvalidType(format!(
+                                "ByStr20 with {kind} end is not an address type"
+                            )))
+                        }
+                    };
+                    Type::ByStr20With(kind)
+                }
+            },
             t if t.starts_with("ByStr") => {
                 if let Some(number) = t
                     .strip_prefix("ByStr")
@@ -116,7 +207,84 @@ impl From<SrType> for Type {
                     Type::Other(type_definition.main_type)
                 }
             }
+            "->" => {
+                let to = pop_type_argument(&mut type_definition)?;
+                let from = pop_type_argument(&mut type_definition)?;
+                Type::Function(Box::new(from), Box::new(to))
+            }
+            "forall" => {
+                let body = pop_type_argument(&mut type_definition)?;
+                let variable = type_definition.sub_types.pop().ok_or_else(|| {
+                    Error::InvalidType("forall is missing a type variable".to_string())
+                })?;
+                Type::Forall(variable.main_type, Box::new(body))
+            }
+            t if t.starts_with('\'') => Type::TypeVar(type_definition.main_type),
+            t if is_adt_name(t) => Type::Adt {
+                name: type_definition.main_type,
+                args: type_definition
+                    .sub_types
+                    .into_iter()
+                    .map(Type::try_from)
+                    .collect::<Result<_, _>>()?,
+                constructors: vec![],
+            },
             _ => Type::Other(type_definition.main_type),
+        };
+
+        Ok(r#type)
+    }
+}
+
+/// Pops the last type argument of `type_definition`, e.g. `Uint128` of `Option Uint128`.
+fn pop_type_argument(type_definition: &mut SrType) -> Result<Type, Error> {
+    let argument = type_definition.sub_types.pop().ok_or_else(|| {
+        Error::InvalidType(format!(
+            "{} is missing a type argument",
+            type_definition.main_type
+        ))
+    })?;
+    argument.try_into()
+}
+
+/// Returns true if `name` is a (possibly namespaced) type name such as `Denom` or `Lib.Denom`.
+fn is_adt_name(name: &str) -> bool {
+    name.split('.').all(|part| {
+        part.starts_with(|c: char| c.is_ascii_uppercase())
+            && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+impl Type {
+    /// Fills in the constructors of user-defined ADTs from the given type definitions.
+    /// ADTs without a matching definition are left untouched.
+    pub fn resolve_adts(&mut self, type_definitions: &[TypeDefinition]) {
+        match self {
+            Type::Map(k, v) | Type::Pair(k, v) | Type::Function(k, v) => {
+                k.resolve_adts(type_definitions);
+                v.resolve_adts(type_definitions);
+            }
+            Type::Option(t) | Type::List(t) | Type::Forall(_, t) => {
+                t.resolve_adts(type_definitions)
+            }
+            Type::ByStr20With(AddressKind::Contract(fields)) => {
+                for field in fields.0.iter_mut() {
+                    field.r#type.resolve_adts(type_definitions);
+                }
+            }
+            Type::Adt {
+                name,
+                args,
+                constructors,
+            } => {
+                for arg in args.iter_mut() {
+                    arg.resolve_adts(type_definitions);
+                }
+                if let Some(definition) = type_definitions.iter().find(|d| &d.name == name) {
+                    constructors.clone_from(&definition.constructors);
+                }
+            }
+            _ => {}
         }
     }
 }
@@ -137,7 +305,7 @@ mod tests {
         )));
 
         assert_eq!(
-            "(List (Pair ByStr20 (List (Pair ByStr20 Uint32))))",
+            "List (Pair (ByStr20) (List (Pair (ByStr20) (Uint32))))",
             list_type.to_string()
         );
 
@@ -154,8 +322,62 @@ mod tests {
         )));
 
         assert_eq!(
-            "(List (Pair ByStr20 (List (Pair ByStr20 (List (Pair Uint32 Uint128))))))",
+            "List (Pair (ByStr20) (List (Pair (ByStr20) (List (Pair (Uint32) (Uint128))))))",
             list_type.to_string()
         );
     }
+
+    #[test]
+    fn test_user_defined_adt() {
+        // (MyPair Uint128 ByStr20)
+        let sr_type = SrType {
+            main_type: "MyPair".to_string(),
+            sub_types: ["Uint128", "ByStr20"]
+                .iter()
+                .map(|t| SrType {
+                    main_type: t.to_string(),
+                    sub_types: vec![],
+                    address_type: None,
+                })
+                .collect(),
+            address_type: None,
+        };
+        let mut my_pair = Type::try_from(sr_type).unwrap();
+        assert_eq!(
+            my_pair,
+            Type::Adt {
+                name: "MyPair".to_string(),
+                args: vec![Type::Uint128, Type::ByStr20],
+                constructors: vec![],
+            }
+        );
+        assert_eq!("MyPair (Uint128) (ByStr20)", my_pair.to_string());
+
+        let constructors = vec![Constructor::new(
+            "MyPair",
+            vec![Type::Uint128, Type::ByStr20],
+        )];
+        my_pair.resolve_adts(&[TypeDefinition::new("MyPair", constructors.clone())]);
+        assert_eq!(
+            my_pair,
+            Type::Adt {
+                name: "MyPair".to_string(),
+                args: vec![Type::Uint128, Type::ByStr20],
+                constructors,
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_type_argument() {
+        let sr_type = SrType {
+            main_type: "Option".to_string(),
+            sub_types: vec![],
+            address_type: None,
+        };
+        assert!(matches!(
+            Type::try_from(sr_type),
+            Err(Error::InvalidType(_))
+        ));
+    }
 }
@@ -0,0 +1,1247 @@
+//! Type checking of programs: declared names, field accesses, builtin arithmetic, constructor
+//! patterns and calls are checked without the Scilla toolchain. Builtin calls are checked
+//! against the signatures of `builtins`.
+//!
+//! The checker is lenient: a value whose type cannot be worked out, e.g. one returned by a
+//! polymorphic function, is compatible with every type, and address types are interchangeable.
+
+use std::{collections::HashMap, fmt};
+
+use thiserror::Error as ThisError;
+
+use crate::{
+    ast::nodes::*,
+    builtins,
+    parser::lexer::SourcePosition,
+    simplified_representation::{
+        emitter::SrEmitter,
+        message_collector::{component_name, type_from_name},
+    },
+    AddressKind, Project, Type,
+};
+
+/// What is wrong with the piece of code a `TypeError` points at.
+#[derive(Debug, Clone, PartialEq, ThisError)]
+pub enum TypeErrorKind {
+    #[error("undeclared variable {0}")]
+    UndeclaredVariable(String),
+
+    /// A field that is not declared, or a variable read as a field.
+    #[error("undeclared field {0}")]
+    UndeclaredField(String),
+
+    #[error("undeclared procedure {0}")]
+    UndeclaredProcedure(String),
+
+    #[error("unknown constructor {0}")]
+    UnknownConstructor(String),
+
+    #[error("expected type {expected}, got {found}")]
+    Mismatch { expected: Type, found: Type },
+
+    /// A pattern matching a value with a constructor of another type.
+    #[error("{constructor} is not a constructor of {r#type}")]
+    ForeignConstructor { constructor: String, r#type: Type },
+
+    /// A constructor, procedure or pattern given the wrong number of arguments.
+    #[error("{name} expects {expected} arguments, got {found}")]
+    Arity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("{name} of type {r#type} is not a function")]
+    NotAFunction { name: String, r#type: Type },
+
+    /// A map access with more keys than the map has.
+    #[error("{field} of type {r#type} is not a map of {keys} keys")]
+    NotAMap {
+        field: String,
+        r#type: Type,
+        keys: usize,
+    },
+
+    #[error("unknown builtin {0}")]
+    UnknownBuiltin(String),
+
+    #[error("builtin {builtin} cannot be applied to {}", join(.arguments))]
+    BuiltinArguments {
+        builtin: String,
+        arguments: Vec<Type>,
+    },
+}
+
+fn join(types: &[Type]) -> String {
+    types
+        .iter()
+        .map(Type::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A type error, with the span of the offending code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeError {
+    pub kind: TypeErrorKind,
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+impl TypeError {
+    pub(crate) fn at<T>(kind: TypeErrorKind, node: &WithMetaData<T>) -> Self {
+        Self {
+            kind,
+            start: node.start,
+            end: node.end,
+        }
+    }
+}
+
+/// Prints the error as `line:column: message`, counting lines and columns from 1.
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}",
+            self.start.line + 1,
+            self.start.column + 1,
+            self.kind
+        )
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// Type checks a program on its own.
+///
+/// The libraries the program imports are not known, so a name that is not declared in the
+/// program is assumed to be imported if the program imports anything. Use `Project::typecheck`
+/// to check the program against its imports.
+pub fn check_program(program: &NodeProgram) -> Result<(), Vec<TypeError>> {
+    let mut checker = Checker::new(true);
+    checker.import(program.import_declarations.as_ref(), &HashMap::new());
+    checker.check_program(program);
+    checker.into_result()
+}
+
+/// Works out the types of the values in the message literals of a program, by the position of
+/// their entry in the source. Type errors are ignored.
+pub(crate) fn message_entry_types(program: &NodeProgram) -> HashMap<usize, Type> {
+    let mut checker = Checker::new(true);
+    checker.import(program.import_declarations.as_ref(), &HashMap::new());
+    checker.check_program(program);
+    checker.entry_types
+}
+
+impl Project {
+    /// Type checks the contract against the libraries it imports. Only the contract is checked:
+    /// the libraries merely provide the types of the names they define.
+    pub fn typecheck(&self) -> Result<(), Vec<TypeError>> {
+        let mut exports: HashMap<String, Exports> = HashMap::new();
+        for library in &self.libraries {
+            let mut checker = Checker::new(true);
+            checker.import(library.module.import_declarations.as_ref(), &exports);
+            let library_exports = checker.check_library(&library.module.library_definition.node);
+            exports.insert(library.name.clone(), library_exports);
+        }
+
+        let mut checker = Checker::new(true);
+        checker.import(self.program.import_declarations.as_ref(), &exports);
+        checker.check_program(&self.program);
+        checker.into_result()
+    }
+}
+
+/// A constructor of an ADT, e.g. `Cons` of `List 'A` with arguments `'A` and `List 'A`.
+#[derive(Debug, Clone)]
+struct ConstructorSignature {
+    adt: String,
+    type_parameters: Vec<String>,
+    arg_types: Vec<Type>,
+}
+
+impl ConstructorSignature {
+    fn new(adt: &str, type_parameters: &[&str], arg_types: Vec<Type>) -> Self {
+        Self {
+            adt: adt.to_string(),
+            type_parameters: type_parameters.iter().map(|p| p.to_string()).collect(),
+            arg_types,
+        }
+    }
+
+    /// The argument types of the constructor in an instance of its ADT with the given type
+    /// arguments.
+    fn instantiate(&self, type_arguments: &[Type]) -> Option<Vec<Type>> {
+        if type_arguments.len() != self.type_parameters.len() {
+            return None;
+        }
+        let arg_types = self
+            .arg_types
+            .iter()
+            .map(|arg_type| {
+                self.type_parameters
+                    .iter()
+                    .zip(type_arguments)
+                    .fold(arg_type.clone(), |t, (parameter, argument)| {
+                        substitute(&t, parameter, argument)
+                    })
+            })
+            .collect();
+        Some(arg_types)
+    }
+}
+
+/// What a library makes available to its importers.
+#[derive(Debug, Clone, Default)]
+struct Exports {
+    values: HashMap<String, Option<Type>>,
+    constructors: HashMap<String, ConstructorSignature>,
+}
+
+/// The types of some of the values defined by the libraries of the Scilla standard library, for
+/// programs checked without them.
+fn stdlib_exports(library: &str) -> Option<Exports> {
+    let mut values: Vec<(String, String)> = vec![];
+    match library {
+        "BoolUtils" => {
+            for name in ["andb", "orb"] {
+                values.push((name.to_string(), "Bool -> Bool -> Bool".to_string()));
+            }
+            values.push(("negb".to_string(), "Bool -> Bool".to_string()));
+        }
+        "IntUtils" => {
+            for integer in ["Int32", "Int64", "Int128", "Int256"]
+                .into_iter()
+                .chain(["Uint32", "Uint64", "Uint128", "Uint256"])
+            {
+                for comparison in ["eq", "lt", "le", "gt", "ge"] {
+                    values.push((
+                        format!("{}_{comparison}", integer.to_lowercase()),
+                        format!("{integer} -> {integer} -> Bool"),
+                    ));
+                }
+            }
+        }
+        "PairUtils" => {
+            values.push((
+                "fst".to_string(),
+                "forall 'A. forall 'B. Pair 'A 'B -> 'A".to_string(),
+            ));
+            values.push((
+                "snd".to_string(),
+                "forall 'A. forall 'B. Pair 'A 'B -> 'B".to_string(),
+            ));
+        }
+        _ => return None,
+    }
+    let values = values
+        .into_iter()
+        .map(|(name, r#type)| (name, r#type.parse().ok()))
+        .collect();
+    Some(Exports {
+        values,
+        constructors: HashMap::new(),
+    })
+}
+
+/// The types of variables, `None` where it could not be worked out.
+type Scope = HashMap<String, Option<Type>>;
+
+struct Checker {
+    scopes: Vec<Scope>,
+    /// Names brought in by imports, qualified by the alias of their library if it has one.
+    imported: Scope,
+    constructors: HashMap<String, ConstructorSignature>,
+    fields: HashMap<String, Type>,
+    /// Parameter types of the procedures declared so far.
+    procedures: HashMap<String, Vec<Option<Type>>>,
+    /// False if names may come from libraries the checker does not know.
+    knows_every_name: bool,
+    /// The types of the message entries checked so far, by the position of the entry.
+    entry_types: HashMap<usize, Type>,
+    errors: Vec<TypeError>,
+}
+
+impl Checker {
+    fn new(knows_every_name: bool) -> Self {
+        let var = |name: &str| Type::TypeVar(name.to_string());
+        let constructors = [
+            ("True", ConstructorSignature::new("Bool", &[], vec![])),
+            ("False", ConstructorSignature::new("Bool", &[], vec![])),
+            (
+                "Some",
+                ConstructorSignature::new("Option", &["'A"], vec![var("'A")]),
+            ),
+            ("None", ConstructorSignature::new("Option", &["'A"], vec![])),
+            (
+                "Cons",
+                ConstructorSignature::new(
+                    "List",
+                    &["'A"],
+                    vec![var("'A"), Type::List(Box::new(var("'A")))],
+                ),
+            ),
+            ("Nil", ConstructorSignature::new("List", &["'A"], vec![])),
+            (
+                "Pair",
+                ConstructorSignature::new("Pair", &["'A", "'B"], vec![var("'A"), var("'B")]),
+            ),
+            ("Zero", ConstructorSignature::new("Nat", &[], vec![])),
+            (
+                "Succ",
+                ConstructorSignature::new("Nat", &[], vec![adt_type("Nat", vec![])]),
+            ),
+        ];
+
+        Self {
+            scopes: vec![Scope::new()],
+            imported: Scope::new(),
+            constructors: constructors
+                .into_iter()
+                .map(|(name, signature)| (name.to_string(), signature))
+                .collect(),
+            fields: HashMap::new(),
+            procedures: HashMap::new(),
+            knows_every_name,
+            entry_types: HashMap::new(),
+            errors: vec![],
+        }
+    }
+
+    fn into_result(self) -> Result<(), Vec<TypeError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    fn error<T>(&mut self, kind: TypeErrorKind, node: &WithMetaData<T>) {
+        self.errors.push(TypeError::at(kind, node));
+    }
+
+    /// Brings the exports of the imported libraries into scope.
+    fn import(
+        &mut self,
+        imports: Option<&WithMetaData<NodeImportDeclarations>>,
+        exports: &HashMap<String, Exports>,
+    ) {
+        let Some(imports) = imports else {
+            return;
+        };
+        for import in &imports.node.import_list {
+            // A library imported under an alias is only known by its alias.
+            let (name, prefixes) = match &import.node {
+                NodeImportedName::RegularImport(name) => {
+                    (name.to_string(), vec![String::new(), format!("{name}.")])
+                }
+                NodeImportedName::AliasedImport(name, alias) => {
+                    (name.to_string(), vec![format!("{alias}.")])
+                }
+            };
+            let stdlib;
+            let exports = match exports.get(&name) {
+                Some(exports) => exports,
+                None => {
+                    self.knows_every_name = false;
+                    // The standard library is not at hand, but the types of its values are known.
+                    match stdlib_exports(&name) {
+                        Some(exports) => {
+                            stdlib = exports;
+                            &stdlib
+                        }
+                        None => continue,
+                    }
+                }
+            };
+            for prefix in prefixes {
+                for (value, r#type) in &exports.values {
+                    self.imported
+                        .insert(format!("{prefix}{value}"), r#type.clone());
+                }
+                for (constructor, signature) in &exports.constructors {
+                    self.constructors
+                        .insert(format!("{prefix}{constructor}"), signature.clone());
+                }
+            }
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(Scope::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, name: &str, r#type: Option<Type>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), r#type);
+        }
+    }
+
+    /// Returns the type of a variable, reporting it if it is not declared.
+    fn lookup(&mut self, variable: &WithMetaData<NodeVariableIdentifier>) -> Option<Type> {
+        let name = variable.to_string();
+        let declared = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name))
+            .or_else(|| self.imported.get(&name));
+        match declared {
+            Some(r#type) => r#type.clone(),
+            None => {
+                // A field used without being loaded is reported even if names may be imported.
+                if self.knows_every_name || self.fields.contains_key(&name) {
+                    self.error(TypeErrorKind::UndeclaredVariable(name), variable);
+                }
+                None
+            }
+        }
+    }
+
+    /// Reports `found` if it is known and not compatible with `expected`.
+    fn expect<T>(&mut self, expected: &Type, found: &Option<Type>, node: &WithMetaData<T>) {
+        if let Some(found) = found {
+            if !compatible(expected, found) {
+                let kind = TypeErrorKind::Mismatch {
+                    expected: expected.clone(),
+                    found: found.clone(),
+                };
+                self.error(kind, node);
+            }
+        }
+    }
+
+    fn check_program(&mut self, program: &NodeProgram) {
+        if let Some(library) = &program.library_definition {
+            self.check_library(&library.node);
+        }
+        self.check_contract(&program.contract_definition.node);
+    }
+
+    /// Checks the definitions of a library, returning what it defines.
+    fn check_library(&mut self, library: &NodeLibraryDefinition) -> Exports {
+        let mut exports = Exports::default();
+        for definition in &library.definitions {
+            match &definition.node {
+                NodeLibrarySingleDefinition::LetDefinition {
+                    variable_name,
+                    type_annotation,
+                    expression,
+                } => {
+                    let r#type = self.check_let(type_annotation.as_ref(), expression);
+                    self.bind(&variable_name.node, r#type.clone());
+                    exports.values.insert(variable_name.node.clone(), r#type);
+                }
+                NodeLibrarySingleDefinition::TypeDefinition(name, clauses) => {
+                    for clause in clauses.iter().flatten() {
+                        let (constructor, arg_types) = match &clause.node {
+                            NodeTypeAlternativeClause::ClauseType(constructor) => {
+                                (constructor, vec![])
+                            }
+                            NodeTypeAlternativeClause::ClauseTypeWithArgs(constructor, args) => {
+                                let arg_types = args
+                                    .iter()
+                                    // An argument of unknown type is compatible with any other.
+                                    .map(|arg| {
+                                        argument_type(arg).unwrap_or(Type::Other(String::new()))
+                                    })
+                                    .collect();
+                                (constructor, arg_types)
+                            }
+                        };
+                        let signature =
+                            ConstructorSignature::new(&name.to_string(), &[], arg_types);
+                        self.constructors
+                            .insert(constructor.to_string(), signature.clone());
+                        exports
+                            .constructors
+                            .insert(constructor.to_string(), signature);
+                    }
+                }
+            }
+        }
+        exports
+    }
+
+    /// Checks the expression of a `let` against its annotation, returning the type of the
+    /// defined name.
+    fn check_let(
+        &mut self,
+        annotation: Option<&WithMetaData<NodeTypeAnnotation>>,
+        expression: &WithMetaData<NodeFullExpression>,
+    ) -> Option<Type> {
+        let found = self.check_expression(expression);
+        match annotation.and_then(|annotation| annotation_type(&annotation.node)) {
+            Some(expected) => {
+                self.expect(&expected, &found, expression);
+                Some(expected)
+            }
+            None => found,
+        }
+    }
+
+    fn check_contract(&mut self, contract: &NodeContractDefinition) {
+        self.push_scope();
+        self.bind("_this_address", Some(Type::ByStr20));
+        self.bind("_creation_block", Some(Type::BNum));
+        self.bind("_scilla_version", Some(Type::Uint32));
+        for (name, r#type) in parameters(&contract.parameters.node) {
+            self.bind(&name, r#type);
+        }
+
+        if let Some(constraint) = &contract.constraint {
+            let found = self.check_expression(&constraint.node.expression);
+            self.expect(&Type::Bool, &found, &constraint.node.expression);
+        }
+
+        self.fields.insert("_balance".to_string(), Type::Uint128);
+        for field in &contract.fields {
+            let typed_identifier = &field.node.typed_identifier.node;
+            let found = self.check_expression(&field.node.right_hand_side);
+            let Some(expected) = annotation_type(&typed_identifier.annotation.node) else {
+                continue;
+            };
+            self.expect(&expected, &found, &field.node.right_hand_side);
+            self.fields
+                .insert(typed_identifier.identifier_name.node.clone(), expected);
+        }
+
+        for component in &contract.components {
+            let (name, parameter_list, body, is_procedure) = match &component.node {
+                NodeComponentDefinition::TransitionComponent(transition) => {
+                    let transition = &transition.node;
+                    (
+                        &transition.name,
+                        &transition.parameters,
+                        &transition.body,
+                        false,
+                    )
+                }
+                NodeComponentDefinition::ProcedureComponent(procedure) => {
+                    let procedure = &procedure.node;
+                    (
+                        &procedure.name,
+                        &procedure.parameters,
+                        &procedure.body,
+                        true,
+                    )
+                }
+            };
+
+            let parameter_list = parameters(&parameter_list.node);
+            self.push_scope();
+            self.bind("_sender", Some(Type::ByStr20));
+            self.bind("_origin", Some(Type::ByStr20));
+            self.bind("_amount", Some(Type::Uint128));
+            for (name, r#type) in &parameter_list {
+                self.bind(name, r#type.clone());
+            }
+            if let Some(block) = &body.node.statement_block {
+                self.check_block(&block.node);
+            }
+            self.pop_scope();
+
+            // A procedure can only be called by the components declared after it.
+            if is_procedure {
+                let parameter_types = parameter_list.into_iter().map(|(_, t)| t).collect();
+                self.procedures
+                    .insert(component_name(&name.node), parameter_types);
+            }
+        }
+        self.pop_scope();
+    }
+
+    fn check_block(&mut self, block: &NodeStatementBlock) {
+        for statement in &block.statements {
+            self.check_statement(statement);
+        }
+    }
+
+    /// Returns the type of a field, reporting it if it is not declared.
+    fn field<T>(&mut self, name: &str, node: &WithMetaData<T>) -> Option<Type> {
+        let r#type = self.fields.get(name).cloned();
+        if r#type.is_none() {
+            self.error(TypeErrorKind::UndeclaredField(name.to_string()), node);
+        }
+        r#type
+    }
+
+    /// Checks the keys of a map access, returning the type of the accessed value.
+    fn map_value(
+        &mut self,
+        field: &WithMetaData<String>,
+        keys: &[WithMetaData<NodeMapAccess>],
+    ) -> Option<Type> {
+        let field_type = self.field(&field.node, field)?;
+        let mut value_type = Some(field_type.clone());
+        for key in keys {
+            let key_type = self.lookup(&key.node.identifier_name);
+            value_type = match value_type {
+                Some(Type::Map(expected, value)) => {
+                    self.expect(&expected, &key_type, key);
+                    Some(*value)
+                }
+                _ => None,
+            };
+        }
+        if value_type.is_none() {
+            let kind = TypeErrorKind::NotAMap {
+                field: field.node.clone(),
+                r#type: field_type,
+                keys: keys.len(),
+            };
+            self.error(kind, field);
+        }
+        value_type
+    }
+
+    fn check_statement(&mut self, statement: &NodeStatement) {
+        match statement {
+            NodeStatement::Load {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                let r#type = self.field(&right_hand_side.to_string(), right_hand_side);
+                self.bind(&left_hand_side.node, r#type);
+            }
+            NodeStatement::Store {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                let found = self.lookup(right_hand_side);
+                if let Some(expected) = self.field(&left_hand_side.node, left_hand_side) {
+                    self.expect(&expected, &found, right_hand_side);
+                }
+            }
+            NodeStatement::RemoteFetch(fetch) => self.check_remote_fetch(fetch),
+            NodeStatement::Bind {
+                left_hand_side,
+                right_hand_side,
+            } => {
+                let r#type = self.check_expression(right_hand_side);
+                self.bind(&left_hand_side.node, r#type);
+            }
+            NodeStatement::ReadFromBC {
+                left_hand_side,
+                type_name,
+                arguments,
+            } => {
+                for argument in arguments.iter().flat_map(|a| &a.arguments) {
+                    self.lookup(argument);
+                }
+                let r#type = match type_name.to_string().as_str() {
+                    "BLOCKNUMBER" => Some(Type::BNum),
+                    "CHAINID" => Some(Type::Uint32),
+                    "TIMESTAMP" => Some(Type::Option(Box::new(Type::Uint64))),
+                    _ => None,
+                };
+                self.bind(&left_hand_side.node, r#type);
+            }
+            NodeStatement::MapGet {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                let r#type = self.map_value(right_hand_side, keys);
+                self.bind(
+                    &left_hand_side.node,
+                    r#type.map(|t| Type::Option(Box::new(t))),
+                );
+            }
+            NodeStatement::MapGetExists {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                self.map_value(right_hand_side, keys);
+                self.bind(&left_hand_side.node, Some(Type::Bool));
+            }
+            NodeStatement::MapUpdate {
+                left_hand_side,
+                keys,
+                right_hand_side,
+            } => {
+                let found = self.lookup(right_hand_side);
+                if let Some(expected) = self.map_value(left_hand_side, keys) {
+                    self.expect(&expected, &found, right_hand_side);
+                }
+            }
+            NodeStatement::MapUpdateDelete {
+                left_hand_side,
+                keys,
+            } => {
+                self.map_value(left_hand_side, keys);
+            }
+            NodeStatement::Accept => {}
+            NodeStatement::Send { identifier_name } => {
+                let found = self.lookup(identifier_name);
+                self.expect(
+                    &Type::List(Box::new(message_type())),
+                    &found,
+                    identifier_name,
+                );
+            }
+            NodeStatement::CreateEvnt { identifier_name } => {
+                let found = self.lookup(identifier_name);
+                self.expect(&message_type(), &found, identifier_name);
+            }
+            NodeStatement::Throw { error_variable } => {
+                if let Some(error_variable) = error_variable {
+                    let found = self.lookup(error_variable);
+                    self.expect(&message_type(), &found, error_variable);
+                }
+            }
+            NodeStatement::MatchStmt { variable, clauses } => {
+                let scrutinee = self.lookup(variable);
+                for clause in clauses {
+                    self.push_scope();
+                    self.check_pattern(&clause.node.pattern_expression, scrutinee.as_ref());
+                    if let Some(block) = &clause.node.statement_block {
+                        self.check_block(&block.node);
+                    }
+                    self.pop_scope();
+                }
+            }
+            NodeStatement::CallProc {
+                component_id,
+                arguments,
+            } => {
+                let found: Vec<_> = arguments.iter().map(|a| self.lookup(a)).collect();
+                let Some(expected) = self.procedure(component_id) else {
+                    return;
+                };
+                if expected.len() != found.len() {
+                    let kind = TypeErrorKind::Arity {
+                        name: component_name(&component_id.node),
+                        expected: expected.len(),
+                        found: found.len(),
+                    };
+                    self.error(kind, component_id);
+                    return;
+                }
+                for ((expected, found), argument) in expected.iter().zip(&found).zip(arguments) {
+                    if let Some(expected) = expected {
+                        self.expect(expected, found, argument);
+                    }
+                }
+            }
+            NodeStatement::Iterate {
+                identifier_name,
+                component_id,
+            } => {
+                let list = self.lookup(identifier_name);
+                let Some(expected) = self.procedure(component_id) else {
+                    return;
+                };
+                if expected.len() != 1 {
+                    let kind = TypeErrorKind::Arity {
+                        name: component_name(&component_id.node),
+                        expected: expected.len(),
+                        found: 1,
+                    };
+                    self.error(kind, component_id);
+                    return;
+                }
+                if let Some(element) = &expected[0] {
+                    self.expect(
+                        &Type::List(Box::new(element.clone())),
+                        &list,
+                        identifier_name,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Returns the parameter types of a procedure, reporting it if it is not declared.
+    fn procedure(
+        &mut self,
+        component_id: &WithMetaData<NodeComponentId>,
+    ) -> Option<Vec<Option<Type>>> {
+        let name = component_name(&component_id.node);
+        let procedure = self.procedures.get(&name).cloned();
+        if procedure.is_none() {
+            self.error(TypeErrorKind::UndeclaredProcedure(name), component_id);
+        }
+        procedure
+    }
+
+    fn check_remote_fetch(&mut self, fetch: &NodeRemoteFetchStatement) {
+        let (left_hand_side, r#type) = match fetch {
+            NodeRemoteFetchStatement::ReadStateMutable(left_hand_side, address, field) => {
+                let address_type = self.lookup_name(address);
+                let field_type = match address_type {
+                    Some(Type::ByStr20With(AddressKind::Contract(fields))) => fields
+                        .iter()
+                        .find(|f| f.name == field.to_string())
+                        .map(|f| f.r#type.clone()),
+                    _ => None,
+                };
+                (left_hand_side, field_type)
+            }
+            NodeRemoteFetchStatement::ReadStateMutableSpecialId(left_hand_side, address, id) => {
+                self.lookup_name(address);
+                let r#type = match id.node.as_str() {
+                    "_balance" => Some(Type::Uint128),
+                    "_nonce" => Some(Type::Uint64),
+                    _ => None,
+                };
+                (left_hand_side, r#type)
+            }
+            NodeRemoteFetchStatement::ReadStateMutableMapAccess(
+                left_hand_side,
+                address,
+                _,
+                keys,
+            ) => {
+                self.lookup_name(address);
+                for key in keys {
+                    self.lookup(&key.node.identifier_name);
+                }
+                (left_hand_side, None)
+            }
+            NodeRemoteFetchStatement::ReadStateMutableMapAccessExists(
+                left_hand_side,
+                address,
+                _,
+                keys,
+            ) => {
+                self.lookup_name(address);
+                for key in keys {
+                    self.lookup(&key.node.identifier_name);
+                }
+                (left_hand_side, Some(Type::Bool))
+            }
+            NodeRemoteFetchStatement::ReadStateMutableCastAddress(
+                left_hand_side,
+                address,
+                address_type,
+            ) => {
+                self.lookup(address);
+                let node = WithMetaData {
+                    node: NodeScillaType::ScillaAddresseType(Box::new(address_type.clone())),
+                    start: address_type.start,
+                    end: address_type.end,
+                    trivia: Trivia::default(),
+                };
+                let r#type = scilla_type(&node).map(|t| Type::Option(Box::new(t)));
+                (left_hand_side, r#type)
+            }
+        };
+        self.bind(&left_hand_side.node, r#type);
+    }
+
+    /// Looks up a variable given by its name alone.
+    fn lookup_name(&mut self, name: &WithMetaData<String>) -> Option<Type> {
+        let variable = WithMetaData {
+            node: NodeVariableIdentifier::VariableName(name.clone()),
+            start: name.start,
+            end: name.end,
+            trivia: Trivia::default(),
+        };
+        self.lookup(&variable)
+    }
+
+    /// Returns the signature of a constructor, reporting it if it is unknown.
+    fn constructor(
+        &mut self,
+        constructor: &WithMetaData<NodeMetaIdentifier>,
+    ) -> Option<ConstructorSignature> {
+        let name = constructor.to_string();
+        let signature = self.constructors.get(&name).cloned();
+        let qualified = !matches!(constructor.node, NodeMetaIdentifier::MetaName(_));
+        if signature.is_none() && (self.knows_every_name || !qualified) {
+            self.error(TypeErrorKind::UnknownConstructor(name), constructor);
+        }
+        signature
+    }
+
+    /// Checks a pattern against the type of the matched value, binding the names it introduces.
+    fn check_pattern(&mut self, pattern: &WithMetaData<NodePattern>, scrutinee: Option<&Type>) {
+        match &pattern.node {
+            NodePattern::Wildcard => {}
+            NodePattern::Binder(name) => self.bind(&name.node, scrutinee.cloned()),
+            NodePattern::Constructor(constructor, arguments) => {
+                let arg_types =
+                    self.check_constructor_pattern(constructor, arguments.len(), scrutinee);
+                for (i, argument) in arguments.iter().enumerate() {
+                    let arg_type = arg_types.as_ref().and_then(|types| types.get(i));
+                    match &argument.node {
+                        NodeArgumentPattern::WildcardArgument => {}
+                        NodeArgumentPattern::BinderArgument(name) => {
+                            self.bind(&name.node, arg_type.cloned())
+                        }
+                        NodeArgumentPattern::ConstructorArgument(constructor) => {
+                            self.check_constructor_pattern(constructor, 0, arg_type);
+                        }
+                        NodeArgumentPattern::PatternArgument(pattern) => {
+                            self.check_pattern(pattern, arg_type)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks that a constructor pattern with `arity` arguments matches a value of type
+    /// `scrutinee`, returning the types of the arguments.
+    fn check_constructor_pattern(
+        &mut self,
+        constructor: &WithMetaData<NodeMetaIdentifier>,
+        arity: usize,
+        scrutinee: Option<&Type>,
+    ) -> Option<Vec<Type>> {
+        let signature = self.constructor(constructor)?;
+        if signature.arg_types.len() != arity {
+            let kind = TypeErrorKind::Arity {
+                name: constructor.to_string(),
+                expected: signature.arg_types.len(),
+                found: arity,
+            };
+            self.error(kind, constructor);
+            return None;
+        }
+
+        let scrutinee = scrutinee?;
+        // Types the checker cannot tell apart from an ADT are left alone.
+        if matches!(scrutinee, Type::Other(_) | Type::TypeVar(_)) {
+            return None;
+        }
+        let instance = adt_instance(scrutinee);
+        let Some((_, type_arguments)) = instance
+            .as_ref()
+            .filter(|(adt, _)| *adt == unqualified(&signature.adt))
+        else {
+            let kind = TypeErrorKind::ForeignConstructor {
+                constructor: constructor.to_string(),
+                r#type: scrutinee.clone(),
+            };
+            self.error(kind, constructor);
+            return None;
+        };
+        signature.instantiate(type_arguments)
+    }
+
+    fn check_expression(&mut self, expression: &WithMetaData<NodeFullExpression>) -> Option<Type> {
+        match &expression.node {
+            NodeFullExpression::LocalVariableDeclaration {
+                identifier_name,
+                expression,
+                type_annotation,
+                containing_expression,
+            } => {
+                let r#type = self.check_let(type_annotation.as_ref(), expression);
+                self.push_scope();
+                self.bind(&identifier_name.node, r#type);
+                let result = self.check_expression(containing_expression);
+                self.pop_scope();
+                result
+            }
+            NodeFullExpression::FunctionDeclaration {
+                identier_value,
+                type_annotation,
+                expression,
+            } => {
+                let parameter = annotation_type(&type_annotation.node);
+                self.push_scope();
+                self.bind(&identier_value.node, parameter.clone());
+                let result = self.check_expression(expression);
+                self.pop_scope();
+                Some(Type::Function(Box::new(parameter?), Box::new(result?)))
+            }
+            NodeFullExpression::FunctionCall {
+                function_name,
+                argument_list,
+            } => {
+                let mut r#type = self.lookup(function_name);
+                for argument in argument_list {
+                    let found = self.lookup(argument);
+                    r#type = match r#type {
+                        Some(Type::Function(from, to)) => {
+                            self.expect(&from, &found, argument);
+                            Some(*to)
+                        }
+                        Some(Type::Forall(..) | Type::TypeVar(_) | Type::Other(_)) | None => None,
+                        Some(r#type) => {
+                            let kind = TypeErrorKind::NotAFunction {
+                                name: function_name.to_string(),
+                                r#type,
+                            };
+                            self.error(kind, function_name);
+                            None
+                        }
+                    };
+                }
+                r#type
+            }
+            NodeFullExpression::ExpressionAtomic(atomic) => match &atomic.node {
+                NodeAtomicExpression::AtomicSid(variable) => self.lookup(variable),
+                NodeAtomicExpression::AtomicLit(literal) => literal_type(&literal.node),
+            },
+            NodeFullExpression::ExpressionBuiltin { xs, .. } => {
+                let arguments: Vec<_> = xs.node.arguments.iter().map(|a| self.lookup(a)).collect();
+                match builtins::check_call(expression, &arguments) {
+                    Ok(r#type) => r#type,
+                    Err(error) => {
+                        self.errors.push(error);
+                        None
+                    }
+                }
+            }
+            NodeFullExpression::Message(entries) => {
+                for entry in entries {
+                    let r#type = match &entry.node {
+                        NodeMessageEntry::MessageVariable(_, variable) => self.lookup(variable),
+                        NodeMessageEntry::MessageLiteral(_, literal) => literal_type(&literal.node),
+                    };
+                    if let Some(r#type) = r#type {
+                        self.entry_types.insert(entry.start.position, r#type);
+                    }
+                }
+                Some(message_type())
+            }
+            NodeFullExpression::Match {
+                match_expression,
+                clauses,
+            } => {
+                let scrutinee = self.lookup(match_expression);
+                let mut result: Option<Type> = None;
+                for clause in clauses {
+                    self.push_scope();
+                    self.check_pattern(&clause.node.pattern, scrutinee.as_ref());
+                    let found = self.check_expression(&clause.node.expression);
+                    self.pop_scope();
+                    match &result {
+                        Some(expected) => {
+                            let expected = expected.clone();
+                            self.expect(&expected, &found, &clause.node.expression);
+                        }
+                        None => result = found,
+                    }
+                }
+                result
+            }
+            NodeFullExpression::ConstructorCall {
+                identifier_name,
+                contract_type_arguments,
+                argument_list,
+            } => {
+                let found: Vec<_> = argument_list.iter().map(|a| self.lookup(a)).collect();
+                let signature = self.constructor(identifier_name)?;
+                if signature.arg_types.len() != found.len() {
+                    let kind = TypeErrorKind::Arity {
+                        name: identifier_name.to_string(),
+                        expected: signature.arg_types.len(),
+                        found: found.len(),
+                    };
+                    self.error(kind, identifier_name);
+                    return None;
+                }
+
+                let type_arguments = match contract_type_arguments {
+                    Some(arguments) => arguments
+                        .node
+                        .type_arguments
+                        .iter()
+                        .map(argument_type)
+                        .collect::<Option<Vec<_>>>()?,
+                    None => vec![],
+                };
+                let expected = signature.instantiate(&type_arguments)?;
+                for ((expected, found), argument) in expected.iter().zip(&found).zip(argument_list)
+                {
+                    self.expect(expected, found, argument);
+                }
+                Some(adt_type(&signature.adt, type_arguments))
+            }
+            NodeFullExpression::TemplateFunction {
+                identifier_name,
+                expression,
+            } => {
+                let body = self.check_expression(expression)?;
+                Some(Type::Forall(identifier_name.node.clone(), Box::new(body)))
+            }
+            NodeFullExpression::TApp {
+                identifier_name,
+                type_arguments,
+            } => {
+                let mut r#type = self.lookup(identifier_name)?;
+                for argument in type_arguments {
+                    let Type::Forall(variable, body) = r#type else {
+                        return None;
+                    };
+                    r#type = substitute(&body, &variable, &argument_type(argument)?);
+                }
+                Some(r#type)
+            }
+        }
+    }
+}
+
+/// The parameters of a contract or component, with their types.
+fn parameters(parameters: &NodeComponentParameters) -> Vec<(String, Option<Type>)> {
+    parameters
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let identifier = &parameter.node.identifier_with_type.node;
+            (
+                identifier.identifier_name.node.clone(),
+                annotation_type(&identifier.annotation.node),
+            )
+        })
+        .collect()
+}
+
+fn scilla_type(node: &WithMetaData<NodeScillaType>) -> Option<Type> {
+    SrEmitter::default().emit_type(node).ok()
+}
+
+pub(crate) fn annotation_type(annotation: &NodeTypeAnnotation) -> Option<Type> {
+    scilla_type(&annotation.type_name)
+}
+
+/// The type given as a type argument, e.g. `Uint128` in `Some {Uint128} x`.
+pub(crate) fn argument_type(argument: &WithMetaData<NodeTypeArgument>) -> Option<Type> {
+    let node = match &argument.node {
+        NodeTypeArgument::EnclosedTypeArgument(r#type) => return scilla_type(r#type),
+        NodeTypeArgument::TemplateTypeArgument(variable) => {
+            return Some(Type::TypeVar(variable.node.clone()))
+        }
+        NodeTypeArgument::GenericTypeArgument(name) => {
+            NodeScillaType::GenericTypeWithArgs(name.clone(), vec![])
+        }
+        NodeTypeArgument::AddressTypeArgument(address) => {
+            NodeScillaType::ScillaAddresseType(Box::new(address.clone()))
+        }
+        NodeTypeArgument::MapTypeArgument(key, value) => {
+            NodeScillaType::MapType(key.clone(), value.clone())
+        }
+    };
+    scilla_type(&WithMetaData {
+        node,
+        start: argument.start,
+        end: argument.end,
+        trivia: Trivia::default(),
+    })
+}
+
+fn literal_type(literal: &NodeValueLiteral) -> Option<Type> {
+    match literal {
+        NodeValueLiteral::LiteralInt(type_name, _) => type_from_name(&type_name.to_string()),
+        NodeValueLiteral::LiteralHex(value) => {
+            type_from_name(&format!("ByStr{}", value.node.len().saturating_sub(2) / 2))
+        }
+        NodeValueLiteral::LiteralString(_) => Some(Type::String),
+        NodeValueLiteral::LiteralEmptyMap(key, value) => scilla_type(&WithMetaData {
+            node: NodeScillaType::MapType(key.clone(), value.clone()),
+            start: key.start,
+            end: value.end,
+            trivia: Trivia::default(),
+        }),
+    }
+}
+
+/// The type of messages, events and exceptions.
+fn message_type() -> Type {
+    adt_type("Message", vec![])
+}
+
+/// The type of the ADT `name` applied to `args`.
+fn adt_type(name: &str, mut args: Vec<Type>) -> Type {
+    match (name, args.len()) {
+        ("Bool", 0) => Type::Bool,
+        ("Option", 1) => Type::Option(Box::new(args.remove(0))),
+        ("List", 1) => Type::List(Box::new(args.remove(0))),
+        ("Pair", 2) => {
+            let second = args.remove(1);
+            Type::Pair(Box::new(args.remove(0)), Box::new(second))
+        }
+        _ => Type::Adt {
+            name: name.to_string(),
+            args,
+            constructors: vec![],
+        },
+    }
+}
+
+/// The name of the ADT `r#type` is an instance of, with its type arguments.
+fn adt_instance(r#type: &Type) -> Option<(&str, Vec<Type>)> {
+    match r#type {
+        Type::Bool => Some(("Bool", vec![])),
+        Type::Option(t) => Some(("Option", vec![*t.clone()])),
+        Type::List(t) => Some(("List", vec![*t.clone()])),
+        Type::Pair(a, b) => Some(("Pair", vec![*a.clone(), *b.clone()])),
+        Type::Adt { name, args, .. } => Some((unqualified(name), args.clone())),
+        _ => None,
+    }
+}
+
+/// `name` without the library it may be qualified with, e.g. `Denom` for `Lib.Denom`.
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Replaces the type variable `variable` with `by` in `r#type`.
+pub(crate) fn substitute(r#type: &Type, variable: &str, by: &Type) -> Type {
+    let boxed = |t: &Type| Box::new(substitute(t, variable, by));
+    match r#type {
+        Type::TypeVar(name) if name == variable => by.clone(),
+        Type::Map(k, v) => Type::Map(boxed(k), boxed(v)),
+        Type::Pair(a, b) => Type::Pair(boxed(a), boxed(b)),
+        Type::Function(from, to) => Type::Function(boxed(from), boxed(to)),
+        Type::Option(t) => Type::Option(boxed(t)),
+        Type::List(t) => Type::List(boxed(t)),
+        Type::Forall(name, body) if name != variable => Type::Forall(name.clone(), boxed(body)),
+        Type::Adt {
+            name,
+            args,
+            constructors,
+        } => Type::Adt {
+            name: name.clone(),
+            args: args
+                .iter()
+                .map(|arg| substitute(arg, variable, by))
+                .collect(),
+            constructors: constructors.clone(),
+        },
+        _ => r#type.clone(),
+    }
+}
+
+/// Returns true if a value of type `found` can be used where `expected` is. Type variables and
+/// unknown types are compatible with anything, and so are all address types with each other.
+pub(crate) fn compatible(expected: &Type, found: &Type) -> bool {
+    let is_address =
+        |t: &Type| matches!(t, Type::ByStr20 | Type::ByStrX(20) | Type::ByStr20With(_));
+    match (expected, found) {
+        (Type::TypeVar(_) | Type::Other(_), _) | (_, Type::TypeVar(_) | Type::Other(_)) => true,
+        (a, b) if is_address(a) && is_address(b) => true,
+        (Type::Map(k1, v1), Type::Map(k2, v2))
+        | (Type::Pair(k1, v1), Type::Pair(k2, v2))
+        | (Type::Function(k1, v1), Type::Function(k2, v2)) => {
+            compatible(k1, k2) && compatible(v1, v2)
+        }
+        (Type::Option(t1), Type::Option(t2))
+        | (Type::List(t1), Type::List(t2))
+        | (Type::Forall(_, t1), Type::Forall(_, t2)) => compatible(t1, t2),
+        (
+            Type::Adt {
+                name: n1, args: a1, ..
+            },
+            Type::Adt {
+                name: n2, args: a2, ..
+            },
+        ) => {
+            unqualified(n1) == unqualified(n2)
+                && a1.len() == a2.len()
+                && a1.iter().zip(a2).all(|(a, b)| compatible(a, b))
+        }
+        (a, b) => a == b,
+    }
+}
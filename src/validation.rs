@@ -0,0 +1,128 @@
+//! Checking of `init.json` files and transition calls against a [`Contract`].
+
+use std::collections::HashSet;
+
+use serde_json::Value as Json;
+
+use crate::{value::field_path, Contract, Field, FieldList, NamedValue, Type, ValueError};
+
+impl FieldList {
+    /// Checks a JSON list of named values against the parameters, reporting every missing,
+    /// unexpected, duplicate or mistyped parameter.
+    pub fn validate(&self, values: &Json) -> Result<Vec<NamedValue>, Vec<ValueError>> {
+        validate_params(self, &[], values, "")
+    }
+}
+
+impl Contract {
+    /// Checks the content of an `init.json` file against the contract's init parameters.
+    /// The implicit `_scilla_version`, `_this_address`, `_creation_block`, `_library` and `_extlibs`
+    /// parameters may be given too.
+    pub fn validate_init(&self, init: &Json) -> Result<Vec<NamedValue>, Vec<ValueError>> {
+        validate_params(&self.init_params, &implicit_init_params(), init, "")
+    }
+
+    /// Checks a transition call, i.e. `{"_tag": ..., "params": [...]}`, against the contract's
+    /// transitions.
+    pub fn validate_call(&self, call: &Json) -> Result<Vec<NamedValue>, Vec<ValueError>> {
+        let Some(tag) = call.get("_tag").and_then(Json::as_str) else {
+            return Err(vec![ValueError::new("_tag", "missing transition name")]);
+        };
+        let Some(transition) = self.transitions.iter().find(|t| t.name == tag) else {
+            return Err(vec![ValueError::new(
+                "_tag",
+                format!("unknown transition {tag}"),
+            )]);
+        };
+        // A call without parameters may leave them out.
+        let no_params = Json::Array(vec![]);
+        let params = match call.get("params") {
+            None | Some(Json::Null) => &no_params,
+            Some(params) => params,
+        };
+        validate_params(&transition.params, &[], params, "params")
+    }
+}
+
+/// The implicit init parameters of every contract, listed first in its contract info.
+pub(crate) fn implicit_contract_params() -> Vec<Field> {
+    vec![
+        Field::new("_scilla_version", Type::Uint32),
+        Field::new("_this_address", Type::ByStr20),
+        Field::new("_creation_block", Type::BNum),
+    ]
+}
+
+fn implicit_init_params() -> Vec<Field> {
+    let mut params = implicit_contract_params();
+    params.extend([
+        Field::new("_library", Type::Bool),
+        Field::new(
+            "_extlibs",
+            Type::List(Box::new(Type::Pair(
+                Box::new(Type::String),
+                Box::new(Type::ByStr20),
+            ))),
+        ),
+    ]);
+    params
+}
+
+/// Decodes `values` as the `params`, and optionally the `implicit` ones. Errors are reported at
+/// the name of the offending parameter under `path`, e.g. `params.to`, or at its index in the
+/// list if it has no name, e.g. `params[3]`.
+fn validate_params(
+    params: &[Field],
+    implicit: &[Field],
+    values: &Json,
+    path: &str,
+) -> Result<Vec<NamedValue>, Vec<ValueError>> {
+    let Some(values) = values.as_array() else {
+        return Err(vec![ValueError::new(path, "expected a list of parameters")]);
+    };
+
+    let mut errors = vec![];
+    let mut decoded = vec![];
+    let mut seen = HashSet::new();
+    for (i, value) in values.iter().enumerate() {
+        let Some(vname) = value.get("vname").and_then(Json::as_str) else {
+            errors.push(ValueError::new(
+                &format!("{path}[{i}]"),
+                "expected a string vname",
+            ));
+            continue;
+        };
+        if !seen.insert(vname) {
+            errors.push(ValueError::new(
+                &field_path(path, vname),
+                "duplicate parameter",
+            ));
+            continue;
+        }
+        let Some(field) = params.iter().chain(implicit).find(|f| f.name == vname) else {
+            errors.push(ValueError::new(
+                &field_path(path, vname),
+                "unexpected parameter",
+            ));
+            continue;
+        };
+        match NamedValue::from_json_at(&field.r#type, value, path) {
+            Ok(value) => decoded.push(value),
+            Err(error) => errors.push(error),
+        }
+    }
+    for field in params {
+        if !seen.contains(field.name.as_str()) {
+            errors.push(ValueError::new(
+                &field_path(path, &field.name),
+                "missing parameter",
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(decoded)
+    } else {
+        Err(errors)
+    }
+}
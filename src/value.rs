@@ -0,0 +1,577 @@
+//! Scilla values and their JSON encoding, as found in `init.json` and the parameters of a
+//! transition call.
+
+use std::fmt::Display;
+
+use serde_json::{json, Value as Json};
+
+use crate::{AddressKind, Type};
+
+/// Represents a scilla value. A value is encoded, decoded and checked against a [`Type`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    Int32(i32),
+    Int64(i64),
+    Int128(i128),
+    /// A decimal integer.
+    Int256(String),
+
+    Uint32(u32),
+    Uint64(u64),
+    Uint128(u128),
+    /// A decimal integer.
+    Uint256(String),
+
+    String(String),
+
+    /// A block number, in decimal.
+    BNum(String),
+    /// The entries of a map, in order.
+    Map(Vec<(Value, Value)>),
+
+    /// The bytes of a `ByStr`, a `ByStrX` or an address.
+    ByStr(Vec<u8>),
+
+    // ADT
+    Bool(bool),
+    Option(Option<Box<Value>>),
+    Pair(Box<Value>, Box<Value>),
+    List(Vec<Value>),
+
+    /// A value of a user-defined ADT.
+    Adt {
+        constructor: String,
+        arguments: Vec<Value>,
+    },
+}
+
+/// A value not matching its type. `path` leads from the checked value to the mismatch, e.g.
+/// `[2].val.arguments[0]`, and is empty if the checked value itself is wrong.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValueError {
+    pub path: String,
+    pub message: String,
+}
+
+impl ValueError {
+    pub fn new(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+const UINT256_MAX: &str =
+    "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+const INT256_MAX: &str =
+    "57896044618658097711785492504343953926634992332820282019728792003956564819967";
+const INT256_MIN_ABS: &str =
+    "57896044618658097711785492504343953926634992332820282019728792003956564819968";
+
+impl Value {
+    /// Decodes a JSON value of type `r#type`, e.g. `"42"` for a `Uint128` or
+    /// `{"constructor": "True", "argtypes": [], "arguments": []}` for a `Bool`.
+    pub fn from_json(r#type: &Type, json: &Json) -> Result<Value, ValueError> {
+        decode(r#type, json, "")
+    }
+
+    /// Encodes the value as JSON, checking that it is of type `r#type`.
+    pub fn to_json(&self, r#type: &Type) -> Result<Json, ValueError> {
+        encode(self, r#type, "")
+    }
+
+    /// Checks that the value is of type `r#type`.
+    pub fn check(&self, r#type: &Type) -> Result<(), ValueError> {
+        self.to_json(r#type).map(|_| ())
+    }
+}
+
+/// A named value, encoded as `{"vname": ..., "type": ..., "value": ...}`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NamedValue {
+    pub vname: String,
+    pub r#type: Type,
+    pub value: Value,
+}
+
+impl NamedValue {
+    pub fn new(vname: &str, r#type: Type, value: Value) -> Self {
+        Self {
+            vname: vname.to_string(),
+            r#type,
+            value,
+        }
+    }
+
+    /// Encodes the named value as JSON, checking that the value is of its type.
+    pub fn to_json(&self) -> Result<Json, ValueError> {
+        Ok(json!({
+            "vname": self.vname,
+            "type": self.r#type.to_string(),
+            "value": self.value.to_json(&self.r#type)?,
+        }))
+    }
+
+    /// Decodes a named value whose type is expected to be `r#type`.
+    pub fn from_json(r#type: &Type, json: &Json) -> Result<Self, ValueError> {
+        Self::from_json_at(r#type, json, "")
+    }
+
+    /// Decodes a named value found at `path`, reporting errors at `path.vname`.
+    pub(crate) fn from_json_at(r#type: &Type, json: &Json, path: &str) -> Result<Self, ValueError> {
+        let vname = json
+            .get("vname")
+            .and_then(Json::as_str)
+            .ok_or_else(|| ValueError::new(path, "expected a string vname"))?;
+        let path = field_path(path, vname);
+        let declared = json
+            .get("type")
+            .and_then(Json::as_str)
+            .ok_or_else(|| ValueError::new(&path, "expected a string type"))?;
+        if !same_type(declared, r#type) {
+            return Err(ValueError::new(
+                &path,
+                format!("expected type {type}, got {declared}"),
+            ));
+        }
+        let value = json
+            .get("value")
+            .ok_or_else(|| ValueError::new(&path, "missing value"))?;
+        Ok(Self {
+            vname: vname.to_string(),
+            r#type: r#type.clone(),
+            value: decode(r#type, value, &path)?,
+        })
+    }
+}
+
+/// The path of the field `name` of the value at `path`, e.g. `params.to`.
+pub(crate) fn field_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+/// Whether a type written in JSON denotes `r#type`.
+pub(crate) fn same_type(written: &str, r#type: &Type) -> bool {
+    written
+        .parse::<Type>()
+        .is_ok_and(|written| denotes(&written, r#type))
+}
+
+/// Whether a parsed type is `r#type`. The JSON does not carry the constructors of ADTs, so only
+/// their names and arguments are compared.
+fn denotes(written: &Type, r#type: &Type) -> bool {
+    let all = |written: &[Type], types: &[Type]| {
+        written.len() == types.len() && written.iter().zip(types).all(|(w, t)| denotes(w, t))
+    };
+    match (written, r#type) {
+        (Type::Map(k1, v1), Type::Map(k2, v2))
+        | (Type::Pair(k1, v1), Type::Pair(k2, v2))
+        | (Type::Function(k1, v1), Type::Function(k2, v2)) => denotes(k1, k2) && denotes(v1, v2),
+        (Type::Option(t1), Type::Option(t2)) | (Type::List(t1), Type::List(t2)) => denotes(t1, t2),
+        (Type::Forall(a1, t1), Type::Forall(a2, t2)) => a1 == a2 && denotes(t1, t2),
+        (
+            Type::Adt {
+                name: n1, args: a1, ..
+            },
+            Type::Adt {
+                name: n2, args: a2, ..
+            },
+        ) => n1 == n2 && all(a1, a2),
+        (
+            Type::ByStr20With(AddressKind::Contract(f1)),
+            Type::ByStr20With(AddressKind::Contract(f2)),
+        ) => {
+            f1.len() == f2.len()
+                && f1
+                    .iter()
+                    .zip(f2.iter())
+                    .all(|(f1, f2)| f1.name == f2.name && denotes(&f1.r#type, &f2.r#type))
+        }
+        (Type::ByStr20, Type::ByStrX(20)) => true,
+        _ => written == r#type,
+    }
+}
+
+fn decode(r#type: &Type, json: &Json, path: &str) -> Result<Value, ValueError> {
+    let value = match r#type {
+        Type::Int32 => Value::Int32(decode_int(r#type, json, path)?),
+        Type::Int64 => Value::Int64(decode_int(r#type, json, path)?),
+        Type::Int128 => Value::Int128(decode_int(r#type, json, path)?),
+        Type::Uint32 => Value::Uint32(decode_int(r#type, json, path)?),
+        Type::Uint64 => Value::Uint64(decode_int(r#type, json, path)?),
+        Type::Uint128 => Value::Uint128(decode_int(r#type, json, path)?),
+        Type::Int256 | Type::Uint256 | Type::BNum => {
+            let s = expect_str(r#type, json, path)?;
+            check_big_int(r#type, s, path)?;
+            match r#type {
+                Type::Int256 => Value::Int256(s.to_string()),
+                Type::Uint256 => Value::Uint256(s.to_string()),
+                _ => Value::BNum(s.to_string()),
+            }
+        }
+        Type::String => Value::String(expect_str(r#type, json, path)?.to_string()),
+        Type::ByStr | Type::ByStrX(_) | Type::ByStr20 | Type::ByStr20With(_) => {
+            let s = expect_str(r#type, json, path)?;
+            let bytes = decode_hex(s)
+                .ok_or_else(|| ValueError::new(path, format!("{s} is not a hex string")))?;
+            check_length(r#type, &bytes, path)?;
+            Value::ByStr(bytes)
+        }
+        Type::Map(key_type, value_type) => {
+            let entries = json.as_array().ok_or_else(|| expected(r#type, path))?;
+            let mut map = Vec::with_capacity(entries.len());
+            for (i, entry) in entries.iter().enumerate() {
+                let path = format!("{path}[{i}]");
+                let (Some(key), Some(value)) = (entry.get("key"), entry.get("val")) else {
+                    return Err(ValueError::new(&path, "expected a key and a val"));
+                };
+                map.push((
+                    decode(key_type, key, &format!("{path}.key"))?,
+                    decode(value_type, value, &format!("{path}.val"))?,
+                ));
+            }
+            Value::Map(map)
+        }
+        Type::List(element_type) => {
+            let elements = json.as_array().ok_or_else(|| expected(r#type, path))?;
+            Value::List(
+                elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| decode(element_type, element, &format!("{path}[{i}]")))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        Type::Bool | Type::Option(_) | Type::Pair(_, _) | Type::Adt { .. } => {
+            let (constructor, arguments) = decode_constructor(r#type, json, path)?;
+            let arg_types = constructor_arg_types(r#type, constructor, path)?;
+            if arguments.len() != arg_types.len() {
+                return Err(ValueError::new(
+                    path,
+                    format!(
+                        "{constructor} takes {} arguments, got {}",
+                        arg_types.len(),
+                        arguments.len()
+                    ),
+                ));
+            }
+            let mut arguments = arg_types
+                .iter()
+                .zip(arguments)
+                .enumerate()
+                .map(|(i, (t, argument))| decode(t, argument, &format!("{path}.arguments[{i}]")))
+                .collect::<Result<Vec<_>, _>>()?;
+            match r#type {
+                Type::Bool => Value::Bool(constructor == "True"),
+                Type::Option(_) => Value::Option(arguments.pop().map(Box::new)),
+                Type::Pair(_, _) => {
+                    let second = arguments.pop().unwrap();
+                    let first = arguments.pop().unwrap();
+                    Value::Pair(Box::new(first), Box::new(second))
+                }
+                _ => Value::Adt {
+                    constructor: constructor.to_string(),
+                    arguments,
+                },
+            }
+        }
+        Type::Function(..) | Type::Forall(..) | Type::TypeVar(_) | Type::Other(_) => {
+            return Err(unsupported(r#type, path))
+        }
+    };
+    Ok(value)
+}
+
+fn encode(value: &Value, r#type: &Type, path: &str) -> Result<Json, ValueError> {
+    let json = match (value, r#type) {
+        (Value::Int32(n), Type::Int32) => json!(n.to_string()),
+        (Value::Int64(n), Type::Int64) => json!(n.to_string()),
+        (Value::Int128(n), Type::Int128) => json!(n.to_string()),
+        (Value::Uint32(n), Type::Uint32) => json!(n.to_string()),
+        (Value::Uint64(n), Type::Uint64) => json!(n.to_string()),
+        (Value::Uint128(n), Type::Uint128) => json!(n.to_string()),
+        (Value::Int256(s), Type::Int256)
+        | (Value::Uint256(s), Type::Uint256)
+        | (Value::BNum(s), Type::BNum) => {
+            check_big_int(r#type, s, path)?;
+            json!(s)
+        }
+        (Value::String(s), Type::String) => json!(s),
+        (
+            Value::ByStr(bytes),
+            Type::ByStr | Type::ByStrX(_) | Type::ByStr20 | Type::ByStr20With(_),
+        ) => {
+            check_length(r#type, bytes, path)?;
+            json!(encode_hex(bytes))
+        }
+        (Value::Map(entries), Type::Map(key_type, value_type)) => Json::Array(
+            entries
+                .iter()
+                .enumerate()
+                .map(|(i, (key, value))| {
+                    Ok(json!({
+                        "key": encode(key, key_type, &format!("{path}[{i}].key"))?,
+                        "val": encode(value, value_type, &format!("{path}[{i}].val"))?,
+                    }))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        (Value::List(elements), Type::List(element_type)) => Json::Array(
+            elements
+                .iter()
+                .enumerate()
+                .map(|(i, element)| encode(element, element_type, &format!("{path}[{i}]")))
+                .collect::<Result<_, _>>()?,
+        ),
+        (Value::Bool(b), Type::Bool) => {
+            encode_constructor(r#type, if *b { "True" } else { "False" }, &[], path)?
+        }
+        (Value::Option(Some(value)), Type::Option(_)) => {
+            encode_constructor(r#type, "Some", &[value.as_ref()], path)?
+        }
+        (Value::Option(None), Type::Option(_)) => encode_constructor(r#type, "None", &[], path)?,
+        (Value::Pair(first, second), Type::Pair(_, _)) => {
+            encode_constructor(r#type, "Pair", &[first.as_ref(), second.as_ref()], path)?
+        }
+        (
+            Value::Adt {
+                constructor,
+                arguments,
+            },
+            Type::Adt { .. },
+        ) => encode_constructor(
+            r#type,
+            constructor,
+            &arguments.iter().collect::<Vec<_>>(),
+            path,
+        )?,
+        (_, Type::Function(..) | Type::Forall(..) | Type::TypeVar(_) | Type::Other(_)) => {
+            return Err(unsupported(r#type, path))
+        }
+        _ => return Err(expected(r#type, path)),
+    };
+    Ok(json)
+}
+
+/// Encodes an ADT value as `{"constructor": ..., "argtypes": ..., "arguments": ...}`.
+fn encode_constructor(
+    r#type: &Type,
+    constructor: &str,
+    arguments: &[&Value],
+    path: &str,
+) -> Result<Json, ValueError> {
+    let arg_types = constructor_arg_types(r#type, constructor, path)?;
+    if arguments.len() != arg_types.len() {
+        return Err(ValueError::new(
+            path,
+            format!(
+                "{constructor} takes {} arguments, got {}",
+                arg_types.len(),
+                arguments.len()
+            ),
+        ));
+    }
+    let arguments = arguments
+        .iter()
+        .zip(&arg_types)
+        .enumerate()
+        .map(|(i, (argument, t))| encode(argument, t, &format!("{path}.arguments[{i}]")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let argtypes = type_arguments(r#type)
+        .iter()
+        .map(Type::to_string)
+        .collect::<Vec<_>>();
+    Ok(json!({
+        "constructor": constructor,
+        "argtypes": argtypes,
+        "arguments": arguments,
+    }))
+}
+
+/// Splits an ADT value into its constructor and arguments, checking its `argtypes`.
+fn decode_constructor<'a>(
+    r#type: &Type,
+    json: &'a Json,
+    path: &str,
+) -> Result<(&'a str, &'a [Json]), ValueError> {
+    let constructor = json
+        .get("constructor")
+        .and_then(Json::as_str)
+        .ok_or_else(|| expected(r#type, path))?;
+    let empty: &[Json] = &[];
+    let list = |key: &str| match json.get(key) {
+        None => Ok(empty),
+        Some(Json::Array(list)) => Ok(list.as_slice()),
+        Some(_) => Err(ValueError::new(
+            path,
+            format!("expected {key} to be a list"),
+        )),
+    };
+    let argtypes = list("argtypes")?;
+    let arguments = list("arguments")?;
+
+    let expected_argtypes = type_arguments(r#type);
+    let argtypes_match = argtypes.len() == expected_argtypes.len()
+        && argtypes.iter().zip(&expected_argtypes).all(|(written, t)| {
+            written
+                .as_str()
+                .is_some_and(|written| same_type(written, t))
+        });
+    if !argtypes_match {
+        return Err(ValueError::new(
+            path,
+            format!("argtypes of {type} do not match"),
+        ));
+    }
+    Ok((constructor, arguments))
+}
+
+/// The type arguments of an ADT, as written in its `argtypes`.
+fn type_arguments(r#type: &Type) -> Vec<Type> {
+    match r#type {
+        Type::Option(t) => vec![*t.clone()],
+        Type::Pair(t1, t2) => vec![*t1.clone(), *t2.clone()],
+        Type::Adt { args, .. } => args.clone(),
+        _ => vec![],
+    }
+}
+
+/// The types of the arguments of `constructor` of the ADT `r#type`.
+fn constructor_arg_types(
+    r#type: &Type,
+    constructor: &str,
+    path: &str,
+) -> Result<Vec<Type>, ValueError> {
+    let arg_types = match (r#type, constructor) {
+        (Type::Bool, "True" | "False") => Some(vec![]),
+        (Type::Option(t), "Some") => Some(vec![*t.clone()]),
+        (Type::Option(_), "None") => Some(vec![]),
+        (Type::Pair(t1, t2), "Pair") => Some(vec![*t1.clone(), *t2.clone()]),
+        (Type::Adt { constructors, .. }, _) if constructors.is_empty() => {
+            return Err(ValueError::new(
+                path,
+                format!("the constructors of {type} are unknown"),
+            ))
+        }
+        (Type::Adt { constructors, .. }, _) => constructors
+            .iter()
+            .find(|c| c.name == constructor)
+            .map(|c| c.arg_types.clone()),
+        _ => None,
+    };
+    arg_types.ok_or_else(|| {
+        ValueError::new(
+            path,
+            format!("{constructor} is not a constructor of {type}"),
+        )
+    })
+}
+
+fn decode_int<T: std::str::FromStr>(
+    r#type: &Type,
+    json: &Json,
+    path: &str,
+) -> Result<T, ValueError> {
+    let s = expect_str(r#type, json, path)?;
+    if !is_integer(s) {
+        return Err(ValueError::new(path, format!("{s} is not an integer")));
+    }
+    s.parse()
+        .map_err(|_| ValueError::new(path, format!("{s} is out of range of {type}")))
+}
+
+/// Checks a decimal integer against the range of `Int256`, `Uint256` or `BNum`.
+fn check_big_int(r#type: &Type, s: &str, path: &str) -> Result<(), ValueError> {
+    if !is_integer(s) {
+        return Err(ValueError::new(path, format!("{s} is not an integer")));
+    }
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    let digits = match digits.trim_start_matches('0') {
+        "" => "0",
+        digits => digits,
+    };
+    let max = match (r#type, negative) {
+        (Type::Int256, false) => Some(INT256_MAX),
+        (Type::Int256, true) => Some(INT256_MIN_ABS),
+        (Type::Uint256, false) => Some(UINT256_MAX),
+        (_, false) => None,
+        (_, true) => Some("0"),
+    };
+    // Without leading zeros, comparing lengths first orders decimal numbers.
+    match max {
+        Some(max) if (digits.len(), digits) > (max.len(), max) => Err(ValueError::new(
+            path,
+            format!("{s} is out of range of {type}"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn check_length(r#type: &Type, bytes: &[u8], path: &str) -> Result<(), ValueError> {
+    let length = match r#type {
+        Type::ByStrX(n) => *n,
+        Type::ByStr20 | Type::ByStr20With(_) => 20,
+        _ => return Ok(()),
+    };
+    if bytes.len() == length {
+        Ok(())
+    } else {
+        Err(ValueError::new(
+            path,
+            format!("expected {length} bytes for {type}, got {}", bytes.len()),
+        ))
+    }
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let digits = s.strip_prefix("0x")?;
+    if digits.len() % 2 != 0 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("0x{digits}")
+}
+
+fn expect_str<'a>(r#type: &Type, json: &'a Json, path: &str) -> Result<&'a str, ValueError> {
+    json.as_str().ok_or_else(|| expected(r#type, path))
+}
+
+fn expected(r#type: &Type, path: &str) -> ValueError {
+    ValueError::new(path, format!("expected a value of type {type}"))
+}
+
+fn unsupported(r#type: &Type, path: &str) -> ValueError {
+    ValueError::new(path, format!("values of type {type} are not supported"))
+}
@@ -0,0 +1,215 @@
+use pretty_assertions::assert_eq;
+
+use scilla_parser::{
+    ast::nodes::NodeLibrarySingleDefinition,
+    builtins::{check_call, find_builtin, TypeClass},
+    parser::parse_program,
+    typecheck::check_program,
+    Type, TypeErrorKind,
+};
+
+fn boxed(t: Type) -> Box<Type> {
+    Box::new(t)
+}
+
+#[test]
+fn test_polymorphic_signatures() {
+    let balances = Type::Map(boxed(Type::ByStr20), boxed(Type::Uint128));
+    let cases = vec![
+        ("add", vec![Type::Int64, Type::Int64], Some(Type::Int64)),
+        ("add", vec![Type::Int64, Type::Uint64], None),
+        ("add", vec![Type::String, Type::String], None),
+        (
+            "pow",
+            vec![Type::Uint256, Type::Uint32],
+            Some(Type::Uint256),
+        ),
+        ("eq", vec![Type::BNum, Type::BNum], Some(Type::Bool)),
+        ("eq", vec![Type::Bool, Type::Bool], None),
+        (
+            "put",
+            vec![balances.clone(), Type::ByStr20, Type::Uint128],
+            Some(balances.clone()),
+        ),
+        (
+            "put",
+            vec![balances.clone(), Type::ByStr20, Type::Int32],
+            None,
+        ),
+        (
+            "get",
+            vec![balances.clone(), Type::ByStr20],
+            Some(Type::Option(boxed(Type::Uint128))),
+        ),
+        (
+            "to_list",
+            vec![balances.clone()],
+            Some(Type::List(boxed(Type::Pair(
+                boxed(Type::ByStr20),
+                boxed(Type::Uint128),
+            )))),
+        ),
+        ("size", vec![balances], Some(Type::Uint32)),
+        (
+            "to_uint32",
+            vec![Type::Uint128],
+            Some(Type::Option(boxed(Type::Uint32))),
+        ),
+        ("to_uint32", vec![Type::ByStrX(4)], Some(Type::Uint32)),
+        ("to_int32", vec![Type::ByStrX(4)], None),
+        (
+            "concat",
+            vec![Type::ByStr20, Type::ByStrX(12)],
+            Some(Type::ByStrX(32)),
+        ),
+        ("concat", vec![Type::String, Type::ByStr], None),
+        ("sha256hash", vec![Type::String], Some(Type::ByStrX(32))),
+        ("badd", vec![Type::BNum, Type::Uint128], Some(Type::BNum)),
+        ("bsub", vec![Type::BNum, Type::BNum], Some(Type::Int256)),
+    ];
+
+    for (name, arguments, expected) in cases {
+        let builtin = find_builtin(name).unwrap();
+        assert_eq!(builtin.apply(&arguments), expected, "{name} {arguments:?}");
+    }
+}
+
+#[test]
+fn test_signature_display() {
+    let cases = [
+        ("add", "'A -> 'A -> 'A where 'A is an integer type"),
+        (
+            "get",
+            "Map ('K) ('V) -> 'K -> Option ('V) where 'K is a primitive type",
+        ),
+        ("schnorr_verify", "ByStr33 -> ByStr -> ByStr64 -> Bool"),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(
+            find_builtin(name).unwrap().signatures[0].to_string(),
+            expected
+        );
+    }
+    assert!(TypeClass::ByStrX.contains(&Type::ByStr20));
+    assert!(!TypeClass::ByStrX.contains(&Type::ByStr));
+}
+
+#[test]
+fn test_check_call() {
+    let source = r#"scilla_version 0
+library Calls
+let call = builtin concat a b
+contract Calls ()
+"#;
+    let program = parse_program(source).0.unwrap();
+    let library = program.library_definition.unwrap();
+    let NodeLibrarySingleDefinition::LetDefinition { expression, .. } =
+        &library.node.definitions[0].node
+    else {
+        panic!("expected a let definition");
+    };
+
+    assert_eq!(
+        check_call(expression, &[Some(Type::String), Some(Type::String)]),
+        Ok(Some(Type::String))
+    );
+    // The call may be valid once the type of `a` is known.
+    assert_eq!(
+        check_call(expression, &[None, Some(Type::Uint32)]),
+        Ok(None)
+    );
+
+    let error = check_call(expression, &[Some(Type::String), Some(Type::Uint32)]).unwrap_err();
+    assert_eq!(
+        error.kind,
+        TypeErrorKind::BuiltinArguments {
+            builtin: "concat".to_string(),
+            arguments: vec![Type::String, Type::Uint32],
+        }
+    );
+    assert_eq!((error.start.line, error.start.column), (2, 11));
+}
+
+#[test]
+fn test_builtin_diagnostics() {
+    let source = r#"scilla_version 0
+library Diagnostics
+let one = Uint128 1
+let two = builtin plus one one
+let three = builtin add one
+let big = Uint256 1
+let small = builtin to_uint32 big
+let sum = builtin add small small
+
+contract Diagnostics ()
+"#;
+    let errors: Vec<_> = check_program(&parse_program(source).0.unwrap())
+        .unwrap_err()
+        .into_iter()
+        .map(|e| (e.start.line + 1, e.start.column + 1, e.kind))
+        .collect();
+    let small = Type::Option(boxed(Type::Uint32));
+    assert_eq!(
+        errors,
+        vec![
+            (4, 19, TypeErrorKind::UnknownBuiltin("plus".to_string())),
+            (
+                5,
+                21,
+                TypeErrorKind::Arity {
+                    name: "add".to_string(),
+                    expected: 2,
+                    found: 1,
+                }
+            ),
+            (
+                8,
+                11,
+                TypeErrorKind::BuiltinArguments {
+                    builtin: "add".to_string(),
+                    arguments: vec![small.clone(), small],
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_byte_string_and_curve_builtins() {
+    let source = r#"scilla_version 0
+library Curves
+
+contract Curves
+(
+  raw : ByStr,
+  nonce : Uint32,
+  point : Pair ByStr32 ByStr32,
+  scalar : ByStr32,
+  pairs : List (Pair (Pair ByStr32 ByStr32) (Pair ByStr64 ByStr64))
+)
+
+field address : Option ByStr20 = None {ByStr20}
+field nonce_bytes : ByStr4 = 0x00000000
+field sum : Option (Pair ByStr32 ByStr32) = None {(Pair ByStr32 ByStr32)}
+field product : Option (Pair ByStr32 ByStr32) = None {(Pair ByStr32 ByStr32)}
+field paired : Option Bool = None {Bool}
+
+transition Compute ()
+  a = builtin to_bystr20 raw;
+  address := a;
+  n = builtin to_bystr4 nonce;
+  nonce_bytes := n;
+  s = builtin alt_bn128_G1_add point point;
+  sum := s;
+  p = builtin alt_bn128_G1_mul point scalar;
+  product := p;
+  q = builtin alt_bn128_pairing_product pairs;
+  paired := q
+end
+"#;
+    assert_eq!(check_program(&parse_program(source).0.unwrap()), Ok(()));
+
+    // The results are typed, so misusing them is reported.
+    let misused = source.replace("address := a", "address := n");
+    assert!(check_program(&parse_program(&misused).0.unwrap()).is_err());
+}
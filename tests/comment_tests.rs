@@ -0,0 +1,100 @@
+use scilla_parser::{
+    ast::nodes::{NodeComponentDefinition, NodeProgram, NodeStatement},
+    parser::parse_program,
+    Contract,
+};
+
+const CONTRACT: &str = r#"scilla_version 0
+
+contract Documented
+(owner : ByStr20)
+
+(* @doc: The greeting sent back by getHello *)
+field welcome_msg : String = "" (* empty until set *)
+
+(* Not part of the documentation *)
+(* @doc Sets the greeting. *)
+(* @doc Only the owner may call it. *)
+transition setHello (msg : String)
+  (* Store the greeting *)
+  welcome_msg := msg
+end
+
+(* @doc Emits the greeting. *)
+procedure EmitHello ()
+  msg <- welcome_msg;
+  (* Build the event *)
+  e = {_eventname : "getHello()"; msg : msg};
+  event e
+end
+
+transition getHello ()
+  EmitHello
+end
+"#;
+
+fn parse(source: &str) -> NodeProgram {
+    let (program, errors) = parse_program(source);
+    assert!(errors.is_empty(), "{errors:?}");
+    program.unwrap()
+}
+
+#[test]
+fn test_leading_and_trailing_comments() {
+    let program = parse(CONTRACT);
+    let contract = &program.contract_definition.node;
+
+    let field = &contract.fields[0].trivia;
+    assert_eq!(
+        field.leading[0].text,
+        " @doc: The greeting sent back by getHello "
+    );
+    assert_eq!(field.trailing[0].text, " empty until set ");
+
+    let transition = &contract.components[0].trivia;
+    assert_eq!(transition.leading.len(), 3);
+    assert!(transition.trailing.is_empty());
+
+    // The outermost node starting after a comment gets it, here the body of the transition.
+    let NodeComponentDefinition::TransitionComponent(set_hello) = &contract.components[0].node
+    else {
+        panic!("Expected a transition");
+    };
+    assert_eq!(
+        set_hello.node.body.trivia.leading[0].text,
+        " Store the greeting "
+    );
+
+    // Statements carry no metadata, so the comment goes to the first node of the statement.
+    let NodeComponentDefinition::ProcedureComponent(emit_hello) = &contract.components[1].node
+    else {
+        panic!("Expected a procedure");
+    };
+    let block = emit_hello.node.body.node.statement_block.as_ref().unwrap();
+    let NodeStatement::Bind { left_hand_side, .. } = &block.node.statements[1] else {
+        panic!("Expected a bind statement");
+    };
+    assert_eq!(left_hand_side.trivia.leading[0].text, " Build the event ");
+
+    assert!(contract.components[2].trivia.is_empty());
+}
+
+#[test]
+fn test_doc_comments() {
+    let contract: Contract = CONTRACT.parse().unwrap();
+
+    assert_eq!(
+        contract.fields[0].doc.as_deref(),
+        Some("The greeting sent back by getHello")
+    );
+    assert_eq!(
+        contract.transitions[0].doc.as_deref(),
+        Some("Sets the greeting.\nOnly the owner may call it.")
+    );
+    assert_eq!(
+        contract.procedures[0].doc.as_deref(),
+        Some("Emits the greeting.")
+    );
+    assert_eq!(contract.transitions[1].doc, None);
+    assert_eq!(contract.init_params[0].doc, None);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "json")]
+
+use pretty_assertions::assert_eq;
+use std::{error::Error, path::PathBuf};
+
+use scilla_parser::Contract;
+
+#[test]
+fn test_contract_info_matches_expected_output() -> Result<(), Box<dyn Error>> {
+    for entry in std::fs::read_dir("tests/contracts")? {
+        let contract_path = entry?.path();
+        let contract = Contract::parse(&contract_path)?;
+
+        let expected_path = PathBuf::from("tests/contract_info")
+            .join(contract_path.file_stem().unwrap())
+            .with_extension("json");
+        let expected = std::fs::read_to_string(&expected_path)
+            .map_err(|e| format!("{}: {e}", expected_path.display()))?;
+
+        // The checker's layout differs from serde_json's, only the values are compared.
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&contract.contract_info().to_json()?)?,
+            serde_json::from_str::<serde_json::Value>(&expected)?,
+            "{}",
+            contract_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Types print the way scilla-checker prints them in the contract info.
+#[test]
+fn test_types_print_like_scilla_checker() -> Result<(), Box<dyn Error>> {
+    let cases = [
+        ("GetFields", "balances", "Map (ByStr20) (Uint128)"),
+        ("GetFields", "field_option_bystr20_none", "Option (ByStr20)"),
+        ("GetFields", "field_pair", "Pair (String) (Uint32)"),
+        ("GetFields", "field_list", "List (Int32)"),
+        (
+            "ZRC2Interop",
+            "allowances",
+            "Map (ByStr20) (Map (ByStr20) (Uint128))",
+        ),
+        ("Map", "user_withdrawal_dict", "Option (Map (BNum) (Uint128))"),
+        ("ByStr", "library_address", "ByStr20 with library end"),
+        (
+            "ByStr",
+            "token",
+            "ByStr20 with contract field balances : Map (ByStr20) (Uint128) end",
+        ),
+        (
+            "ByStr",
+            "guardianship",
+            "Option (ByStr20 with contract field verification_methods : Map (String) (ByStr33) end)",
+        ),
+    ];
+
+    for (contract_name, name, expected) in cases {
+        let contract_path = PathBuf::from("tests/contracts")
+            .join(contract_name)
+            .with_extension("scilla");
+        let contract = Contract::parse(&contract_path)?;
+        let r#type = &contract
+            .fields
+            .iter()
+            .chain(contract.init_params.iter())
+            .chain(contract.transitions.iter().flat_map(|t| t.params.iter()))
+            .find(|field| field.name == name)
+            .ok_or_else(|| format!("{contract_name} has no {name}"))?
+            .r#type;
+
+        assert_eq!(r#type.to_string(), expected, "{contract_name}: {name}");
+    }
+
+    Ok(())
+}
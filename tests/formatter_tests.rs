@@ -0,0 +1,143 @@
+use std::{error::Error, fs};
+
+use regex::Regex;
+use scilla_parser::{
+    ast::nodes::NodeProgram,
+    formatter::{format, ScillaFormatter},
+    parser::parse_program,
+};
+
+fn parse(source: &str) -> NodeProgram {
+    let (program, errors) = parse_program(source);
+    assert!(errors.is_empty(), "{errors:?}");
+    program.unwrap()
+}
+
+/// The debug representation of `program` without source positions, which change with the layout.
+fn without_positions(program: &NodeProgram) -> String {
+    let positions = Regex::new(r"SourcePosition \{[^}]*\}").unwrap();
+    positions
+        .replace_all(&format!("{program:?}"), "SourcePosition")
+        .to_string()
+}
+
+#[test]
+fn test_format_round_trip() -> Result<(), Box<dyn Error>> {
+    for entry in fs::read_dir("tests/contracts")? {
+        let path = entry?.path();
+        println!("Formatting {}", path.display());
+        let source = fs::read_to_string(&path)?;
+        let program = parse(&source);
+
+        let formatted = ScillaFormatter::default().format(&program)?;
+        let reparsed = parse(&formatted);
+        assert_eq!(without_positions(&program), without_positions(&reparsed));
+
+        // Formatting is idempotent.
+        assert_eq!(format(&formatted)?, formatted);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_format_layout() -> Result<(), Box<dyn Error>> {
+    let source = r#"scilla_version 0
+library Layout
+let one = Uint32 1
+let add_one = fun (a : Uint32) => let b = builtin add a one in b
+type Error = | NotOwner | Code of Int32
+contract Layout (owner : ByStr20)
+field counter : Uint32 = Uint32 0
+field balances : Map ByStr20 Uint128 = Emp ByStr20 Uint128
+transition Increment (to : ByStr20)
+is_owner = builtin eq owner _sender; match is_owner with | True => c <- counter; c = add_one c; counter := c
+| False => e = {_exception : "NotOwner"}; throw e end end
+"#;
+    let expected = r#"scilla_version 0
+
+library Layout
+
+let one = Uint32 1
+
+let add_one =
+  fun (a : Uint32) =>
+    let b = builtin add a one in
+    b
+
+type Error =
+  | NotOwner
+  | Code of Int32
+
+contract Layout
+(owner : ByStr20)
+
+field counter : Uint32 = Uint32 0
+
+field balances : Map ByStr20 Uint128 = Emp ByStr20 Uint128
+
+transition Increment (to : ByStr20)
+  is_owner = builtin eq owner _sender;
+  match is_owner with
+  | True =>
+    c <- counter;
+    c = add_one c;
+    counter := c
+  | False =>
+    e = {_exception : "NotOwner"};
+    throw e
+  end
+end
+"#;
+    assert_eq!(format(source)?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_format_keeps_comments() -> Result<(), Box<dyn Error>> {
+    let source = r#"(* SPDX-License-Identifier: MIT *)
+scilla_version 0
+library Comments
+let one = Uint32 1 (* the unit *)
+contract Comments ()
+(* @doc The running total *)
+field counter : Uint32 = Uint32 0
+transition Increment ()
+  (* Read the counter *)
+  c <- counter; (* current value *)
+  (* Only owners may pause *)
+  match c with
+  | _ =>
+    (* Nothing to do *)
+    accept
+  end
+end
+(* The end *)
+"#;
+    let expected = r#"(* SPDX-License-Identifier: MIT *)
+scilla_version 0
+
+library Comments
+
+let one = Uint32 1 (* the unit *)
+
+contract Comments
+()
+
+(* @doc The running total *)
+field counter : Uint32 = Uint32 0
+
+transition Increment ()
+  (* Read the counter *)
+  c <- counter; (* current value *)
+  (* Only owners may pause *)
+  match c with
+  | _ =>
+    (* Nothing to do *)
+    accept
+  end
+end
+(* The end *)
+"#;
+    assert_eq!(format(source)?, expected);
+    Ok(())
+}
@@ -1,7 +1,27 @@
 use pretty_assertions::assert_eq;
 use std::{error::Error, path::PathBuf};
 
-use scilla_parser::{Contract, Field, FieldList, Transition, TransitionList, Type};
+use scilla_parser::{
+    AddressKind, Constructor, Contract, Event, Exception, Field, FieldList, Import, LetDefinition,
+    Library, LibraryModule, Message, MessageParam, Procedure, ProcedureList, Transition,
+    TransitionList, Type, TypeDefinition,
+};
+
+/// The type of a function taking all but the last type as arguments, e.g. `Uint64 -> Uint128`.
+fn function(mut types: Vec<Type>) -> Type {
+    let result = types.pop().unwrap();
+    types.into_iter().rev().fold(result, |to, from| {
+        Type::Function(Box::new(from), Box::new(to))
+    })
+}
+
+fn adt(definition: &TypeDefinition) -> Type {
+    Type::Adt {
+        name: definition.name.clone(),
+        args: vec![],
+        constructors: definition.constructors.clone(),
+    }
+}
 
 #[test]
 fn test_parse() -> Result<(), Box<dyn Error>> {
@@ -23,6 +43,8 @@ fn test_map_contract_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![],
             name: "DifferentMaps".to_string(),
             init_params: FieldList::default(),
             fields: FieldList(vec![
@@ -52,7 +74,10 @@ fn test_map_contract_parse() {
                     )))
                 )
             ]),
-            transitions: TransitionList::default()
+            transitions: TransitionList::default(),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: None,
         }
     );
 }
@@ -65,97 +90,74 @@ fn test_bystr_contract_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![],
             name: "AllByStrVariants".to_string(),
             init_params: FieldList(vec![
                 Field::new("bystr", Type::ByStr),
                 Field::new("bystr32", Type::ByStrX(32)),
                 Field::new("raw_address", Type::ByStr20),
-                Field::new(
-                    "library_address",
-                    Type::ByStr20With {
-                        type_name: "library".to_string(),
-                        fields: FieldList::default()
-                    }
-                ),
+                Field::new("library_address", Type::ByStr20With(AddressKind::Library)),
                 Field::new(
                     "contract_address",
-                    Type::ByStr20With {
-                        type_name: "contract".to_string(),
-                        fields: FieldList::default()
-                    }
+                    Type::ByStr20With(AddressKind::Contract(FieldList::default()))
                 ),
                 Field::new(
                     "detailed_contract_address",
-                    Type::ByStr20With {
-                        type_name: "contract".to_string(),
-                        fields: FieldList(vec![
-                            Field::new(
-                                "allowances",
-                                Type::Map(
+                    Type::ByStr20With(AddressKind::Contract(FieldList(vec![
+                        Field::new(
+                            "allowances",
+                            Type::Map(
+                                Box::new(Type::ByStr20),
+                                Box::new(Type::Map(
                                     Box::new(Type::ByStr20),
-                                    Box::new(Type::Map(
-                                        Box::new(Type::ByStr20),
-                                        Box::new(Type::Uint128)
-                                    ))
-                                )
-                            ),
-                            Field::new(
-                                "balances",
-                                Type::Map(Box::new(Type::ByStr20), Box::new(Type::Uint128))
-                            ),
-                            Field::new("total_supply", Type::Uint128)
-                        ])
-                    }
+                                    Box::new(Type::Uint128)
+                                ))
+                            )
+                        ),
+                        Field::new(
+                            "balances",
+                            Type::Map(Box::new(Type::ByStr20), Box::new(Type::Uint128))
+                        ),
+                        Field::new("total_supply", Type::Uint128)
+                    ])))
                 ),
                 Field::new(
                     "complex_contract_address",
-                    Type::ByStr20With {
-                        type_name: "contract".to_string(),
-                        fields: FieldList(vec![
-                            Field::new(
-                                "implementation",
-                                Type::ByStr20With {
-                                    type_name: "contract".to_string(),
-                                    fields: FieldList(vec![
-                                        Field::new(
-                                            "services",
-                                            Type::Map(
-                                                Box::new(Type::String),
-                                                Box::new(Type::ByStr20)
-                                            )
-                                        ),
-                                        Field::new(
-                                            "utility",
-                                            Type::Map(
-                                                Box::new(Type::String),
-                                                Box::new(Type::Uint128)
-                                            )
-                                        ),
-                                    ])
-                                }
-                            ),
-                            Field::new(
-                                "dns",
-                                Type::Map(Box::new(Type::String), Box::new(Type::ByStr20))
-                            ),
-                            Field::new(
-                                "guardians",
-                                Type::Map(
-                                    Box::new(Type::String),
-                                    Box::new(Type::ByStr20With {
-                                        type_name: "contract".to_string(),
-                                        fields: FieldList(vec![Field::new(
-                                            "verification_methods",
-                                            Type::Map(
-                                                Box::new(Type::String),
-                                                Box::new(Type::ByStrX(33))
-                                            )
-                                        ),])
-                                    }),
-                                )
+                    Type::ByStr20With(AddressKind::Contract(FieldList(vec![
+                        Field::new(
+                            "implementation",
+                            Type::ByStr20With(AddressKind::Contract(FieldList(vec![
+                                Field::new(
+                                    "services",
+                                    Type::Map(Box::new(Type::String), Box::new(Type::ByStr20))
+                                ),
+                                Field::new(
+                                    "utility",
+                                    Type::Map(Box::new(Type::String), Box::new(Type::Uint128))
+                                ),
+                            ])))
+                        ),
+                        Field::new(
+                            "dns",
+                            Type::Map(Box::new(Type::String), Box::new(Type::ByStr20))
+                        ),
+                        Field::new(
+                            "guardians",
+                            Type::Map(
+                                Box::new(Type::String),
+                                Box::new(Type::ByStr20With(AddressKind::Contract(FieldList(
+                                    vec![Field::new(
+                                        "verification_methods",
+                                        Type::Map(
+                                            Box::new(Type::String),
+                                            Box::new(Type::ByStrX(33))
+                                        )
+                                    ),]
+                                )))),
                             )
-                        ])
-                    }
+                        )
+                    ])))
                 )
             ]),
             fields: FieldList::default(),
@@ -164,13 +166,10 @@ fn test_bystr_contract_parse() {
                     "ArbitrageFromXCAD",
                     FieldList(vec![Field::new(
                         "token",
-                        Type::ByStr20With {
-                            type_name: "contract".to_string(),
-                            fields: FieldList(vec![Field::new(
-                                "balances",
-                                Type::Map(Box::new(Type::ByStr20), Box::new(Type::Uint128))
-                            ),],),
-                        },
+                        Type::ByStr20With(AddressKind::Contract(FieldList(vec![Field::new(
+                            "balances",
+                            Type::Map(Box::new(Type::ByStr20), Box::new(Type::Uint128))
+                        ),],))),
                     )]),
                 ),
                 Transition::new(
@@ -179,19 +178,21 @@ fn test_bystr_contract_parse() {
                         Field::new("username", Type::String),
                         Field::new(
                             "guardianship",
-                            Type::Option(Box::new(Type::ByStr20With {
-                                type_name: "contract".to_string(),
-                                fields: FieldList(vec![Field::new(
+                            Type::Option(Box::new(Type::ByStr20With(AddressKind::Contract(
+                                FieldList(vec![Field::new(
                                     "verification_methods",
                                     Type::Map(Box::new(Type::String), Box::new(Type::ByStrX(33)))
                                 )])
-                            }))
+                            ))))
                         ),
                         Field::new("id", Type::String),
                         Field::new("tyron", Type::Option(Box::new(Type::Uint128))),
                     ])
                 )
-            ])
+            ]),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: None,
         }
     );
 }
@@ -204,13 +205,27 @@ fn test_timestamp_contract_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![],
             name: "Timestamp".to_string(),
             init_params: FieldList::default(),
             fields: FieldList::default(),
-            transitions: TransitionList(vec![Transition::new(
-                "EventTimestamp",
-                FieldList(vec![Field::new("bnum", Type::BNum)])
-            )])
+            transitions: TransitionList(vec![Transition {
+                events: vec![Event::new(
+                    "TS",
+                    vec![MessageParam::new(
+                        "timestamp",
+                        Some(Type::Option(Box::new(Type::Uint64)))
+                    )]
+                )],
+                ..Transition::new(
+                    "EventTimestamp",
+                    FieldList(vec![Field::new("bnum", Type::BNum)])
+                )
+            }]),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: None,
         }
     );
 }
@@ -219,35 +234,99 @@ fn test_timestamp_contract_parse() {
 fn test_staking_contract_parse() {
     let contract_path = PathBuf::from("tests/contracts/StakingContract.scilla");
     let contract = Contract::parse(&contract_path).unwrap();
+    let denom = TypeDefinition::new(
+        "Denom",
+        vec![
+            Constructor::new("Zil", vec![]),
+            Constructor::new("Token", vec![Type::ByStr20]),
+        ],
+    );
+    let coins = TypeDefinition::new(
+        "Coins",
+        vec![Constructor::new("Coins", vec![adt(&denom), Type::Uint128])],
+    );
+    let reward_param = TypeDefinition::new(
+        "RewardParam",
+        vec![Constructor::new(
+            "RewardParam",
+            vec![Type::Uint128, Type::Uint128, Type::Uint64, Type::Uint64],
+        )],
+    );
+    let stake = TypeDefinition::new(
+        "Stake",
+        vec![Constructor::new(
+            "Stake",
+            vec![Type::Uint128, Type::Uint64, Type::Uint64, Type::Uint128],
+        )],
+    );
+    let reward_result = TypeDefinition::new(
+        "RewardResult",
+        vec![Constructor::new(
+            "RewardResult",
+            vec![Type::Uint128, Type::Uint128],
+        )],
+    );
+    let error = TypeDefinition::new(
+        "Error",
+        vec![
+            Constructor::new("CodeNotOwner", vec![]),
+            Constructor::new("ContractFrozenFailure", vec![]),
+            Constructor::new("Unauthorized", vec![]),
+            Constructor::new("ContractFrozenFailurePaused", vec![]),
+            Constructor::new("ContractFrozenFailureNotPaused", vec![]),
+            Constructor::new("RewardTokenNotFound", vec![]),
+            Constructor::new("NoStakeFoundBySender", vec![]),
+            Constructor::new("AmountGreaterThanStakedAmount", vec![]),
+            Constructor::new("NotAllowedToWithdrawStakeTokens", vec![]),
+            Constructor::new("InvalidBPSError", vec![]),
+            Constructor::new("CodeInsufficientFunds", vec![]),
+        ],
+    );
+    let calculate_rewards_type = Type::Forall(
+        "'A".to_string(),
+        Box::new(function(vec![
+            Type::List(Box::new(adt(&reward_param))),
+            Type::Uint128,
+            Type::Uint64,
+            Type::Uint64,
+            Type::Uint128,
+            Type::Uint256,
+            adt(&reward_result),
+        ])),
+    );
 
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![
+                Import::new("BoolUtils"),
+                Import::new("IntUtils"),
+                Import::new("ListUtils"),
+                Import::new("PairUtils")
+            ],
             name: "StakingContract".to_string(),
             init_params: FieldList(vec![
                 Field::new("initial_owner", Type::ByStr20),
                 Field::new(
                     "initial_staking_token_address",
-                    Type::ByStr20With {
-                        type_name: "contract".to_string(),
-                        fields: FieldList(vec![
-                            Field::new(
-                                "allowances",
-                                Type::Map(
+                    Type::ByStr20With(AddressKind::Contract(FieldList(vec![
+                        Field::new(
+                            "allowances",
+                            Type::Map(
+                                Box::new(Type::ByStr20),
+                                Box::new(Type::Map(
                                     Box::new(Type::ByStr20),
-                                    Box::new(Type::Map(
-                                        Box::new(Type::ByStr20),
-                                        Box::new(Type::Uint128)
-                                    ))
-                                )
-                            ),
-                            Field::new(
-                                "balances",
-                                Type::Map(Box::new(Type::ByStr20), Box::new(Type::Uint128))
-                            ),
-                            Field::new("total_supply", Type::Uint128)
-                        ])
-                    }
+                                    Box::new(Type::Uint128)
+                                ))
+                            )
+                        ),
+                        Field::new(
+                            "balances",
+                            Type::Map(Box::new(Type::ByStr20), Box::new(Type::Uint128))
+                        ),
+                        Field::new("total_supply", Type::Uint128)
+                    ])))
                 )
             ]),
             fields: FieldList(vec![
@@ -259,15 +338,12 @@ fn test_staking_contract_parse() {
                     "reward_pairs",
                     Type::Map(
                         Box::new(Type::ByStr20),
-                        Box::new(Type::List(Box::new(Type::Other("RewardParam".to_string()))))
+                        Box::new(Type::List(Box::new(adt(&reward_param))))
                     )
                 ),
                 Field::new(
                     "stakes",
-                    Type::Map(
-                        Box::new(Type::ByStr20),
-                        Box::new(Type::Other("Stake".to_string()))
-                    )
+                    Type::Map(Box::new(Type::ByStr20), Box::new(adt(&stake)))
                 ),
                 Field::new(
                     "rewards",
@@ -289,19 +365,187 @@ fn test_staking_contract_parse() {
                 Field::new("total_staked_amount", Type::Uint128),
             ]),
             transitions: TransitionList(vec![
-                Transition::new(
-                    "AddStake",
-                    FieldList(vec![
-                        Field::new("amount", Type::Uint128),
-                        Field::new("expiration_time", Type::Uint64),
-                        Field::new("penalty_fee_bps", Type::Uint128)
-                    ])
-                ),
-                Transition::new(
-                    "RemoveStake",
-                    FieldList(vec![Field::new("amount", Type::Uint128,),],)
-                ),
-                Transition::new("ClaimRewards", FieldList::default()),
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "UpdateRewardsAmountWithPreviousValueSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("old_amount", Some(Type::Uint128)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                                MessageParam::new("difference", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "UpdateRewardsAmountFirstClaimSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "UpdateTreasuryFeeAmountWithPreviousValueSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("old_amount", Some(Type::Uint128)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                                MessageParam::new("difference", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    messages: vec![
+                        Message::new(
+                            "AddFunds",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "Transfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("to", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "TransferFrom",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("from", Some(Type::ByStr20)),
+                                MessageParam::new("to", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "AddStake",
+                        FieldList(vec![
+                            Field::new("amount", Type::Uint128),
+                            Field::new("expiration_time", Type::Uint64),
+                            Field::new("penalty_fee_bps", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "UpdateRewardsAmountWithPreviousValueSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("old_amount", Some(Type::Uint128)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                                MessageParam::new("difference", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "UpdateRewardsAmountFirstClaimSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "UpdateTreasuryFeeAmountWithPreviousValueSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("old_amount", Some(Type::Uint128)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                                MessageParam::new("difference", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    messages: vec![
+                        Message::new(
+                            "AddFunds",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "Transfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("to", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "RemoveStake",
+                        FieldList(vec![Field::new("amount", Type::Uint128,)],)
+                    )
+                },
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "UpdateRewardsAmountWithPreviousValueSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("old_amount", Some(Type::Uint128)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                                MessageParam::new("difference", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "UpdateRewardsAmountFirstClaimSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "UpdateTreasuryFeeAmountWithPreviousValueSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("old_amount", Some(Type::Uint128)),
+                                MessageParam::new("new_amount", Some(Type::Uint128)),
+                                MessageParam::new("difference", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "WithdrawRewardsSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("reward_token", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    messages: vec![
+                        Message::new(
+                            "AddFunds",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "Transfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("to", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new("ClaimRewards", FieldList::default())
+                },
                 Transition::new(
                     "AddRewardToken",
                     FieldList(vec![
@@ -317,37 +561,129 @@ fn test_staking_contract_parse() {
                 Transition::new("RemoveAllRewardTokens", FieldList::default()),
                 Transition::new("Pause", FieldList::default()),
                 Transition::new("UnPause", FieldList::default()),
-                Transition::new(
-                    "AddAdmin",
-                    FieldList(vec![Field::new("address", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "RemoveAdmin",
-                    FieldList(vec![Field::new("address", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "TransferOwnership",
-                    FieldList(vec![Field::new("new_owner", Type::ByStr20)])
-                ),
-                Transition::new("AcceptPendingOwnership", FieldList::default()),
-                Transition::new(
-                    "WithdrawTokens",
-                    FieldList(vec![
-                        Field::new("token_address", Type::ByStr20),
-                        Field::new("token_amount", Type::Uint128)
-                    ])
-                ),
-                Transition::new(
-                    "WithdrawZils",
-                    FieldList(vec![Field::new("zil_amount", Type::Uint128),])
-                ),
-                Transition::new(
-                    "Deposit",
-                    FieldList(vec![
-                        Field::new("token_address", Type::ByStr20),
-                        Field::new("token_amount", Type::Uint128)
-                    ])
-                ),
+                Transition {
+                    events: vec![Event::new(
+                        "AddAdminSuccess",
+                        vec![MessageParam::new("addressAdded", Some(Type::ByStr20))]
+                    )],
+                    ..Transition::new(
+                        "AddAdmin",
+                        FieldList(vec![Field::new("address", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "RemoveAdminSuccess",
+                        vec![MessageParam::new("address", Some(Type::ByStr20))]
+                    )],
+                    ..Transition::new(
+                        "RemoveAdmin",
+                        FieldList(vec![Field::new("address", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    exceptions: vec![Exception::new("ExistingOwner", vec![])],
+                    ..Transition::new(
+                        "TransferOwnership",
+                        FieldList(vec![Field::new("new_owner", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "OwnershipTransferred",
+                        vec![MessageParam::new("owner", Some(Type::ByStr20))]
+                    )],
+                    exceptions: vec![Exception::new("InvalidSender", vec![])],
+                    ..Transition::new("AcceptPendingOwnership", FieldList::default())
+                },
+                Transition {
+                    messages: vec![
+                        Message::new(
+                            "AddFunds",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "Transfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("to", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "WithdrawTokens",
+                        FieldList(vec![
+                            Field::new("token_address", Type::ByStr20),
+                            Field::new("token_amount", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![
+                        Message::new(
+                            "AddFunds",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "Transfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("to", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "WithdrawZils",
+                        FieldList(vec![Field::new("zil_amount", Type::Uint128)])
+                    )
+                },
+                Transition {
+                    messages: vec![
+                        Message::new(
+                            "AddFunds",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "Transfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("to", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "TransferFrom",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("from", Some(Type::ByStr20)),
+                                MessageParam::new("to", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "Deposit",
+                        FieldList(vec![
+                            Field::new("token_address", Type::ByStr20),
+                            Field::new("token_amount", Type::Uint128)
+                        ])
+                    )
+                },
                 Transition::new(
                     "TransferFromSuccessCallBack",
                     FieldList(vec![
@@ -365,15 +701,18 @@ fn test_staking_contract_parse() {
                         Field::new("amount", Type::Uint128)
                     ])
                 ),
-                Transition::new(
-                    "RecipientAcceptTransferFrom",
-                    FieldList(vec![
-                        Field::new("initiator", Type::ByStr20),
-                        Field::new("sender", Type::ByStr20),
-                        Field::new("recipient", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
-                ),
+                Transition {
+                    exceptions: vec![Exception::new("InvalidInvocation", vec![])],
+                    ..Transition::new(
+                        "RecipientAcceptTransferFrom",
+                        FieldList(vec![
+                            Field::new("initiator", Type::ByStr20),
+                            Field::new("sender", Type::ByStr20),
+                            Field::new("recipient", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
                 Transition::new("AddFunds", FieldList::default()),
                 Transition::new(
                     "RecipientAcceptTransfer",
@@ -383,7 +722,204 @@ fn test_staking_contract_parse() {
                         Field::new("amount", Type::Uint128)
                     ])
                 ),
-            ])
+            ]),
+            procedures: ProcedureList(vec![
+                Procedure::new("Throw", FieldList(vec![Field::new("err", adt(&error))]),),
+                Procedure::new_without_param("EnsureContractIsNotPaused"),
+                Procedure::new_without_param("EnsureContractIsPaused"),
+                Procedure::new(
+                    "EnsureSenderIsOwner",
+                    FieldList(vec![Field::new("initiator", Type::ByStr20)]),
+                ),
+                Procedure::new(
+                    "EnsureSenderIsAdminOrOwner",
+                    FieldList(vec![Field::new("address", Type::ByStr20)]),
+                ),
+                Procedure::new(
+                    "EnsureNotStakeToken",
+                    FieldList(vec![Field::new("address", Type::ByStr20)]),
+                ),
+                Procedure::new(
+                    "RequireValidBPS",
+                    FieldList(vec![Field::new("val_bps", Type::Uint128)]),
+                ),
+                Procedure::new(
+                    "Send",
+                    FieldList(vec![
+                        Field::new("coins", adt(&coins)),
+                        Field::new("to_address", Type::ByStr20),
+                    ]),
+                ),
+                Procedure::new(
+                    "Receive",
+                    FieldList(vec![
+                        Field::new("coins", adt(&coins)),
+                        Field::new("initiator", Type::ByStr20),
+                    ]),
+                ),
+                Procedure::new(
+                    "UpdateTreasuryFeeAmount",
+                    FieldList(vec![
+                        Field::new("reward_token", Type::ByStr20),
+                        Field::new("amount", Type::Uint128),
+                    ]),
+                ),
+                Procedure::new(
+                    "UpdateRewardsAmount",
+                    FieldList(vec![
+                        Field::new("reward_token", Type::ByStr20),
+                        Field::new("amount", Type::Uint128),
+                    ]),
+                ),
+                Procedure::new(
+                    "UpdateRewards",
+                    FieldList(vec![
+                        Field::new("reward_token", Type::ByStr20),
+                        Field::new("reward_res", adt(&reward_result)),
+                    ]),
+                ),
+                Procedure::new(
+                    "DoClaimRewards",
+                    FieldList(vec![Field::new(
+                        "reward_pair",
+                        Type::Pair(
+                            Box::new(Type::ByStr20),
+                            Box::new(Type::List(Box::new(adt(&reward_param)))),
+                        ),
+                    )]),
+                ),
+                Procedure::new(
+                    "DoRemoveRewardToken",
+                    FieldList(vec![Field::new("reward_token_address", Type::ByStr20)]),
+                ),
+                Procedure::new(
+                    "DoRemoveAllRewardTokens",
+                    FieldList(vec![Field::new(
+                        "reward_pair",
+                        Type::Pair(
+                            Box::new(Type::ByStr20),
+                            Box::new(Type::List(Box::new(adt(&reward_param)))),
+                        ),
+                    )]),
+                ),
+                Procedure::new(
+                    "DoWithdrawRewards",
+                    FieldList(vec![Field::new(
+                        "reward_pair",
+                        Type::Pair(
+                            Box::new(Type::ByStr20),
+                            Box::new(Type::List(Box::new(adt(&reward_param)))),
+                        ),
+                    )]),
+                ),
+            ]),
+            constraint: None,
+            library: Some(Library {
+                name: "StakingContract".to_string(),
+                let_definitions: vec![
+                    LetDefinition::new("fee_denom", None),
+                    LetDefinition::new("zil_address", None),
+                    LetDefinition::new("bool_active", None),
+                    LetDefinition::new("bool_inactive", None),
+                    LetDefinition::new("zil", None),
+                    LetDefinition::new("zero", None),
+                    LetDefinition::new("zero_i256", None),
+                    LetDefinition::new("min_bps", None),
+                    LetDefinition::new("max_bps", None),
+                    LetDefinition::new("seconds_per_year", None),
+                    LetDefinition::new("true", None),
+                    LetDefinition::new("one_msg", None),
+                    LetDefinition::new("make_error", None),
+                    LetDefinition::new("get_value", None),
+                    LetDefinition::new(
+                        "uint64_to_uint128",
+                        Some(function(vec![Type::Uint64, Type::Uint128]))
+                    ),
+                    LetDefinition::new(
+                        "int256_to_uint128",
+                        Some(function(vec![Type::Int256, Type::Uint128]))
+                    ),
+                    LetDefinition::new(
+                        "is_admin_or_owner",
+                        Some(function(vec![
+                            Type::Map(Box::new(Type::ByStr20), Box::new(Type::Bool)),
+                            Type::ByStr20,
+                            Type::ByStr20,
+                            Type::Bool
+                        ]))
+                    ),
+                    LetDefinition::new(
+                        "update_reward_param",
+                        Some(function(vec![
+                            adt(&reward_param),
+                            Type::Uint64,
+                            adt(&reward_param)
+                        ]))
+                    ),
+                    LetDefinition::new(
+                        "update_all_reward_params",
+                        Some(Type::Forall(
+                            "'A".to_string(),
+                            Box::new(function(vec![
+                                Type::List(Box::new(adt(&reward_param))),
+                                Type::Uint64,
+                                Type::List(Box::new(adt(&reward_param)))
+                            ]))
+                        ))
+                    ),
+                    LetDefinition::new(
+                        "is_timestamp_let",
+                        Some(function(vec![Type::Uint64, Type::Uint64, Type::Bool]))
+                    ),
+                    LetDefinition::new(
+                        "get_time_difference",
+                        Some(function(vec![
+                            Type::Uint128,
+                            Type::Uint64,
+                            adt(&reward_param),
+                            Type::Uint64,
+                            Type::Uint64
+                        ]))
+                    ),
+                    LetDefinition::new(
+                        "uint128_to_uint256",
+                        Some(function(vec![Type::Uint128, Type::Uint256]))
+                    ),
+                    LetDefinition::new(
+                        "muldiv",
+                        Some(function(vec![
+                            Type::Uint128,
+                            Type::Uint128,
+                            Type::Uint128,
+                            Type::Uint128,
+                            Type::Uint256,
+                            Type::Uint128
+                        ]))
+                    ),
+                    LetDefinition::new(
+                        "calculate_treasury_fee_amt",
+                        Some(function(vec![
+                            Type::Uint128,
+                            Type::Uint256,
+                            Type::Uint128,
+                            Type::Uint128
+                        ]))
+                    ),
+                    LetDefinition::new("calculate_rewards", Some(calculate_rewards_type)),
+                    LetDefinition::new("portion", None),
+                    LetDefinition::new("option_value", None),
+                    LetDefinition::new("option_timestamp", None),
+                    LetDefinition::new("get_prev_bnum", None),
+                ],
+                type_definitions: vec![
+                    denom.clone(),
+                    coins.clone(),
+                    reward_param.clone(),
+                    stake.clone(),
+                    reward_result.clone(),
+                    error.clone(),
+                ]
+            }),
         }
     );
 }
@@ -396,13 +932,21 @@ fn test_chain_id_contract_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![],
             name: "ChainId".to_string(),
             fields: FieldList(vec![Field::new("dummy_field", Type::Uint256)]),
             init_params: FieldList::default(),
-            transitions: TransitionList(vec![Transition::new(
-                "EventChainID",
-                FieldList::default()
-            )])
+            transitions: TransitionList(vec![Transition {
+                events: vec![Event::new(
+                    "ChainID",
+                    vec![MessageParam::new("chain_id", Some(Type::Uint32))]
+                )],
+                ..Transition::new("EventChainID", FieldList::default())
+            }]),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: None,
         }
     );
 }
@@ -415,13 +959,37 @@ fn test_hello_world_contract_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![Import::new("ListUtils")],
             name: "HelloWorld".to_string(),
             init_params: FieldList(vec![Field::new("owner", Type::ByStr20)]),
             fields: FieldList(vec![Field::new("welcome_msg", Type::String)]),
             transitions: TransitionList(vec![
-                Transition::new("setHello", FieldList(vec![Field::new("msg", Type::String)])),
-                Transition::new_without_param("getHello")
-            ])
+                Transition {
+                    events: vec![Event::new(
+                        "setHello()",
+                        vec![MessageParam::new("code", Some(Type::Int32))]
+                    )],
+                    ..Transition::new("setHello", FieldList(vec![Field::new("msg", Type::String)]))
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "getHello()",
+                        vec![MessageParam::new("msg", Some(Type::String))]
+                    )],
+                    ..Transition::new_without_param("getHello")
+                }
+            ]),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: Some(Library {
+                name: "HelloWorld".to_string(),
+                let_definitions: vec![
+                    LetDefinition::new("not_owner_code", None),
+                    LetDefinition::new("set_hello_code", None),
+                ],
+                type_definitions: vec![]
+            }),
         }
     );
 }
@@ -434,6 +1002,8 @@ fn test_get_fields_contract_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![],
             name: "GetFields".to_string(),
             init_params: FieldList::default(),
             fields: FieldList(vec![
@@ -473,6 +1043,9 @@ fn test_get_fields_contract_parse() {
                 Field::new("field_list", Type::List(Box::new(Type::Int32)))
             ]),
             transitions: TransitionList::default(),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: None,
         }
     );
 }
@@ -484,6 +1057,8 @@ fn test_call_transition_contract_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![],
             name: "CallTransition".to_string(),
             init_params: FieldList::default(),
             fields: FieldList::default(),
@@ -587,7 +1162,10 @@ fn test_call_transition_contract_parse() {
                         )))
                     )])
                 ),
-            ])
+            ]),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: None,
         }
     );
 }
@@ -600,6 +1178,8 @@ fn test_send_zil_contract_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![],
             name: "SendZil".to_string(),
             init_params: FieldList::default(),
             fields: FieldList(vec![
@@ -614,42 +1194,94 @@ fn test_send_zil_contract_parse() {
                 Field::new("list", Type::List(Box::new(Type::Int32))),
             ]),
             transitions: TransitionList(vec![
-                Transition::new_without_param("acceptZil"),
+                Transition {
+                    events: vec![Event::new(
+                        "currentBalance",
+                        vec![MessageParam::new("value", Some(Type::Uint128))]
+                    )],
+                    ..Transition::new_without_param("acceptZil")
+                },
                 Transition::new(
                     "updateTestField",
                     FieldList(vec![Field::new("val", Type::Uint256)])
                 ),
-                Transition::new_without_param("dontAcceptZil"),
-                Transition::new(
-                    "fundUserWithTag",
-                    FieldList(vec![
-                        Field::new("user", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
-                ),
-                Transition::new(
-                    "fundUser",
-                    FieldList(vec![
-                        Field::new("user", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
-                ),
-                Transition::new(
-                    "fundContract",
-                    FieldList(vec![
-                        Field::new("contract_address", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
-                ),
-                Transition::new(
-                    "callOtherContract",
-                    FieldList(vec![
-                        Field::new("contract_address", Type::ByStr20),
-                        Field::new("tag", Type::String),
-                        Field::new("value", Type::Uint256)
-                    ])
-                ),
-            ])
+                Transition {
+                    events: vec![Event::new(
+                        "currentBalance",
+                        vec![MessageParam::new("value", Some(Type::Uint128))]
+                    )],
+                    ..Transition::new_without_param("dontAcceptZil")
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "AddFunds",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "fundUserWithTag",
+                        FieldList(vec![
+                            Field::new("user", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "fundUser",
+                        FieldList(vec![
+                            Field::new("user", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "acceptZil",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "fundContract",
+                        FieldList(vec![
+                            Field::new("contract_address", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![Message {
+                        tag: None,
+                        params: vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("val", Some(Type::Uint256)),
+                        ]
+                    }],
+                    ..Transition::new(
+                        "callOtherContract",
+                        FieldList(vec![
+                            Field::new("contract_address", Type::ByStr20),
+                            Field::new("tag", Type::String),
+                            Field::new("value", Type::Uint256)
+                        ])
+                    )
+                },
+            ]),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: None,
         }
     );
 }
@@ -658,9 +1290,20 @@ fn test_send_zil_contract_parse() {
 fn test_fungible_token_parse() {
     let contract_path = PathBuf::from("tests/contracts/FungibleToken.scilla");
     let contract = Contract::parse(&contract_path).unwrap();
+    let error = TypeDefinition::new(
+        "Error",
+        vec![
+            Constructor::new("CodeIsSender", vec![]),
+            Constructor::new("CodeInsufficientFunds", vec![]),
+            Constructor::new("CodeInsufficientAllowance", vec![]),
+        ],
+    );
+    let unit = TypeDefinition::new("Unit", vec![Constructor::new("Unit", vec![])]);
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![Import::new("IntUtils")],
             name: "FungibleToken".to_string(),
             init_params: FieldList(vec![
                 Field::new("contract_owner", Type::ByStr20),
@@ -684,43 +1327,194 @@ fn test_fungible_token_parse() {
                 )
             ]),
             transitions: TransitionList(vec![
-                Transition::new(
-                    "IncreaseAllowance",
-                    FieldList(vec![
-                        Field::new("spender", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
-                ),
-                Transition::new(
-                    "DecreaseAllowance",
-                    FieldList(vec![
-                        Field::new("spender", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
-                ),
-                Transition::new(
-                    "Transfer",
-                    FieldList(vec![
-                        Field::new("to", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
+                Transition {
+                    events: vec![Event::new(
+                        "IncreasedAllowance",
+                        vec![
+                            MessageParam::new("token_owner", Some(Type::ByStr20)),
+                            MessageParam::new("spender", Some(Type::ByStr20)),
+                            MessageParam::new("new_allowance", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "IncreaseAllowance",
+                        FieldList(vec![
+                            Field::new("spender", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "DecreasedAllowance",
+                        vec![
+                            MessageParam::new("token_owner", Some(Type::ByStr20)),
+                            MessageParam::new("spender", Some(Type::ByStr20)),
+                            MessageParam::new("new_allowance", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "DecreaseAllowance",
+                        FieldList(vec![
+                            Field::new("spender", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "TransferSuccess",
+                        vec![
+                            MessageParam::new("sender", Some(Type::ByStr20)),
+                            MessageParam::new("recipient", Some(Type::ByStr20)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    messages: vec![
+                        Message::new(
+                            "RecipientAcceptTransfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "TransferSuccessCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "Transfer",
+                        FieldList(vec![
+                            Field::new("to", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "TransferSuccess",
+                        vec![
+                            MessageParam::new("sender", Some(Type::ByStr20)),
+                            MessageParam::new("recipient", Some(Type::ByStr20)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    messages: vec![
+                        Message::new(
+                            "RecipientAcceptTransfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                                MessageParam::new("_EvmCall", Some(Type::String)),
+                            ]
+                        ),
+                        Message::new(
+                            "TransferSuccessCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                                MessageParam::new("_EvmCall", Some(Type::String)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "TransferFailed",
+                        FieldList(vec![
+                            Field::new("to", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "TransferFromSuccess",
+                        vec![
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                            MessageParam::new("sender", Some(Type::ByStr20)),
+                            MessageParam::new("recipient", Some(Type::ByStr20)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    messages: vec![
+                        Message::new(
+                            "RecipientAcceptTransferFrom",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("initiator", Some(Type::ByStr20)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "TransferFromSuccessCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("initiator", Some(Type::ByStr20)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "TransferFrom",
+                        FieldList(vec![
+                            Field::new("from", Type::ByStr20),
+                            Field::new("to", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ])
+                    )
+                },
+            ]),
+            procedures: ProcedureList(vec![
+                Procedure::new(
+                    "ThrowError",
+                    FieldList(vec![Field::new("err", adt(&error))]),
                 ),
-                Transition::new(
-                    "TransferFailed",
-                    FieldList(vec![
-                        Field::new("to", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
+                Procedure::new(
+                    "IsNotSender",
+                    FieldList(vec![Field::new("address", Type::ByStr20)]),
                 ),
-                Transition::new(
-                    "TransferFrom",
+                Procedure::new(
+                    "AuthorizedMoveIfSufficientBalance",
                     FieldList(vec![
                         Field::new("from", Type::ByStr20),
                         Field::new("to", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ])
+                        Field::new("amount", Type::Uint128),
+                    ]),
                 ),
-            ])
+            ]),
+            constraint: None,
+            library: Some(Library {
+                name: "FungibleToken".to_string(),
+                let_definitions: vec![
+                    LetDefinition::new("one_msg", None),
+                    LetDefinition::new("two_msgs", None),
+                    LetDefinition::new("make_error", None),
+                    LetDefinition::new("zero", None),
+                    LetDefinition::new("get_val", None),
+                ],
+                type_definitions: vec![error.clone(), unit.clone(),]
+            }),
         }
     );
 }
@@ -732,6 +1526,8 @@ fn test_staking_proxy_v2_parse() {
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![],
             name: "SSNListProxy_V2".to_string(),
             init_params: FieldList(vec![
                 Field::new("init_implementation", Type::ByStr20),
@@ -743,281 +1539,825 @@ fn test_staking_proxy_v2_parse() {
                 Field::new("stagingadmin", Type::Option(Box::new(Type::ByStr20))),
             ]),
             transitions: TransitionList(vec![
-                Transition::new(
-                    "UpgradeTo",
-                    FieldList(vec![Field::new("newImplementation", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "ChangeProxyAdmin",
-                    FieldList(vec![Field::new("newAdmin", Type::ByStr20)])
-                ),
-                Transition::new_without_param("ClaimProxyAdmin"),
-                Transition::new(
-                    "OptInSSNToConsensusPoolAdminOverride",
-                    FieldList(vec![Field::new("ssnaddr", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "OptOutSSNFromConsensusPoolAdminOverride",
-                    FieldList(vec![Field::new("ssnaddr", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "RemoveFromConsensusPoolAdminOverride",
-                    FieldList(vec![Field::new("ssnaddr", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "ChangeMinCommissionRate",
-                    FieldList(vec![Field::new("mincommrate_value", Type::Uint128)])
-                ),
-                Transition::new(
-                    "AddSSNNonStaking",
-                    FieldList(vec![
-                        Field::new("ssnaddr", Type::ByStr20),
-                        Field::new("name", Type::String),
-                        Field::new("urlraw", Type::String),
-                        Field::new("urlapi", Type::String),
-                        Field::new("comm", Type::Uint128)
-                    ])
-                ),
-                Transition::new_without_param("AddSSNToConsensusPool"),
-                Transition::new_without_param("RemoveSSNFromConsensusPool"),
-                Transition::new(
-                    "WithdrawStakeRewardsForCycles",
-                    FieldList(vec![
-                        Field::new("ssnaddr", Type::ByStr20),
-                        Field::new("cycles", Type::Uint32)
-                    ])
-                ),
-                Transition::new(
-                    "CopySSNDelegAmt",
-                    FieldList(vec![
-                        Field::new("ssn", Type::ByStr20),
-                        Field::new(
-                            "keys",
-                            Type::List(Box::new(Type::Pair(
-                                Box::new(Type::ByStr20),
-                                Box::new(Type::Uint128)
-                            )))
-                        )
-                    ])
-                ),
-                Transition::new(
-                    "MigrateStakeSSNPerCycle",
-                    FieldList(vec![
-                        Field::new("ssn", Type::ByStr20),
-                        Field::new(
-                            "keys",
-                            Type::List(Box::new(Type::Pair(
-                                Box::new(Type::Uint32),
-                                Box::new(Type::Pair(
-                                    Box::new(Type::Uint128),
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "Upgraded",
+                            vec![MessageParam::new(
+                                "implementation_address",
+                                Some(Type::ByStr20)
+                            )]
+                        ),
+                        Event::new(
+                            "upgradeTo FailedNotAdmin",
+                            vec![MessageParam::new("newImplementation", Some(Type::ByStr20))]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "UpgradeTo",
+                        FieldList(vec![Field::new("newImplementation", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "ChangeProxyAdmin",
+                            vec![
+                                MessageParam::new("oldAdmin", Some(Type::ByStr20)),
+                                MessageParam::new("newAdmin", Some(Type::ByStr20)),
+                            ]
+                        ),
+                        Event::new(
+                            "ChangeProxyAdmin FailedNotAdmin",
+                            vec![MessageParam::new("newAdmin", Some(Type::ByStr20))]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "ChangeProxyAdmin",
+                        FieldList(vec![Field::new("newAdmin", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "ClaimProxyAdmin",
+                            vec![MessageParam::new("newAdmin", Some(Type::ByStr20))]
+                        ),
+                        Event::new(
+                            "ClaimProxyAdmin FailedNotStagingadmin",
+                            vec![MessageParam::new("newAdmin", Some(Type::ByStr20))]
+                        ),
+                        Event::new("ClaimProxyAdmin FailedNoStagingadmin", vec![]),
+                    ],
+                    ..Transition::new_without_param("ClaimProxyAdmin")
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "OptInSSNToConsensusPoolAdminOverride",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "OptInSSNToConsensusPoolAdminOverride",
+                        FieldList(vec![Field::new("ssnaddr", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "OptOutSSNFromConsensusPoolAdminOverride",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "OptOutSSNFromConsensusPoolAdminOverride",
+                        FieldList(vec![Field::new("ssnaddr", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "RemoveFromConsensusPoolAdminOverride",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "RemoveFromConsensusPoolAdminOverride",
+                        FieldList(vec![Field::new("ssnaddr", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeMinCommissionRate",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("mincommrate_value", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeMinCommissionRate",
+                        FieldList(vec![Field::new("mincommrate_value", Type::Uint128)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "AddSSNNonStaking",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                            MessageParam::new("name", Some(Type::String)),
+                            MessageParam::new("urlraw", Some(Type::String)),
+                            MessageParam::new("urlapi", Some(Type::String)),
+                            MessageParam::new("comm", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "AddSSNNonStaking",
+                        FieldList(vec![
+                            Field::new("ssnaddr", Type::ByStr20),
+                            Field::new("name", Type::String),
+                            Field::new("urlraw", Type::String),
+                            Field::new("urlapi", Type::String),
+                            Field::new("comm", Type::Uint128)
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "AddSSNToConsensusPool",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new_without_param("AddSSNToConsensusPool")
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "RemoveSSNFromConsensusPool",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new_without_param("RemoveSSNFromConsensusPool")
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "WithdrawStakeRewardsForCycles",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                            MessageParam::new("cycles", Some(Type::Uint32)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "WithdrawStakeRewardsForCycles",
+                        FieldList(vec![
+                            Field::new("ssnaddr", Type::ByStr20),
+                            Field::new("cycles", Type::Uint32)
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopySSNDelegAmt",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssn", Some(Type::ByStr20)),
+                            MessageParam::new(
+                                "keys",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
                                     Box::new(Type::Uint128)
-                                ))
-                            )))
-                        )
-                    ])
-                ),
-                Transition::new(
-                    "CopyBuffDepositDeleg",
-                    FieldList(vec![
-                        Field::new("deleg", Type::ByStr20),
-                        Field::new(
-                            "keys",
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopySSNDelegAmt",
+                        FieldList(vec![
+                            Field::new("ssn", Type::ByStr20),
+                            Field::new(
+                                "keys",
+                                Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::Uint128)
+                                )))
+                            )
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "MigrateStakeSSNPerCycle",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssn", Some(Type::ByStr20)),
+                            MessageParam::new(
+                                "keys",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::Uint32),
+                                    Box::new(Type::Pair(
+                                        Box::new(Type::Uint128),
+                                        Box::new(Type::Uint128)
+                                    ))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "MigrateStakeSSNPerCycle",
+                        FieldList(vec![
+                            Field::new("ssn", Type::ByStr20),
+                            Field::new(
+                                "keys",
+                                Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::Uint32),
+                                    Box::new(Type::Pair(
+                                        Box::new(Type::Uint128),
+                                        Box::new(Type::Uint128)
+                                    ))
+                                )))
+                            )
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyBuffDepositDeleg",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("deleg", Some(Type::ByStr20)),
+                            MessageParam::new(
+                                "keys",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::Uint32),
+                                        Box::new(Type::Uint128)
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyBuffDepositDeleg",
+                        FieldList(vec![
+                            Field::new("deleg", Type::ByStr20),
+                            Field::new(
+                                "keys",
+                                Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::Uint32),
+                                        Box::new(Type::Uint128)
+                                    ))))
+                                )))
+                            )
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyLastBufDepositCycleDelegList",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "last_buf_deposit_cycle_deleg_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::ByStr20),
+                                        Box::new(Type::Uint32)
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyLastBufDepositCycleDelegList",
+                        FieldList(vec![Field::new(
+                            "last_buf_deposit_cycle_deleg_list",
                             Type::List(Box::new(Type::Pair(
                                 Box::new(Type::ByStr20),
                                 Box::new(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
                                     Box::new(Type::Uint32),
-                                    Box::new(Type::Uint128)
                                 ))))
                             )))
-                        )
-                    ])
-                ),
-                Transition::new(
-                    "CopyLastBufDepositCycleDelegList",
-                    FieldList(vec![Field::new(
-                        "last_buf_deposit_cycle_deleg_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::List(Box::new(Type::Pair(
-                                Box::new(Type::ByStr20),
-                                Box::new(Type::Uint32),
-                            ))))
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "CopyLastWithdrawCycleDelegList",
-                    FieldList(vec![Field::new(
-                        "last_withdraw_cycle_deleg_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::List(Box::new(Type::Pair(
-                                Box::new(Type::ByStr20),
-                                Box::new(Type::Uint32),
-                            ))))
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "CopyDelegStakePerCycleList",
-                    FieldList(vec![Field::new(
-                        "deleg_stake_per_cycle_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::List(Box::new(Type::Pair(
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyLastWithdrawCycleDelegList",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "last_withdraw_cycle_deleg_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::ByStr20),
+                                        Box::new(Type::Uint32)
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyLastWithdrawCycleDelegList",
+                        FieldList(vec![Field::new(
+                            "last_withdraw_cycle_deleg_list",
+                            Type::List(Box::new(Type::Pair(
                                 Box::new(Type::ByStr20),
                                 Box::new(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
                                     Box::new(Type::Uint32),
-                                    Box::new(Type::Uint128)
-                                )))),
-                            ))))
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "CopyDirectDepositDelegList",
-                    FieldList(vec![Field::new(
-                        "direct_deposit_deleg_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::List(Box::new(Type::Pair(
+                                ))))
+                            )))
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyDelegStakePerCycleList",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "deleg_stake_per_cycle_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::ByStr20),
+                                        Box::new(Type::List(Box::new(Type::Pair(
+                                            Box::new(Type::Uint32),
+                                            Box::new(Type::Uint128)
+                                        ))))
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyDelegStakePerCycleList",
+                        FieldList(vec![Field::new(
+                            "deleg_stake_per_cycle_list",
+                            Type::List(Box::new(Type::Pair(
                                 Box::new(Type::ByStr20),
                                 Box::new(Type::List(Box::new(Type::Pair(
-                                    Box::new(Type::Uint32),
-                                    Box::new(Type::Uint128)
-                                )))),
-                            ))))
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "CopyBuffDepositDelegList",
-                    FieldList(vec![Field::new(
-                        "buff_deposit_deleg_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::Uint32),
+                                        Box::new(Type::Uint128)
+                                    )))),
+                                ))))
+                            )))
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyDirectDepositDelegList",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "direct_deposit_deleg_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::ByStr20),
+                                        Box::new(Type::List(Box::new(Type::Pair(
+                                            Box::new(Type::Uint32),
+                                            Box::new(Type::Uint128)
+                                        ))))
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyDirectDepositDelegList",
+                        FieldList(vec![Field::new(
+                            "direct_deposit_deleg_list",
+                            Type::List(Box::new(Type::Pair(
                                 Box::new(Type::ByStr20),
                                 Box::new(Type::List(Box::new(Type::Pair(
-                                    Box::new(Type::Uint32),
-                                    Box::new(Type::Uint128)
-                                )))),
-                            ))))
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "CopyDepositAmtDelegList",
-                    FieldList(vec![Field::new(
-                        "deposit_amt_deleg_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::Uint32),
+                                        Box::new(Type::Uint128)
+                                    )))),
+                                ))))
+                            )))
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyBuffDepositDelegList",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "buff_deposit_deleg_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::ByStr20),
+                                        Box::new(Type::List(Box::new(Type::Pair(
+                                            Box::new(Type::Uint32),
+                                            Box::new(Type::Uint128)
+                                        ))))
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyBuffDepositDelegList",
+                        FieldList(vec![Field::new(
+                            "buff_deposit_deleg_list",
+                            Type::List(Box::new(Type::Pair(
                                 Box::new(Type::ByStr20),
-                                Box::new(Type::Uint128)
-                            ))))
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "CopyWithDrawalPendingList",
-                    FieldList(vec![Field::new(
-                        "withdrawal_pending_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::List(Box::new(Type::Pair(
-                                Box::new(Type::BNum),
-                                Box::new(Type::Uint128)
-                            ))))
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "CopyCommForSSNList",
-                    FieldList(vec![Field::new(
-                        "comm_for_ssn_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::List(Box::new(Type::Pair(
-                                Box::new(Type::Uint32),
-                                Box::new(Type::Uint128)
-                            ))))
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "CopyDelegSwapRequest",
-                    FieldList(vec![Field::new(
-                        "deleg_swap_request_list",
-                        Type::List(Box::new(Type::Pair(
-                            Box::new(Type::ByStr20),
-                            Box::new(Type::ByStr20),
-                        )))
-                    )])
-                ),
-                Transition::new(
-                    "ChangeCycleRewardsDeleg",
-                    FieldList(vec![Field::new("input_cycle_rewards_deleg", Type::Uint128)])
-                ),
-                Transition::new(
-                    "ChangeVerifierReward",
-                    FieldList(vec![Field::new("input_verifier_reward", Type::Uint128)])
-                ),
-                Transition::new(
-                    "ChangeAvailableWithdrawal",
-                    FieldList(vec![Field::new(
-                        "input_available_withdrawal",
-                        Type::Uint128
-                    )])
-                ),
-                Transition::new(
-                    "ChangeCurrentDeleg",
-                    FieldList(vec![Field::new("input_current_deleg", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "ChangeCurrentSSN",
-                    FieldList(vec![Field::new("input_current_ssn", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "ChangeNewDeleg",
-                    FieldList(vec![Field::new("input_new_deleg", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "ChangeVerifier",
-                    FieldList(vec![Field::new("input_verifier", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "ChangeVerifierReceivingAddr",
-                    FieldList(vec![Field::new(
-                        "input_verifier_receiving_addr",
-                        Type::ByStr20
-                    )])
-                ),
-                Transition::new(
-                    "ChangeMinStake",
-                    FieldList(vec![Field::new("input_minstake", Type::Uint128)])
-                ),
-                Transition::new(
-                    "ChangeMinDelegStake",
-                    FieldList(vec![Field::new("input_mindelegstake", Type::Uint128)])
-                ),
-                Transition::new(
-                    "ChangeLastRewardCycle",
-                    FieldList(vec![Field::new("input_lastrewardcycle", Type::Uint32)])
-                ),
-                Transition::new(
-                    "ChangeMaxCommChangeRate",
-                    FieldList(vec![Field::new("input_maxcommchangerate", Type::Uint128)])
-                ),
-                Transition::new(
-                    "ChangeMaxCommRate",
-                    FieldList(vec![Field::new("input_maxcommrate", Type::Uint128)])
-                ),
-                Transition::new(
-                    "ChangeTotalStakeAmount",
-                    FieldList(vec![Field::new("input_totalstakeamount", Type::Uint128)])
-                ),
-            ])
+                                Box::new(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::Uint32),
+                                        Box::new(Type::Uint128)
+                                    )))),
+                                ))))
+                            )))
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyDepositAmtDelegList",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "deposit_amt_deleg_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::ByStr20),
+                                        Box::new(Type::Uint128)
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyDepositAmtDelegList",
+                        FieldList(vec![Field::new(
+                            "deposit_amt_deleg_list",
+                            Type::List(Box::new(Type::Pair(
+                                Box::new(Type::ByStr20),
+                                Box::new(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::Uint128)
+                                ))))
+                            )))
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyWithDrawalPendingList",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "withdrawal_pending_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::BNum),
+                                        Box::new(Type::Uint128)
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyWithDrawalPendingList",
+                        FieldList(vec![Field::new(
+                            "withdrawal_pending_list",
+                            Type::List(Box::new(Type::Pair(
+                                Box::new(Type::ByStr20),
+                                Box::new(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::BNum),
+                                    Box::new(Type::Uint128)
+                                ))))
+                            )))
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyCommForSSNList",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "comm_for_ssn_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::List(Box::new(Type::Pair(
+                                        Box::new(Type::Uint32),
+                                        Box::new(Type::Uint128)
+                                    ))))
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyCommForSSNList",
+                        FieldList(vec![Field::new(
+                            "comm_for_ssn_list",
+                            Type::List(Box::new(Type::Pair(
+                                Box::new(Type::ByStr20),
+                                Box::new(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::Uint32),
+                                    Box::new(Type::Uint128)
+                                ))))
+                            )))
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CopyDelegSwapRequest",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new(
+                                "deleg_swap_request_list",
+                                Some(Type::List(Box::new(Type::Pair(
+                                    Box::new(Type::ByStr20),
+                                    Box::new(Type::ByStr20)
+                                ))))
+                            ),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "CopyDelegSwapRequest",
+                        FieldList(vec![Field::new(
+                            "deleg_swap_request_list",
+                            Type::List(Box::new(Type::Pair(
+                                Box::new(Type::ByStr20),
+                                Box::new(Type::ByStr20),
+                            )))
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeCycleRewardsDeleg",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_cycle_rewards_deleg", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeCycleRewardsDeleg",
+                        FieldList(vec![Field::new("input_cycle_rewards_deleg", Type::Uint128)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeVerifierReward",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_verifier_reward", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeVerifierReward",
+                        FieldList(vec![Field::new("input_verifier_reward", Type::Uint128)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeAvailableWithdrawal",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_available_withdrawal", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeAvailableWithdrawal",
+                        FieldList(vec![Field::new(
+                            "input_available_withdrawal",
+                            Type::Uint128
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeCurrentDeleg",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_current_deleg", Some(Type::ByStr20)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeCurrentDeleg",
+                        FieldList(vec![Field::new("input_current_deleg", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeCurrentSSN",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_current_ssn", Some(Type::ByStr20)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeCurrentSSN",
+                        FieldList(vec![Field::new("input_current_ssn", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeNewDeleg",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_new_deleg", Some(Type::ByStr20)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeNewDeleg",
+                        FieldList(vec![Field::new("input_new_deleg", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeVerifier",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_verifier", Some(Type::ByStr20)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeVerifier",
+                        FieldList(vec![Field::new("input_verifier", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeVerifierReceivingAddr",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_verifier_receiving_addr", Some(Type::ByStr20)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeVerifierReceivingAddr",
+                        FieldList(vec![Field::new(
+                            "input_verifier_receiving_addr",
+                            Type::ByStr20
+                        )])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeMinStake",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_minstake", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeMinStake",
+                        FieldList(vec![Field::new("input_minstake", Type::Uint128)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeMinDelegStake",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_mindelegstake", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeMinDelegStake",
+                        FieldList(vec![Field::new("input_mindelegstake", Type::Uint128)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeLastRewardCycle",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_lastrewardcycle", Some(Type::Uint32)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeLastRewardCycle",
+                        FieldList(vec![Field::new("input_lastrewardcycle", Type::Uint32)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeMaxCommChangeRate",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_maxcommchangerate", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeMaxCommChangeRate",
+                        FieldList(vec![Field::new("input_maxcommchangerate", Type::Uint128)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeMaxCommRate",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_maxcommrate", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeMaxCommRate",
+                        FieldList(vec![Field::new("input_maxcommrate", Type::Uint128)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "ChangeTotalStakeAmount",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("input_totalstakeamount", Some(Type::Uint128)),
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeTotalStakeAmount",
+                        FieldList(vec![Field::new("input_totalstakeamount", Type::Uint128)])
+                    )
+                },
+            ]),
+            procedures: ProcedureList::default(),
+            constraint: None,
+            library: Some(Library {
+                name: "SSNListProxy_V2".to_string(),
+                let_definitions: vec![
+                    LetDefinition::new("zero", None),
+                    LetDefinition::new("one_msg", None),
+                ],
+                type_definitions: vec![]
+            }),
         }
     );
 }
@@ -1026,10 +2366,68 @@ fn test_staking_proxy_v2_parse() {
 fn test_stzil_contract_parse() {
     let contract_path = PathBuf::from("tests/contracts/stzil.scilla");
     let contract = Contract::parse(&contract_path).unwrap();
+    let withdrawal = TypeDefinition::new(
+        "Withdrawal",
+        vec![Constructor::new(
+            "Withdrawal",
+            vec![Type::Uint128, Type::Uint128],
+        )],
+    );
+    let error = TypeDefinition::new(
+        "Error",
+        vec![
+            Constructor::new("CodeIsSender", vec![]),
+            Constructor::new("CodeInsufficientFunds", vec![]),
+            Constructor::new("CodeInsufficientAllowance", vec![]),
+            Constructor::new("CodeNotOwner", vec![]),
+            Constructor::new("AdminValidationFailed", vec![]),
+            Constructor::new("BufferAddrUnknown", vec![]),
+            Constructor::new("BufferAlreadyDrained", vec![]),
+            Constructor::new("BufferNotDrained", vec![]),
+            Constructor::new("BufferOrHolderValidationFailed", vec![]),
+            Constructor::new("BuffersEmpty", vec![]),
+            Constructor::new("CantFetchZimplDelegExists", vec![]),
+            Constructor::new("CantFetchZimplDelegSsnExists", vec![]),
+            Constructor::new("CantFetchZimplFields", vec![]),
+            Constructor::new("CantFetchZimplHolderStakes", vec![]),
+            Constructor::new("ChownStakeCantFetchRemote", vec![]),
+            Constructor::new("ChownStakeSwapRequestNotFound", vec![]),
+            Constructor::new("ClaimWithdrawalNoUnbonded", vec![]),
+            Constructor::new("CompleteWithdrawalFundsMismatch", vec![]),
+            Constructor::new("DelegAddrUnknown", vec![]),
+            Constructor::new("DelegDoesNotExistAtSSN", vec![]),
+            Constructor::new("DelegHasNoSufficientAmt", vec![]),
+            Constructor::new("DelegStakeFundsMismatch", vec![]),
+            Constructor::new("DelegStakeNotEnough", vec![]),
+            Constructor::new("InvalidTotalAmt", vec![]),
+            Constructor::new("InvalidRewardsFee", vec![]),
+            Constructor::new("HolderAlreadySet", vec![]),
+            Constructor::new("NotPausedIn", vec![]),
+            Constructor::new("NotPausedOut", vec![]),
+            Constructor::new("NotPausedZrc2", vec![]),
+            Constructor::new("PausedIn", vec![]),
+            Constructor::new("PausedOut", vec![]),
+            Constructor::new("PausedZrc2", vec![]),
+            Constructor::new("SsnAddressesEmpty", vec![]),
+            Constructor::new("SsnAddressDoesNotExist", vec![]),
+            Constructor::new("SsnAddressExists", vec![]),
+            Constructor::new("StagingOwnerNotExists", vec![]),
+            Constructor::new("StagingOwnerValidationFailed", vec![]),
+            Constructor::new("WithdrawAmountTooBig", vec![]),
+            Constructor::new("WithdrawTokensAmtLessThanFee", vec![]),
+        ],
+    );
 
     assert_eq!(
         contract,
         Contract {
+            scilla_version: 0,
+            imports: vec![
+                Import::new("ListUtils"),
+                Import::new("IntUtils"),
+                Import::new("BoolUtils"),
+                Import::new("PairUtils")
+            ],
             name: "StZIL".to_string(),
             init_params: FieldList(vec![
                 Field::new("contract_owner", Type::ByStr20),
@@ -1079,7 +2477,7 @@ fn test_stzil_contract_parse() {
                         Box::new(Type::BNum),
                         Box::new(Type::Map(
                             Box::new(Type::ByStr20),
-                            Box::new(Type::Other("Withdrawal".to_string()))
+                            Box::new(adt(&withdrawal))
                         ))
                     )
                 ),
@@ -1087,18 +2485,12 @@ fn test_stzil_contract_parse() {
                     "withdrawal_pending_of_delegator",
                     Type::Map(
                         Box::new(Type::ByStr20),
-                        Box::new(Type::Map(
-                            Box::new(Type::BNum),
-                            Box::new(Type::Other("Withdrawal".to_string()))
-                        ))
+                        Box::new(Type::Map(Box::new(Type::BNum), Box::new(adt(&withdrawal))))
                     )
                 ),
                 Field::new(
                     "withdrawal_unbonded",
-                    Type::Map(
-                        Box::new(Type::ByStr20),
-                        Box::new(Type::Other("Withdrawal".to_string()))
-                    )
+                    Type::Map(Box::new(Type::ByStr20), Box::new(adt(&withdrawal)))
                 ),
                 Field::new(
                     "buffer_drained_cycle",
@@ -1116,21 +2508,81 @@ fn test_stzil_contract_parse() {
                 Field::new("local_lastrewardcycle", Type::Uint32),
             ],),
             transitions: TransitionList(vec![
-                Transition::new_without_param("PauseIn"),
-                Transition::new_without_param("UnPauseIn"),
-                Transition::new_without_param("PauseOut"),
-                Transition::new_without_param("UnPauseOut"),
-                Transition::new_without_param("PauseZrc2"),
-                Transition::new_without_param("UnPauseZrc2"),
-                Transition::new(
-                    "ChangeAdmin",
-                    FieldList(vec![Field::new("new_admin", Type::ByStr20)])
-                ),
-                Transition::new(
-                    "ChangeOwner",
-                    FieldList(vec![Field::new("new_owner", Type::ByStr20)])
-                ),
-                Transition::new_without_param("ClaimOwner"),
+                Transition {
+                    events: vec![Event::new(
+                        "PauseIn",
+                        vec![MessageParam::new("is_paused_in", Some(Type::Uint32))]
+                    )],
+                    ..Transition::new_without_param("PauseIn")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "UnPauseIn",
+                        vec![MessageParam::new("is_paused_in", Some(Type::Uint32))]
+                    )],
+                    ..Transition::new_without_param("UnPauseIn")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "PauseOut",
+                        vec![MessageParam::new("is_paused_out", Some(Type::Uint32))]
+                    )],
+                    ..Transition::new_without_param("PauseOut")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "UnPauseOut",
+                        vec![MessageParam::new("is_paused_out", Some(Type::Uint32))]
+                    )],
+                    ..Transition::new_without_param("UnPauseOut")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "PauseZrc2",
+                        vec![MessageParam::new("is_paused_zrc2", Some(Type::Uint32))]
+                    )],
+                    ..Transition::new_without_param("PauseZrc2")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "UnPauseZrc2",
+                        vec![MessageParam::new("is_paused_zrc2", Some(Type::Uint32))]
+                    )],
+                    ..Transition::new_without_param("UnPauseZrc2")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "ChangeAdmin",
+                        vec![
+                            MessageParam::new("old_admin", Some(Type::ByStr20)),
+                            MessageParam::new("new_admin", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeAdmin",
+                        FieldList(vec![Field::new("new_admin", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "ChangeOwner",
+                        vec![
+                            MessageParam::new("current_owner", Some(Type::ByStr20)),
+                            MessageParam::new("new_owner", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ChangeOwner",
+                        FieldList(vec![Field::new("new_owner", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "ClaimOwner",
+                        vec![MessageParam::new("new_owner", Some(Type::ByStr20))]
+                    )],
+                    ..Transition::new_without_param("ClaimOwner")
+                },
                 Transition::new(
                     "ChangeTreasuryAddress",
                     FieldList(vec![Field::new("address", Type::ByStr20)])
@@ -1139,14 +2591,26 @@ fn test_stzil_contract_parse() {
                     "ChangeWithdrawalFeeAddress",
                     FieldList(vec![Field::new("address", Type::ByStr20)])
                 ),
-                Transition::new(
-                    "SetHolderAddress",
-                    FieldList(vec![Field::new("address", Type::ByStr20)]),
-                ),
-                Transition::new(
-                    "ChangeZimplAddress",
-                    FieldList(vec![Field::new("address", Type::ByStr20)]),
-                ),
+                Transition {
+                    events: vec![Event::new(
+                        "SetHolderAddress",
+                        vec![MessageParam::new("address", Some(Type::ByStr20))]
+                    )],
+                    ..Transition::new(
+                        "SetHolderAddress",
+                        FieldList(vec![Field::new("address", Type::ByStr20)]),
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "ChangeZimplAddress",
+                        vec![MessageParam::new("address", Some(Type::ByStr20))]
+                    )],
+                    ..Transition::new(
+                        "ChangeZimplAddress",
+                        FieldList(vec![Field::new("address", Type::ByStr20)]),
+                    )
+                },
                 Transition::new(
                     "ChangeBuffers",
                     FieldList(vec![Field::new(
@@ -1162,98 +2626,898 @@ fn test_stzil_contract_parse() {
                     "RemoveSSN",
                     FieldList(vec![Field::new("ssnaddr", Type::ByStr20)])
                 ),
-                Transition::new(
-                    "ClaimRewards",
-                    FieldList(vec![
-                        Field::new("buffer_or_holder", Type::ByStr20),
-                        Field::new("ssn", Type::ByStr20)
-                    ])
+                Transition {
+                    messages: vec![Message::new(
+                        "ClaimRewards",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ClaimRewards",
+                        FieldList(vec![
+                            Field::new("buffer_or_holder", Type::ByStr20),
+                            Field::new("ssn", Type::ByStr20)
+                        ])
+                    )
+                },
+                Transition {
+                    messages: vec![
+                        Message::new(
+                            "RequestDelegatorSwap",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("new_deleg_addr", Some(Type::ByStr20)),
+                            ]
+                        ),
+                        Message::new(
+                            "ConfirmDelegatorSwap",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("requestor", Some(Type::ByStr20)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "ConsolidateInHolder",
+                        FieldList(vec![Field::new("buffer_addr", Type::ByStr20)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message {
+                        tag: None,
+                        params: vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                        ]
+                    }],
+                    ..Transition::new_without_param("ClaimRewardsSuccessCallBack")
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "DelegateStake",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new_without_param("PerformAutoRestake")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "IncreaseAutoRestakeAmount",
+                        vec![MessageParam::new("amount", Some(Type::Uint128))]
+                    )],
+                    ..Transition::new_without_param("IncreaseAutoRestakeAmount")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "UpdateStakingParameters",
+                        vec![
+                            MessageParam::new("mindelegstake", Some(Type::Uint128)),
+                            MessageParam::new("rewards_fee", Some(Type::Uint128)),
+                            MessageParam::new("withdrawal_fee", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "UpdateStakingParameters",
+                        FieldList(vec![
+                            Field::new("new_mindelegstake", Type::Uint128),
+                            Field::new("new_rewards_fee", Type::Uint128),
+                            Field::new("new_withdrawal_fee", Type::Uint128),
+                        ],)
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "DelegateStake",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new_without_param("DelegateStake")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "DelegateStakeWithReferral",
+                        vec![
+                            MessageParam::new("referral", Some(Type::ByStr20)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    messages: vec![Message::new(
+                        "DelegateStake",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "DelegateStakeWithReferral",
+                        FieldList(vec![Field::new("referral", Type::ByStr20)],)
+                    )
+                },
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "Minted",
+                            vec![
+                                MessageParam::new("minter", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "DelegateStake",
+                            vec![
+                                MessageParam::new("delegator", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    messages: vec![Message::new(
+                        "DelegateStakeSuccessCallBack",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "DelegateStakeSuccessCallBack",
+                        FieldList(vec![Field::new("amount", Type::Uint128,)])
+                    )
+                },
+                Transition {
+                    messages: vec![Message::new(
+                        "CompleteWithdrawal",
+                        vec![
+                            MessageParam::new("_recipient", Some(Type::ByStr20)),
+                            MessageParam::new("_amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "ClaimWithdrawal",
+                        FieldList(vec![Field::new(
+                            "blocks_to_withdraw",
+                            Type::List(Box::new(Type::BNum))
+                        )],)
+                    )
+                },
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "TransferSuccess",
+                            vec![
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "WithdrawStakeAmt",
+                            vec![
+                                MessageParam::new("ssn_addr", Some(Type::ByStr20)),
+                                MessageParam::new("deleg_address", Some(Type::ByStr20)),
+                                MessageParam::new("withdraw_amount", Some(Type::Uint128)),
+                                MessageParam::new("withdraw_stake_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "Burnt",
+                            vec![
+                                MessageParam::new("burner", Some(Type::ByStr20)),
+                                MessageParam::new("burn_account", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    messages: vec![
+                        Message::new(
+                            "WithdrawStakeAmt",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "WithdrawTokensAmtSuccessCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "WithdrawTokensAmt",
+                        FieldList(vec![Field::new("amount", Type::Uint128)],)
+                    )
+                },
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "WithdrawStakeAmt",
+                            vec![
+                                MessageParam::new("ssn_addr", Some(Type::ByStr20)),
+                                MessageParam::new("deleg_address", Some(Type::ByStr20)),
+                                MessageParam::new("withdraw_amount", Some(Type::Uint128)),
+                                MessageParam::new("withdraw_stake_amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new(
+                            "Burnt",
+                            vec![
+                                MessageParam::new("burner", Some(Type::ByStr20)),
+                                MessageParam::new("burn_account", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    messages: vec![
+                        Message::new(
+                            "WithdrawStakeAmt",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "WithdrawTokensAmtSuccessCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "SlashSSN",
+                        FieldList(vec![
+                            Field::new("withdraw_stake_amt", Type::Uint128),
+                            Field::new("ssnaddr", Type::ByStr20)
+                        ],)
+                    )
+                },
+                Transition {
+                    events: vec![
+                        Event::new(
+                            "CompleteWithdrawal",
+                            vec![
+                                MessageParam::new("delegator", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Event::new("NoUnbondedStake", vec![]),
+                    ],
+                    messages: vec![
+                        Message {
+                            tag: None,
+                            params: vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                            ]
+                        },
+                        Message::new(
+                            "CompleteWithdrawalSuccessCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "CompleteWithdrawalNoUnbondedStakeCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new_without_param("CompleteWithdrawal")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "CompleteWithdrawalFundsMismatch",
+                        vec![
+                            MessageParam::new("amountStZil", Some(Type::Uint128)),
+                            MessageParam::new("amounthZimpl", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new_without_param("CompleteWithdrawalSuccessCallBack")
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "Minted",
+                        vec![
+                            MessageParam::new("minter", Some(Type::ByStr20)),
+                            MessageParam::new("recipient", Some(Type::ByStr20)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    messages: vec![
+                        Message::new(
+                            "RejectDelegatorSwap",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("requestor", Some(Type::ByStr20)),
+                            ]
+                        ),
+                        Message::new(
+                            "ConfirmDelegatorSwap",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("requestor", Some(Type::ByStr20)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "ChownStakeConfirmSwap",
+                        FieldList(vec![Field::new("delegator", Type::ByStr20)],)
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "ChownStakeReDelegateSkip",
+                        vec![
+                            MessageParam::new("from_ssn", Some(Type::ByStr20)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    messages: vec![
+                        Message::new(
+                            "ClaimRewards",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                            ]
+                        ),
+                        Message::new(
+                            "ReDelegateStake",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("ssnaddr", Some(Type::ByStr20)),
+                                MessageParam::new("to_ssn", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "ChownStakeReDelegate",
+                        FieldList(vec![
+                            Field::new("from_ssn", Type::ByStr20),
+                            Field::new("amount", Type::Uint128),
+                        ],)
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "IncreasedAllowance",
+                        vec![
+                            MessageParam::new("token_owner", Some(Type::ByStr20)),
+                            MessageParam::new("spender", Some(Type::ByStr20)),
+                            MessageParam::new("new_allowance", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "IncreaseAllowance",
+                        FieldList(vec![
+                            Field::new("spender", Type::ByStr20),
+                            Field::new("amount", Type::Uint128),
+                        ],)
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "DecreasedAllowance",
+                        vec![
+                            MessageParam::new("token_owner", Some(Type::ByStr20)),
+                            MessageParam::new("spender", Some(Type::ByStr20)),
+                            MessageParam::new("new_allowance", Some(Type::Uint128)),
+                        ]
+                    )],
+                    ..Transition::new(
+                        "DecreaseAllowance",
+                        FieldList(vec![
+                            Field::new("spender", Type::ByStr20),
+                            Field::new("amount", Type::Uint128),
+                        ],)
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "TransferSuccess",
+                        vec![
+                            MessageParam::new("sender", Some(Type::ByStr20)),
+                            MessageParam::new("recipient", Some(Type::ByStr20)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    messages: vec![
+                        Message::new(
+                            "RecipientAcceptTransfer",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "TransferSuccessCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "Transfer",
+                        FieldList(vec![
+                            Field::new("to", Type::ByStr20),
+                            Field::new("amount", Type::Uint128,),
+                        ],)
+                    )
+                },
+                Transition {
+                    events: vec![Event::new(
+                        "TransferFromSuccess",
+                        vec![
+                            MessageParam::new("initiator", Some(Type::ByStr20)),
+                            MessageParam::new("sender", Some(Type::ByStr20)),
+                            MessageParam::new("recipient", Some(Type::ByStr20)),
+                            MessageParam::new("amount", Some(Type::Uint128)),
+                        ]
+                    )],
+                    messages: vec![
+                        Message::new(
+                            "RecipientAcceptTransferFrom",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("initiator", Some(Type::ByStr20)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                        Message::new(
+                            "TransferFromSuccessCallBack",
+                            vec![
+                                MessageParam::new("_recipient", Some(Type::ByStr20)),
+                                MessageParam::new("_amount", Some(Type::Uint128)),
+                                MessageParam::new("initiator", Some(Type::ByStr20)),
+                                MessageParam::new("sender", Some(Type::ByStr20)),
+                                MessageParam::new("recipient", Some(Type::ByStr20)),
+                                MessageParam::new("amount", Some(Type::Uint128)),
+                            ]
+                        ),
+                    ],
+                    ..Transition::new(
+                        "TransferFrom",
+                        FieldList(vec![
+                            Field::new("from", Type::ByStr20),
+                            Field::new("to", Type::ByStr20),
+                            Field::new("amount", Type::Uint128)
+                        ],)
+                    )
+                },
+            ]),
+            procedures: ProcedureList(vec![
+                Procedure::new(
+                    "ThrowError",
+                    FieldList(vec![Field::new("err", adt(&error))]),
+                ),
+                Procedure::new_without_param("RequireAdmin"),
+                Procedure::new_without_param("RequireOwner"),
+                Procedure::new_without_param("RequireNotPausedIn"),
+                Procedure::new_without_param("RequireNotPausedOut"),
+                Procedure::new_without_param("RequireNotPausedZrc2"),
+                Procedure::new(
+                    "RequireBuffers",
+                    FieldList(vec![Field::new(
+                        "buffers",
+                        Type::List(Box::new(Type::ByStr20)),
+                    )]),
                 ),
-                Transition::new(
-                    "ConsolidateInHolder",
-                    FieldList(vec![Field::new("buffer_addr", Type::ByStr20)])
+                Procedure::new(
+                    "RequireBuffer",
+                    FieldList(vec![Field::new("address", Type::ByStr20)]),
                 ),
-                Transition::new_without_param("ClaimRewardsSuccessCallBack"),
-                Transition::new_without_param("PerformAutoRestake"),
-                Transition::new_without_param("IncreaseAutoRestakeAmount"),
-                Transition::new(
-                    "UpdateStakingParameters",
+                Procedure::new(
+                    "RequireNotDrainedBuffer",
                     FieldList(vec![
-                        Field::new("new_mindelegstake", Type::Uint128),
-                        Field::new("new_rewards_fee", Type::Uint128),
-                        Field::new("new_withdrawal_fee", Type::Uint128),
-                    ],)
+                        Field::new("buffer_addr", Type::ByStr20),
+                        Field::new("cycle", Type::Uint32),
+                    ]),
                 ),
-                Transition::new_without_param("DelegateStake"),
-                Transition::new(
-                    "DelegateStakeWithReferral",
-                    FieldList(vec![Field::new("referral", Type::ByStr20),],)
+                Procedure::new(
+                    "RequireDrainedBuffer",
+                    FieldList(vec![
+                        Field::new("buffer_addr", Type::ByStr20),
+                        Field::new("cycle", Type::Uint32),
+                    ]),
                 ),
-                Transition::new(
-                    "DelegateStakeSuccessCallBack",
-                    FieldList(vec![Field::new("amount", Type::Uint128,),])
+                Procedure::new(
+                    "RequireBufferOrHolder",
+                    FieldList(vec![Field::new("address", Type::ByStr20)]),
                 ),
-                Transition::new(
-                    "ClaimWithdrawal",
+                Procedure::new(
+                    "RequireSsnAddresses",
                     FieldList(vec![Field::new(
-                        "blocks_to_withdraw",
-                        Type::List(Box::new(Type::BNum))
-                    )],)
-                ),
-                Transition::new(
-                    "WithdrawTokensAmt",
-                    FieldList(vec![Field::new("amount", Type::Uint128)],)
+                        "ssnlist",
+                        Type::List(Box::new(Type::ByStr20)),
+                    )]),
                 ),
-                Transition::new(
-                    "SlashSSN",
+                Procedure::new(
+                    "TransferFunds",
                     FieldList(vec![
-                        Field::new("withdraw_stake_amt", Type::Uint128),
-                        Field::new("ssnaddr", Type::ByStr20)
-                    ],)
+                        Field::new("tag", Type::String),
+                        Field::new("amt", Type::Uint128),
+                        Field::new("recipient", Type::ByStr20),
+                    ]),
                 ),
-                Transition::new_without_param("CompleteWithdrawal"),
-                Transition::new_without_param("CompleteWithdrawalSuccessCallBack"),
-                Transition::new(
-                    "ChownStakeConfirmSwap",
-                    FieldList(vec![Field::new("delegator", Type::ByStr20),],)
+                Procedure::new_without_param("GetSsnAddressForInput"),
+                Procedure::new(
+                    "GetSsnAddressForOutput",
+                    FieldList(vec![Field::new("withdraw_amount", Type::Uint128)]),
                 ),
-                Transition::new(
-                    "ChownStakeReDelegate",
+                Procedure::new_without_param("FetchRemoteZimplFields"),
+                Procedure::new(
+                    "FetchZimplDelegExists",
+                    FieldList(vec![Field::new("deleg", Type::ByStr20)]),
+                ),
+                Procedure::new(
+                    "RequireZimplDelegSsnExists",
                     FieldList(vec![
-                        Field::new("from_ssn", Type::ByStr20),
-                        Field::new("amount", Type::Uint128),
-                    ],)
+                        Field::new("deleg", Type::ByStr20),
+                        Field::new("ssnaddr", Type::ByStr20),
+                    ]),
                 ),
-                Transition::new(
-                    "IncreaseAllowance",
+                Procedure::new(
+                    "HolderWithdrawStakeAmt",
                     FieldList(vec![
-                        Field::new("spender", Type::ByStr20),
+                        Field::new("ssnaddr", Type::ByStr20),
                         Field::new("amount", Type::Uint128),
-                    ],)
+                    ]),
                 ),
-                Transition::new(
-                    "DecreaseAllowance",
+                Procedure::new(
+                    "BufferConfirmDelegatorSwap",
                     FieldList(vec![
-                        Field::new("spender", Type::ByStr20),
+                        Field::new("buffer_addr", Type::ByStr20),
+                        Field::new("requestor", Type::ByStr20),
+                    ]),
+                ),
+                Procedure::new(
+                    "BufferRejectDelegatorSwap",
+                    FieldList(vec![
+                        Field::new("buffer_addr", Type::ByStr20),
+                        Field::new("requestor", Type::ByStr20),
+                    ]),
+                ),
+                Procedure::new_without_param("HolderCompleteWithdrawal"),
+                Procedure::new(
+                    "BufferReDelegate",
+                    FieldList(vec![
+                        Field::new("buffer_addr", Type::ByStr20),
+                        Field::new("ssnaddr", Type::ByStr20),
+                        Field::new("to_ssn", Type::ByStr20),
                         Field::new("amount", Type::Uint128),
-                    ],)
+                    ]),
                 ),
-                Transition::new(
-                    "Transfer",
+                Procedure::new(
+                    "BufferDelegate",
+                    FieldList(vec![Field::new("amount", Type::Uint128)]),
+                ),
+                Procedure::new(
+                    "ClaimRewardsProc",
+                    FieldList(vec![
+                        Field::new("deleg", Type::ByStr20),
+                        Field::new("ssnaddr", Type::ByStr20),
+                    ]),
+                ),
+                Procedure::new(
+                    "RequestDelegatorSwap",
                     FieldList(vec![
+                        Field::new("from", Type::ByStr20),
                         Field::new("to", Type::ByStr20),
-                        Field::new("amount", Type::Uint128,),
-                    ],)
+                    ]),
                 ),
-                Transition::new(
-                    "TransferFrom",
+                Procedure::new(
+                    "ConfirmDelegatorSwap",
                     FieldList(vec![
                         Field::new("from", Type::ByStr20),
                         Field::new("to", Type::ByStr20),
-                        Field::new("amount", Type::Uint128)
-                    ],)
+                    ]),
+                ),
+                Procedure::new(
+                    "IsDelegStakeSufficient",
+                    FieldList(vec![Field::new("amount", Type::Uint128)]),
+                ),
+                Procedure::new(
+                    "IncreaseTotalStakeAmt",
+                    FieldList(vec![Field::new("amt", Type::Uint128)]),
+                ),
+                Procedure::new(
+                    "IncreaseTotalSupplyAmt",
+                    FieldList(vec![Field::new("amt", Type::Uint128)]),
+                ),
+                Procedure::new(
+                    "IncreaseAutoRestakeAmt",
+                    FieldList(vec![Field::new("amt", Type::Uint128)]),
+                ),
+                Procedure::new(
+                    "DecreaseTotalStakeAmt",
+                    FieldList(vec![Field::new("amt", Type::Uint128)]),
+                ),
+                Procedure::new(
+                    "DecreaseTotalSupplyAmt",
+                    FieldList(vec![Field::new("amt", Type::Uint128)]),
+                ),
+                Procedure::new(
+                    "TruncateDeleg",
+                    FieldList(vec![Field::new("deleg", Type::ByStr20)]),
+                ),
+                Procedure::new(
+                    "UpdateTokenBalance",
+                    FieldList(vec![
+                        Field::new("initiator", Type::ByStr20),
+                        Field::new("stake_amt", Type::Uint128),
+                    ]),
+                ),
+                Procedure::new(
+                    "RequireValidWithdrawAmount",
+                    FieldList(vec![
+                        Field::new("withdrawal_fee", Type::Uint128),
+                        Field::new("amount", Type::Uint128),
+                    ]),
+                ),
+                Procedure::new(
+                    "TakeWithdrawalFee",
+                    FieldList(vec![
+                        Field::new("sender", Type::ByStr20),
+                        Field::new("withdrawal_fee", Type::Uint128),
+                    ]),
+                ),
+                Procedure::new(
+                    "AdjustDeleg",
+                    FieldList(vec![
+                        Field::new("deleg", Type::ByStr20),
+                        Field::new("total_amount", Type::Uint128),
+                        Field::new("withdraw_amount", Type::Uint128),
+                    ]),
+                ),
+                Procedure::new(
+                    "DoWithdrawal",
+                    FieldList(vec![
+                        Field::new("initiator", Type::ByStr20),
+                        Field::new("from_ssn", Type::ByStr20),
+                        Field::new("withdraw_token_amt", Type::Uint128),
+                        Field::new("withdraw_stake_amt", Type::Uint128),
+                    ]),
+                ),
+                Procedure::new(
+                    "ProcessDelegateStakeSuccessCallBack",
+                    FieldList(vec![
+                        Field::new("delegator", Type::ByStr20),
+                        Field::new("amount", Type::Uint128),
+                    ]),
+                ),
+                Procedure::new(
+                    "CalculateTotalWithdrawalBlock",
+                    FieldList(vec![Field::new(
+                        "deleg_withdrawal",
+                        Type::Pair(Box::new(Type::ByStr20), Box::new(adt(&withdrawal)),),
+                    )]),
                 ),
-            ])
+                Procedure::new(
+                    "CleanWithdrawalDeleg",
+                    FieldList(vec![Field::new("deleg", Type::ByStr20)]),
+                ),
+                Procedure::new(
+                    "DeleteWithdrawalDelegBnum",
+                    FieldList(vec![Field::new(
+                        "pair_deleg_bnum",
+                        Type::Pair(Box::new(Type::ByStr20), Box::new(adt(&withdrawal)),),
+                    )]),
+                ),
+                Procedure::new(
+                    "CalculateTotalWithdrawal",
+                    FieldList(vec![Field::new("bnum", Type::BNum)]),
+                ),
+                Procedure::new_without_param("DelegateStake_"),
+                Procedure::new(
+                    "RequireNotSender",
+                    FieldList(vec![Field::new("address", Type::ByStr20)]),
+                ),
+                Procedure::new(
+                    "AuthorizedMoveIfSufficientBalance",
+                    FieldList(vec![
+                        Field::new("from", Type::ByStr20),
+                        Field::new("to", Type::ByStr20),
+                        Field::new("amount", Type::Uint128),
+                    ]),
+                ),
+            ]),
+            constraint: None,
+            library: Some(Library {
+                name: "StZIL".to_string(),
+                let_definitions: vec![
+                    LetDefinition::new("make_error", None),
+                    LetDefinition::new(
+                        "bystr20_eq",
+                        Some(function(vec![Type::ByStr20, Type::ByStr20, Type::Bool]))
+                    ),
+                    LetDefinition::new(
+                        "uint128_to_uint256",
+                        Some(function(vec![Type::Uint128, Type::Uint256]))
+                    ),
+                    LetDefinition::new(
+                        "muldiv",
+                        Some(function(vec![
+                            Type::Uint128,
+                            Type::Uint128,
+                            Type::Uint128,
+                            Type::Uint128
+                        ]))
+                    ),
+                    LetDefinition::new("one_msg", None),
+                    LetDefinition::new("two_msgs", None),
+                    LetDefinition::new("false", None),
+                    LetDefinition::new("true", None),
+                    LetDefinition::new("uint128_zero", None),
+                    LetDefinition::new("uint128_one", None),
+                    LetDefinition::new("uint32_zero", None),
+                    LetDefinition::new("uint32_one", None),
+                    LetDefinition::new("bystr20_zero", None),
+                    LetDefinition::new("fee_denom", None),
+                    LetDefinition::new("option_value", None),
+                    LetDefinition::new("option_uint32_value", None),
+                    LetDefinition::new("option_uint128_value", None),
+                    LetDefinition::new("option_bystr20_value", None),
+                    LetDefinition::new("list_bystr20_length", None),
+                    LetDefinition::new("not_equal_bystr20", None),
+                    LetDefinition::new("addfunds_tag", None),
+                    LetDefinition::new("get_active_buffer_addr", None),
+                    LetDefinition::new("get_active_ssn_addr", None),
+                    LetDefinition::new("leave_unbonded", None),
+                    LetDefinition::new("is_addr_in_list", None),
+                    LetDefinition::new("remove_addr_from_list", None),
+                    LetDefinition::new("sum_amount", None),
+                    LetDefinition::new("bool_to_uint32", None),
+                    LetDefinition::new("get_heaviest", None),
+                ],
+                type_definitions: vec![withdrawal.clone(), error.clone(),]
+            }),
         }
     )
 }
+
+#[test]
+fn test_version_imports_and_constraint() {
+    let contract: Contract = r#"scilla_version 1
+
+import ListUtils IntUtils as IU
+
+contract Constrained (max_supply : Uint128)
+with
+  let zero = Uint128 0 in
+  builtin lt zero max_supply
+=>
+"#
+    .parse()
+    .unwrap();
+
+    assert_eq!(contract.scilla_version, 1);
+    assert_eq!(
+        contract.imports,
+        vec![
+            Import::new("ListUtils"),
+            Import::with_alias("IntUtils", "IU")
+        ]
+    );
+    assert_eq!(
+        contract.constraint.as_deref(),
+        Some("let zero = Uint128 0 in\n  builtin lt zero max_supply")
+    );
+}
+
+#[test]
+fn test_library_module_parse() {
+    let module_path = PathBuf::from("tests/projects/libs/Counter.scillib");
+    let module = LibraryModule::parse(&module_path).unwrap();
+
+    assert_eq!(
+        module,
+        LibraryModule {
+            scilla_version: 0,
+            imports: vec![Import::new("Math")],
+            library: Library {
+                name: "Counter".to_string(),
+                let_definitions: vec![LetDefinition::new("step_size", None)],
+                type_definitions: vec![TypeDefinition::new(
+                    "Step",
+                    vec![
+                        Constructor::new("Single", vec![]),
+                        Constructor::new("Double", vec![])
+                    ]
+                )],
+            },
+        }
+    );
+
+    // A contract is not a library module.
+    let contract = include_str!("contracts/HelloWorld.scilla");
+    assert!(contract.parse::<LibraryModule>().is_err());
+}
+
+#[test]
+fn test_library_module_parse_stdlib() {
+    let module_path = PathBuf::from("tests/projects/stdlib/BoolUtils.scillib");
+    let module = LibraryModule::parse(&module_path).unwrap();
+
+    assert_eq!(module.scilla_version, 0);
+    assert!(module.imports.is_empty());
+    assert_eq!(module.library.name, "BoolUtils");
+    assert_eq!(
+        module.library.let_definitions,
+        ["andb", "orb", "negb", "eqb"]
+            .into_iter()
+            .map(|name| LetDefinition::new(name, None))
+            .collect::<Vec<_>>()
+    );
+    assert!(module.library.type_definitions.is_empty());
+}
+
+#[test]
+fn test_library_module_parse_imports() {
+    let module_path = PathBuf::from("tests/projects/libs/Ballot.scillib");
+    let module = LibraryModule::parse(&module_path).unwrap();
+
+    assert_eq!(
+        module.imports,
+        vec![Import::new("BoolUtils"), Import::with_alias("Math", "M")]
+    );
+    assert_eq!(module.library.name, "Ballot");
+    assert_eq!(
+        module.library.let_definitions,
+        vec![
+            LetDefinition::new("quorum", Some(Type::Uint128)),
+            LetDefinition::new("approved", None),
+        ]
+    );
+    assert_eq!(
+        module.library.type_definitions,
+        vec![TypeDefinition::new(
+            "Vote",
+            vec![
+                Constructor::new("Yes", vec![Type::Uint128]),
+                Constructor::new("No", vec![Type::Uint128]),
+                Constructor::new("Abstain", vec![]),
+            ]
+        )]
+    );
+}
@@ -0,0 +1,359 @@
+#![cfg(feature = "lsp")]
+
+use std::{
+    io::{BufReader, Write},
+    process::{Command, Stdio},
+    thread::JoinHandle,
+};
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use pretty_assertions::assert_eq;
+use serde_json::{json, Value};
+
+use scilla_parser::Error;
+
+const URI: &str = "file:///contracts/Shop.scilla";
+
+const SOURCE: &str = r#"scilla_version 0
+library Shop
+type Item =
+  | Apple
+  | Pear of Uint128
+
+let price = Uint128 10
+
+contract Shop (owner : ByStr20)
+field stock : Map ByStr20 Item = Emp ByStr20 Item
+
+procedure Restock (item : Item)
+  stock[_sender] := item
+end
+
+transition Buy (weight : Uint128)
+  item = Pear weight;
+  Restock item
+end
+"#;
+
+/// A client talking to a server running in another thread.
+struct Session {
+    client: Connection,
+    server: JoinHandle<Result<(), Error>>,
+    next_id: i32,
+}
+
+impl Session {
+    fn start() -> Self {
+        let (server, client) = Connection::memory();
+        let server = std::thread::spawn(move || scilla_parser::lsp::run(&server));
+        let mut session = Self {
+            client,
+            server,
+            next_id: 0,
+        };
+        session.request("initialize", json!({ "capabilities": {} }));
+        session.notify("initialized", json!({}));
+        session
+    }
+
+    /// Sends a request and returns the response to it.
+    fn request(&mut self, method: &str, params: Value) -> Response {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        let request = Request::new(id.clone(), method.to_string(), params);
+        self.client.sender.send(request.into()).unwrap();
+        match self.client.receiver.recv().unwrap() {
+            Message::Response(response) if response.id == id => response,
+            message => panic!("expected the response to {method}, got {message:?}"),
+        }
+    }
+
+    /// Sends a request and returns its result.
+    fn result(&mut self, method: &str, params: Value) -> Value {
+        let response = self.request(method, params);
+        assert!(response.error.is_none(), "{:?}", response.error);
+        response.result.unwrap()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        let notification = Notification::new(method.to_string(), params);
+        self.client.sender.send(notification.into()).unwrap();
+    }
+
+    /// Opens a document and returns the diagnostics published for it.
+    fn open(&mut self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": URI, "languageId": "scilla", "version": 1, "text": text }
+            }),
+        );
+        self.diagnostics()
+    }
+
+    fn diagnostics(&mut self) -> Value {
+        match self.client.receiver.recv().unwrap() {
+            Message::Notification(notification)
+                if notification.method == "textDocument/publishDiagnostics" =>
+            {
+                assert_eq!(notification.params["uri"], URI);
+                notification.params["diagnostics"].clone()
+            }
+            message => panic!("expected diagnostics, got {message:?}"),
+        }
+    }
+
+    fn shutdown(mut self) {
+        self.result("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        self.server.join().unwrap().unwrap();
+    }
+}
+
+/// The parameters of a request about `line` and `character` in the document, counting from 0.
+fn at(line: u32, character: u32) -> Value {
+    json!({
+        "textDocument": { "uri": URI },
+        "position": { "line": line, "character": character },
+    })
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 },
+    })
+}
+
+#[test]
+fn test_diagnostics() {
+    let mut session = Session::start();
+    assert_eq!(session.open(SOURCE), json!([]));
+
+    session.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": SOURCE.replace("item = Pear", "item = = Pear") }],
+        }),
+    );
+    let diagnostics = session.diagnostics();
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["range"], range((16, 9), (16, 10)));
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert!(diagnostics[0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Unexpected token `=`"));
+
+    session.notify(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(session.diagnostics(), json!([]));
+    session.shutdown();
+}
+
+#[test]
+fn test_document_symbols() {
+    let mut session = Session::start();
+    session.open(SOURCE);
+    let symbols = session.result(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+
+    /// The name, kind and detail of a symbol, with its children.
+    fn outline(symbol: &Value) -> Value {
+        let children: Vec<_> = symbol["children"]
+            .as_array()
+            .map(|children| children.iter().map(outline).collect())
+            .unwrap_or_default();
+        json!([symbol["name"], symbol["kind"], symbol["detail"], children])
+    }
+    let outlines: Vec<_> = symbols.as_array().unwrap().iter().map(outline).collect();
+    assert_eq!(
+        outlines,
+        vec![
+            json!([
+                "Shop",
+                3,
+                null,
+                [
+                    [
+                        "Item",
+                        10,
+                        null,
+                        [["Apple", 22, null, []], ["Pear", 22, null, []]]
+                    ],
+                    ["price", 14, null, []],
+                ]
+            ]),
+            json!([
+                "Shop",
+                5,
+                "(owner : ByStr20)",
+                [
+                    ["stock", 8, "Map (ByStr20) (Item)", []],
+                    ["Restock", 12, "(item : Item)", []],
+                    ["Buy", 6, "(weight : Uint128)", []],
+                ]
+            ]),
+        ]
+    );
+    assert_eq!(symbols[1]["selectionRange"], range((8, 9), (8, 13)));
+    session.shutdown();
+}
+
+#[test]
+fn test_hover() {
+    let mut session = Session::start();
+    session.open(SOURCE);
+
+    let hover = session.result("textDocument/hover", at(12, 3));
+    assert_eq!(
+        hover,
+        json!({
+            "contents": {
+                "kind": "markdown",
+                "value": "```scilla\nstock : Map (ByStr20) (Item)\n```\n\nfield of `Shop`",
+            },
+            "range": range((12, 2), (12, 7)),
+        })
+    );
+
+    let hover = session.result("textDocument/hover", at(16, 10));
+    assert_eq!(
+        hover["contents"]["value"],
+        "```scilla\nPear : Uint128 -> Item\n```\n\nconstructor of `Shop`"
+    );
+    let hover = session.result("textDocument/hover", at(17, 4));
+    assert_eq!(
+        hover["contents"]["value"],
+        "```scilla\nRestock\n```\n\nprocedure of `Shop`"
+    );
+    assert_eq!(
+        session.result("textDocument/hover", at(12, 17)),
+        Value::Null
+    );
+    session.shutdown();
+}
+
+#[test]
+fn test_definition_and_references() {
+    let mut session = Session::start();
+    session.open(SOURCE);
+
+    let definition = session.result("textDocument/definition", at(17, 5));
+    assert_eq!(
+        definition,
+        json!({ "uri": URI, "range": range((11, 10), (11, 17)) })
+    );
+    assert_eq!(
+        session.result("textDocument/definition", at(12, 9)),
+        Value::Null
+    );
+
+    let mut params = at(11, 10);
+    params["context"] = json!({ "includeDeclaration": true });
+    let references = session.result("textDocument/references", params.clone());
+    assert_eq!(
+        references,
+        json!([
+            { "uri": URI, "range": range((11, 10), (11, 17)) },
+            { "uri": URI, "range": range((17, 2), (17, 9)) },
+        ])
+    );
+    params["context"] = json!({ "includeDeclaration": false });
+    let references = session.result("textDocument/references", params);
+    assert_eq!(references.as_array().unwrap().len(), 1);
+    session.shutdown();
+}
+
+#[test]
+fn test_formatting() {
+    let mut session = Session::start();
+    let unformatted = "scilla_version 0\ncontract  Shop ()\nfield   count : Uint32 = Uint32 0\n";
+    session.open(unformatted);
+
+    let params = json!({
+        "textDocument": { "uri": URI },
+        "options": { "tabSize": 2, "insertSpaces": true },
+    });
+    let edits = session.result("textDocument/formatting", params.clone());
+    assert_eq!(
+        edits,
+        json!([{
+            "range": range((0, 0), (3, 0)),
+            "newText": scilla_parser::formatter::format(unformatted).unwrap(),
+        }])
+    );
+
+    session.open(&scilla_parser::formatter::format(unformatted).unwrap());
+    assert_eq!(session.result("textDocument/formatting", params), json!([]));
+    session.shutdown();
+}
+
+#[test]
+fn test_unsupported_request() {
+    let mut session = Session::start();
+    let response = session.request("textDocument/rename", at(0, 0));
+    assert_eq!(response.result, None);
+    assert_eq!(response.error.unwrap().code, -32601);
+    session.shutdown();
+}
+
+#[test]
+fn test_stdio_session() {
+    let mut server = Command::new(env!("CARGO_BIN_EXE_scilla-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let script: Vec<Message> = vec![
+        Request::new(
+            1.into(),
+            "initialize".to_string(),
+            json!({ "capabilities": {} }),
+        )
+        .into(),
+        Notification::new("initialized".to_string(), json!({})).into(),
+        Notification::new(
+            "textDocument/didOpen".to_string(),
+            json!({
+                "textDocument": {
+                    "uri": URI, "languageId": "scilla", "version": 1, "text": "contract Shop ("
+                }
+            }),
+        )
+        .into(),
+        Request::new(2.into(), "shutdown".to_string(), Value::Null).into(),
+        Notification::new("exit".to_string(), Value::Null).into(),
+    ];
+    let mut stdin = server.stdin.take().unwrap();
+    for message in script {
+        message.write(&mut stdin).unwrap();
+    }
+    stdin.flush().unwrap();
+    drop(stdin);
+
+    let mut stdout = BufReader::new(server.stdout.take().unwrap());
+    let mut replies = vec![];
+    while let Some(message) = Message::read(&mut stdout).unwrap() {
+        replies.push(message);
+    }
+    assert!(server.wait().unwrap().success());
+
+    let [Message::Response(initialized), Message::Notification(diagnostics), Message::Response(shutdown)] =
+        &replies[..]
+    else {
+        panic!("unexpected replies {replies:?}");
+    };
+    let capabilities = &initialized.result.as_ref().unwrap()["capabilities"];
+    assert_eq!(capabilities["hoverProvider"], true);
+    assert_eq!(capabilities["documentFormattingProvider"], true);
+    assert_eq!(diagnostics.method, "textDocument/publishDiagnostics");
+    assert_eq!(diagnostics.params["diagnostics"][0]["severity"], 1);
+    assert_eq!(shutdown.id, 2.into());
+}
@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+
+use scilla_parser::{parser::parse_program, Location, Span, SymbolTable, Workspace};
+
+const SOURCE: &str = r#"scilla_version 0
+library Auction
+type Bid =
+  | NoBid
+  | Bid of ByStr20 Uint128
+
+let min_bid = Uint128 10
+
+let is_higher =
+  fun (bid : Bid) =>
+  fun (amount : Uint128) =>
+    match bid with
+    | NoBid => builtin lt min_bid amount
+    | Bid _ current => builtin lt current amount
+    end
+
+contract Auction (seller : ByStr20)
+field highest : Bid = NoBid
+
+procedure Refund (bid : Bid)
+  match bid with
+  | Bid bidder amount =>
+    msg = { _tag : ""; _recipient : bidder; _amount : amount };
+    msgs = one_msg msg;
+    send msgs
+  | NoBid =>
+  end
+end
+
+transition Place ()
+  current <- highest;
+  higher = is_higher current _amount;
+  match higher with
+  | True =>
+    accept;
+    Refund current;
+    bid = Bid _sender _amount;
+    highest := bid
+  | False =>
+  end
+end
+"#;
+
+/// Returns the byte offset of the `occurrence`th `name` in `SOURCE`, counting from 0.
+fn offset(name: &str, occurrence: usize) -> usize {
+    SOURCE.match_indices(name).nth(occurrence).unwrap().0
+}
+
+/// Returns the line and column a span starts at, counting from 1.
+fn start(span: Span) -> (usize, usize) {
+    (span.start.line + 1, span.start.column + 1)
+}
+
+fn resolve() -> SymbolTable {
+    SymbolTable::resolve(&parse_program(SOURCE).0.unwrap())
+}
+
+#[test]
+fn test_definition_at() {
+    let table = resolve();
+    let cases = [
+        // A field, from where it is stored.
+        ("highest", 2, (18, 7)),
+        // A contract parameter, from its own definition.
+        ("seller", 0, (17, 19)),
+        ("Refund", 1, (20, 11)),
+        ("is_higher", 1, (9, 5)),
+        ("min_bid", 1, (7, 5)),
+        // A constructor, from a pattern.
+        ("NoBid", 3, (4, 5)),
+        // A type name, from a parameter annotation.
+        ("Bid", 8, (3, 6)),
+        // A parameter of a function.
+        ("amount", 2, (11, 8)),
+    ];
+    for (name, occurrence, definition) in cases {
+        let location = table.definition_at(offset(name, occurrence)).unwrap();
+        assert_eq!(location.library, None);
+        assert_eq!(start(location.span), definition, "{name} {occurrence}");
+    }
+
+    // The cursor right after a name is on that name.
+    let end_of_refund = offset("Refund", 1) + "Refund".len();
+    assert_eq!(
+        start(table.definition_at(end_of_refund).unwrap().span),
+        (20, 11)
+    );
+
+    // Builtin names and keywords have no definition.
+    assert_eq!(table.definition_at(offset("_sender", 0)), None);
+    assert_eq!(table.definition_at(offset("Uint128", 0)), None);
+    assert_eq!(table.definition_at(offset("accept", 0)), None);
+}
+
+#[test]
+fn test_references_at() {
+    let table = resolve();
+    let lines = |spans: Vec<Span>| spans.into_iter().map(|s| start(s).0).collect::<Vec<_>>();
+
+    assert_eq!(
+        lines(table.references_at(offset("highest", 0), true)),
+        vec![18, 31, 38]
+    );
+    assert_eq!(
+        lines(table.references_at(offset("highest", 1), false)),
+        vec![31, 38]
+    );
+    assert_eq!(
+        lines(table.references_at(offset("Bid", 3), true)),
+        vec![3, 10, 18, 20]
+    );
+    assert_eq!(
+        lines(table.references_at(offset("Bid", 2), true)),
+        vec![5, 14, 22, 37]
+    );
+    assert_eq!(
+        lines(table.references_at(offset("Refund", 0), true)),
+        vec![20, 36]
+    );
+    // The `current` bound by the pattern of `is_higher` is not the one of `Place`.
+    assert_eq!(
+        lines(table.references_at(offset("current", 2), true)),
+        vec![31, 32, 36]
+    );
+    assert_eq!(table.references_at(offset("accept", 0), true), vec![]);
+}
+
+#[test]
+fn test_project_definition() {
+    let workspace = Workspace::new(vec![
+        PathBuf::from("tests/projects/libs"),
+        PathBuf::from("tests/projects/stdlib"),
+    ]);
+    let project = workspace
+        .load(Path::new("tests/projects/Counting.scilla"))
+        .unwrap();
+    let source = std::fs::read_to_string(&project.contract_path).unwrap();
+    let table = project.symbols();
+
+    let location = table
+        .definition_at(source.find("increment").unwrap())
+        .unwrap();
+    assert_eq!(location.library.as_deref(), Some("Math"));
+    assert_eq!(start(location.span), (7, 5));
+    assert_eq!(
+        project.path_of(&location),
+        Some(Path::new("tests/projects/stdlib/Math.scillib"))
+    );
+
+    let count = table.definition_at(source.find("count;").unwrap()).unwrap();
+    assert_eq!(
+        project.path_of(&count),
+        Some(Path::new("tests/projects/Counting.scilla"))
+    );
+    assert_eq!(
+        count,
+        Location {
+            library: None,
+            span: table.references_at(source.find("count;").unwrap(), true)[0],
+        }
+    );
+}
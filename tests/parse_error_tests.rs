@@ -0,0 +1,235 @@
+use scilla_parser::{
+    parser::{
+        lexer::{self, Lexer, SourcePosition, Token},
+        parse_program, ParserError,
+    },
+    Contract, Error,
+};
+
+const CONTRACT: &str = r#"scilla_version 0
+
+contract HelloWorld
+(owner: ByStr20)
+
+field welcome_msg : String = ""
+
+transition setHello (msg : String)
+  welcome_msg := msg
+  x = y
+end
+"#;
+
+fn parse_errors(contract: &str) -> Vec<ParserError> {
+    match contract.parse::<Contract>() {
+        Err(Error::ParseError(errors)) => errors,
+        other => panic!("Expected a parse error, got {other:?}"),
+    }
+}
+
+fn parse_error(contract: &str) -> ParserError {
+    let mut errors = parse_errors(contract);
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    errors.remove(0)
+}
+
+#[test]
+fn test_parse_error_position() {
+    let error = parse_error(CONTRACT);
+    assert_eq!(error.unexpected.as_deref(), Some("x"));
+    assert_eq!(
+        error.start,
+        SourcePosition {
+            position: 147,
+            line: 9,
+            column: 2
+        }
+    );
+    assert_eq!(error.end.column, 3);
+    assert_eq!(error.expected, vec![r#"";""#, r#""end""#]);
+    assert_eq!(error.source_line.as_deref(), Some("  x = y"));
+}
+
+#[test]
+fn test_parse_error_display() {
+    let error = parse_error(CONTRACT);
+    assert_eq!(
+        error.to_string(),
+        r#"Unexpected token `x`, expected one of ";", "end"
+  --> 10:3
+   |
+10 |   x = y
+   |   ^"#
+    );
+}
+
+#[test]
+fn test_parse_error_at_end_of_input() {
+    let error = parse_error("scilla_version 0\n\ncontract HelloWorld\n(");
+    assert_eq!(error.unexpected, None);
+    assert_eq!(error.start.line, 3);
+    assert_eq!(error.start.column, 1);
+}
+
+#[test]
+fn test_parse_error_names_token_classes() {
+    let error = parse_error("scilla_version 0\n\ncontract\n");
+    assert_eq!(
+        error.expected,
+        vec![r#""Event""#, "ByStrX type", "type name"]
+    );
+
+    let error = parse_errors(&CONTRACT.replace("msg\n  x = y", "msg;\n  x = ;")).remove(0);
+    assert!(error.expected.contains(&"identifier".to_string()));
+    assert!(error.expected.contains(&"special identifier".to_string()));
+    assert!(error.expected.contains(&"string literal".to_string()));
+    assert!(error.expected.contains(&"hex literal".to_string()));
+
+    let error =
+        parse_errors(&CONTRACT.replace("msg\n  x = y", "msg;\n  x = tfun 1 => y")).remove(0);
+    assert_eq!(error.expected, vec!["type variable"]);
+    assert!(error
+        .to_string()
+        .starts_with("Unexpected token `1`, expected type variable\n"));
+}
+
+const CONTRACT_WITH_TYPOS: &str = r#"scilla_version 0
+
+library HelloWorld
+
+let one = Uint32 1
+let two = = Uint32 2
+let three = Uint32 3
+
+contract HelloWorld
+(owner: ByStr20)
+
+field welcome_msg : String = ""
+
+transition setHello (msg : String)
+  welcome_msg := msg;
+  x <-;
+  e = {_eventname: "SetHello"};
+  event e
+end
+
+transition getHello ()
+  r <- welcome_msg;
+  e = {_eventname: "GetHello"; msg: r};
+  event e e
+end
+
+procedure checkOwner ()
+  accept
+end
+"#;
+
+#[test]
+fn test_parse_error_recovery() {
+    let errors = parse_errors(CONTRACT_WITH_TYPOS);
+    let lines: Vec<usize> = errors.iter().map(|error| error.start.line + 1).collect();
+    assert_eq!(lines, vec![6, 16, 24]);
+}
+
+#[test]
+fn test_parse_error_recovery_keeps_valid_parts() {
+    let (program, errors) = parse_program(CONTRACT_WITH_TYPOS);
+    assert_eq!(errors.len(), 3);
+    let program = program.expect("the parser should recover");
+
+    let library = program.library_definition.expect("library").node;
+    assert_eq!(library.definitions.len(), 2);
+
+    let contract = program.contract_definition.node;
+    assert_eq!(contract.components.len(), 3);
+}
+
+fn lexer_error(source: &str) -> lexer::ParseError {
+    Lexer::new(source)
+        .find_map(Result::err)
+        .expect("Expected a lexer error")
+}
+
+#[test]
+fn test_lexer_errors() {
+    assert_eq!(
+        lexer_error("x = \"hello"),
+        lexer::ParseError::UnterminatedString {
+            start: SourcePosition {
+                position: 4,
+                line: 0,
+                column: 4
+            }
+        }
+    );
+    assert_eq!(
+        lexer_error("accept\n(* unfinished"),
+        lexer::ParseError::UnterminatedComment {
+            start: SourcePosition {
+                position: 7,
+                line: 1,
+                column: 0
+            }
+        }
+    );
+    assert_eq!(
+        lexer_error("x = 0x123;"),
+        lexer::ParseError::InvalidHex {
+            literal: "0x123".to_string(),
+            start: SourcePosition {
+                position: 4,
+                line: 0,
+                column: 4
+            },
+            end: SourcePosition {
+                position: 9,
+                line: 0,
+                column: 9
+            }
+        }
+    );
+    assert_eq!(
+        lexer_error("x = $y"),
+        lexer::ParseError::InvalidChar {
+            character: '$',
+            start: SourcePosition {
+                position: 4,
+                line: 0,
+                column: 4
+            }
+        }
+    );
+}
+
+#[test]
+fn test_lexer_resumes_after_invalid_hex_with_multibyte_characters() {
+    let tokens: Vec<_> = Lexer::new("x = 0x1é; y").collect();
+    assert!(matches!(
+        &tokens[2],
+        Err(lexer::ParseError::InvalidHex { literal, .. }) if literal == "0x1é"
+    ));
+    let rest: Vec<_> = tokens[3..]
+        .iter()
+        .map(|token| token.as_ref().unwrap().1.clone())
+        .collect();
+    assert_eq!(rest, vec![Token::Semicolon, Token::Identifier("y")]);
+}
+
+#[test]
+fn test_lexer_error_is_reported_where_it_occurs() {
+    let error = parse_error(&CONTRACT.replace("x = y", "x = $y"));
+    assert_eq!(
+        error.lexer_error,
+        Some(lexer::ParseError::InvalidChar {
+            character: '$',
+            start: error.start
+        })
+    );
+    assert_eq!(
+        error.to_string(),
+        r#"Invalid character `$`
+  --> 10:7
+   |
+10 |   x = $y
+   |       ^"#
+    );
+}
@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+
+use scilla_parser::{ast::nodes::NodeImportedName, Error, Import, Workspace};
+
+fn workspace() -> Workspace {
+    Workspace::new(vec![
+        PathBuf::from("tests/projects/libs"),
+        PathBuf::from("tests/projects/stdlib"),
+    ])
+}
+
+#[test]
+fn test_load_project() {
+    let project = workspace()
+        .load(Path::new("tests/projects/Counting.scilla"))
+        .unwrap();
+
+    assert_eq!(project.contract.name, "Counting");
+    assert_eq!(
+        project.contract.imports,
+        vec![Import::with_alias("Counter", "C"), Import::new("Math")]
+    );
+
+    // Libraries come after the libraries they import, and are loaded once.
+    let names: Vec<_> = project.libraries.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, vec!["Math", "Counter"]);
+    assert_eq!(
+        project.libraries[0].path,
+        PathBuf::from("tests/projects/stdlib/Math.scillib")
+    );
+    assert_eq!(
+        project.libraries[1]
+            .module
+            .library_definition
+            .node
+            .definitions
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn test_resolve_imports() {
+    let project = workspace()
+        .load(Path::new("tests/projects/Counting.scilla"))
+        .unwrap();
+
+    assert_eq!(project.resolve("C").unwrap().name, "Counter");
+    assert_eq!(project.resolve("Math").unwrap().name, "Math");
+    // An aliased library is only known by its alias.
+    assert!(project.resolve("Counter").is_none());
+
+    let imports = &project.program.import_declarations.as_ref().unwrap().node;
+    for import in &imports.import_list {
+        let library = project.resolve_import(&import.node).unwrap();
+        match &import.node {
+            NodeImportedName::RegularImport(name) | NodeImportedName::AliasedImport(name, _) => {
+                assert_eq!(library.name, name.to_string())
+            }
+        }
+    }
+}
+
+#[test]
+fn test_missing_import() {
+    match workspace().load(Path::new("tests/projects/Missing.scilla")) {
+        Err(Error::MissingImport { name, importer }) => {
+            assert_eq!(name, "Unknown");
+            assert_eq!(importer, PathBuf::from("tests/projects/Missing.scilla"));
+        }
+        other => panic!("Expected a missing import, got {other:?}"),
+    }
+
+    // Without search paths, not even the first import is found.
+    assert!(matches!(
+        Workspace::default().load(Path::new("tests/projects/Counting.scilla")),
+        Err(Error::MissingImport { name, .. }) if name == "Counter"
+    ));
+}
+
+#[test]
+fn test_cyclic_import() {
+    let error = workspace()
+        .load(Path::new("tests/projects/Cyclic.scilla"))
+        .unwrap_err();
+    assert!(
+        matches!(&error, Error::CyclicImport(cycle) if cycle == &["Ping", "Pong", "Ping"]),
+        "{error:?}"
+    );
+    assert_eq!(error.to_string(), "Cyclic import: Ping -> Pong -> Ping");
+}
+
+#[test]
+fn test_library_name_mismatch() {
+    let error = workspace()
+        .load(Path::new("tests/projects/Mismatched.scilla"))
+        .unwrap_err();
+    match &error {
+        Error::LibraryNameMismatch { name, found, path } => {
+            assert_eq!(name, "Renamed");
+            assert_eq!(found, "Original");
+            assert_eq!(path, &PathBuf::from("tests/projects/libs/Renamed.scillib"));
+        }
+        other => panic!("Expected a library name mismatch, got {other:?}"),
+    }
+    assert_eq!(
+        error.to_string(),
+        "tests/projects/libs/Renamed.scillib defines library Original, not the imported Renamed"
+    );
+}
@@ -0,0 +1,201 @@
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+
+use scilla_parser::{
+    parser::parse_program, simplified_representation::primitives::SrIdentifierKind, Symbol,
+    SymbolKind, SymbolTable, Type, Workspace,
+};
+
+const SOURCE: &str = r#"scilla_version 0
+library Shop
+type Item =
+  | Apple
+  | Pear of Uint128
+
+let price =
+  fun (item : Item) =>
+    match item with
+    | Apple => Uint128 1
+    | Pear weight => weight
+    end
+
+contract Shop (owner : ByStr20)
+field stock : Map ByStr20 Item = Emp ByStr20 Item
+
+procedure Sell (item : Item)
+  p = price item;
+  stock[_sender] := item
+end
+
+transition Buy (weight : Uint128)
+  item = Pear weight;
+  Sell item;
+  s <- stock[owner];
+  match s with
+  | Some item =>
+    Sell item
+  | None =>
+  end
+end
+"#;
+
+/// Returns the byte offset of the `occurrence`th `name` in `SOURCE`, counting from 0.
+fn offset(name: &str, occurrence: usize) -> usize {
+    SOURCE.match_indices(name).nth(occurrence).unwrap().0
+}
+
+fn resolve() -> SymbolTable {
+    SymbolTable::resolve(&parse_program(SOURCE).0.unwrap())
+}
+
+/// Returns the line and column of the definition of `symbol`, counting from 1.
+fn definition(symbol: &Symbol) -> (usize, usize) {
+    let span = symbol.definition.unwrap();
+    (span.start.line + 1, span.start.column + 1)
+}
+
+#[test]
+fn test_symbol_at() {
+    let table = resolve();
+    let cases = [
+        // The parameter of `price`, not the type.
+        ("item", 1, SymbolKind::Parameter, (8, 8)),
+        ("Item", 1, SymbolKind::TypeName, (3, 6)),
+        ("Pear", 1, SymbolKind::Constructor, (5, 5)),
+        ("price", 1, SymbolKind::LibraryLet, (7, 5)),
+        ("stock", 1, SymbolKind::Field, (15, 7)),
+        ("owner", 1, SymbolKind::ContractParameter, (14, 16)),
+        ("Sell", 1, SymbolKind::Procedure, (17, 11)),
+        ("weight", 3, SymbolKind::Parameter, (22, 17)),
+        // The item bound by the pattern shadows the one bound before the match.
+        ("item", 8, SymbolKind::Local, (27, 10)),
+        ("item", 6, SymbolKind::Local, (23, 3)),
+    ];
+    for (name, occurrence, kind, position) in cases {
+        let symbol = table.symbol_at(offset(name, occurrence)).unwrap();
+        assert_eq!(
+            (symbol.name.as_str(), symbol.kind, definition(symbol)),
+            (name, kind, position),
+            "{name} {occurrence}"
+        );
+    }
+
+    let sender = table.symbol_at(offset("_sender", 0)).unwrap();
+    assert_eq!(sender.kind, SymbolKind::Implicit);
+    assert_eq!(sender.definition, None);
+    assert_eq!(
+        table.symbol_at(offset("Uint128", 0)).unwrap().definition,
+        None
+    );
+    assert_eq!(table.symbol_at(offset("match", 0)), None);
+    assert_eq!(table.unresolved().count(), 0);
+}
+
+#[test]
+fn test_references_to() {
+    let table = resolve();
+    let reference = table.reference_at(offset("Sell", 0)).unwrap();
+    assert!(reference.is_definition);
+    let lines: Vec<_> = table
+        .references_to(reference.symbol.unwrap())
+        .map(|r| (r.span.start.line + 1, r.is_definition))
+        .collect();
+    assert_eq!(lines, vec![(17, true), (24, false), (28, false)]);
+
+    let symbol = table.symbol_at(offset("Pear", 0)).unwrap();
+    assert_eq!(
+        symbol.r#type,
+        Some("Uint128 -> Item".parse::<Type>().unwrap())
+    );
+    assert_eq!(symbol.qualified_name(), "Shop.Pear");
+}
+
+#[test]
+fn test_identifiers() {
+    let table = resolve();
+    let cases = [
+        ("stock", 1, "Shop.stock", SrIdentifierKind::State),
+        ("weight", 3, "Shop.Buy.weight", SrIdentifierKind::Memory),
+        ("Sell", 1, "Shop.Sell", SrIdentifierKind::ProcedureName),
+        ("_sender", 0, "_sender", SrIdentifierKind::ContextResource),
+    ];
+    for (name, occurrence, resolved, kind) in cases {
+        let reference = table.reference_at(offset(name, occurrence)).unwrap();
+        let identifier = table.identifier(reference);
+        assert_eq!(identifier.unresolved, name);
+        assert_eq!(identifier.resolved.as_deref(), Some(resolved));
+        assert_eq!(identifier.kind, kind);
+        assert!(!identifier.is_definition);
+    }
+
+    let weight = table.reference_at(offset("weight", 2)).unwrap();
+    assert_eq!(
+        table.identifier(weight).type_reference.as_deref(),
+        Some("Uint128")
+    );
+}
+
+#[test]
+fn test_unresolved_names() {
+    let source = r#"scilla_version 0
+contract Unresolved ()
+field count : Uint32 = Uint32 0
+
+transition Run ()
+  Later;
+  x = counter;
+  c <- counter;
+  y = Orange
+end
+
+procedure Later ()
+end
+"#;
+    let table = SymbolTable::resolve(&parse_program(source).0.unwrap());
+    let unresolved: Vec<_> = table
+        .unresolved()
+        .map(|r| (r.name.as_str(), r.span.start.line + 1))
+        .collect();
+    assert_eq!(
+        unresolved,
+        vec![("Later", 6), ("counter", 7), ("counter", 8), ("Orange", 9)]
+    );
+}
+
+#[test]
+fn test_contracts_resolve() {
+    for entry in std::fs::read_dir("tests/contracts").unwrap() {
+        let path = entry.unwrap().path();
+        let source = std::fs::read_to_string(&path).unwrap();
+        let table = SymbolTable::resolve(&parse_program(&source).0.unwrap());
+        for reference in &table.references {
+            let span = reference.span;
+            assert_eq!(
+                &source[span.start.position..span.end.position],
+                reference.name,
+                "{}",
+                path.display()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_project_symbols() {
+    let workspace = Workspace::new(vec![
+        PathBuf::from("tests/projects/libs"),
+        PathBuf::from("tests/projects/stdlib"),
+    ]);
+    let project = workspace
+        .load(Path::new("tests/projects/Counting.scilla"))
+        .unwrap();
+    let source = std::fs::read_to_string(&project.contract_path).unwrap();
+    let table = project.symbols();
+
+    let increment = table.symbol_at(source.find("increment").unwrap()).unwrap();
+    assert_eq!(increment.name, "increment");
+    assert_eq!(increment.library.as_deref(), Some("Math"));
+    assert_eq!(definition(increment), (7, 5));
+    assert_eq!(table.unresolved().count(), 0);
+}
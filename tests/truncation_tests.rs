@@ -0,0 +1,28 @@
+use std::{error::Error, panic};
+
+use scilla_parser::{parser::lexer::Lexer, Contract};
+
+/// Parses every contract in `tests/contracts` truncated at every token boundary. Truncated
+/// contracts are mostly invalid, but parsing them must fail with an error instead of panicking.
+#[test]
+#[ignore = "parses every prefix of every contract, run with `cargo test --release -- --ignored`"]
+fn test_truncated_contracts_do_not_panic() -> Result<(), Box<dyn Error>> {
+    let mut panics = vec![];
+    for entry in std::fs::read_dir("tests/contracts")? {
+        let contract_path = entry?.path();
+        let contract = std::fs::read_to_string(&contract_path)?;
+
+        let boundaries = Lexer::new(&contract)
+            .filter_map(Result::ok)
+            .map(|(_, _, end)| end.position);
+        for boundary in boundaries {
+            let truncated = &contract[..boundary];
+            if panic::catch_unwind(|| truncated.parse::<Contract>()).is_err() {
+                panics.push(format!("{}:{}", contract_path.display(), boundary));
+            }
+        }
+    }
+
+    assert!(panics.is_empty(), "Parsing panicked at {:?}", panics);
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+use scilla_parser::{AddressKind, Error, Field, FieldList, Type};
+
+fn boxed(t: Type) -> Box<Type> {
+    Box::new(t)
+}
+
+fn adt(name: &str, args: Vec<Type>) -> Type {
+    Type::Adt {
+        name: name.to_string(),
+        args,
+        constructors: vec![],
+    }
+}
+
+#[test]
+fn test_parse_type() {
+    let cases = vec![
+        ("Uint128", Type::Uint128),
+        ("(BNum)", Type::BNum),
+        ("ByStr33", Type::ByStrX(33)),
+        (
+            "Map ByStr20 (List (Pair ByStr20 Uint128))",
+            Type::Map(
+                boxed(Type::ByStr20),
+                boxed(Type::List(boxed(Type::Pair(
+                    boxed(Type::ByStr20),
+                    boxed(Type::Uint128),
+                )))),
+            ),
+        ),
+        (
+            "Map Uint32 (Map String Bool)",
+            Type::Map(
+                boxed(Type::Uint32),
+                boxed(Type::Map(boxed(Type::String), boxed(Type::Bool))),
+            ),
+        ),
+        ("Option Denom", Type::Option(boxed(adt("Denom", vec![])))),
+        (
+            "MyPair Uint128 (Option Int32)",
+            adt(
+                "MyPair",
+                vec![Type::Uint128, Type::Option(boxed(Type::Int32))],
+            ),
+        ),
+        (
+            "ByStr20 with contract field owner: ByStr20 end",
+            Type::ByStr20With(AddressKind::Contract(FieldList(vec![Field::new(
+                "owner",
+                Type::ByStr20,
+            )]))),
+        ),
+        (
+            "Map (ByStr20 with end) Uint128",
+            Type::Map(
+                boxed(Type::ByStr20With(AddressKind::Any)),
+                boxed(Type::Uint128),
+            ),
+        ),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(source.parse::<Type>().unwrap(), expected, "{source}");
+    }
+}
+
+#[test]
+fn test_parse_address_types() {
+    let contract = |fields: Vec<Field>| Type::ByStr20With(AddressKind::Contract(FieldList(fields)));
+    let cases = vec![
+        ("ByStr20", Type::ByStr20),
+        ("ByStr20 with end", Type::ByStr20With(AddressKind::Any)),
+        ("ByStr20 with library end", Type::ByStr20With(AddressKind::Library)),
+        ("ByStr20 with contract end", contract(vec![])),
+        (
+            "ByStr20 with contract field admin: ByStr20 with end, field token: ByStr20 with contract field balances: Map ByStr20 Uint128 end end",
+            contract(vec![
+                Field::new("admin", Type::ByStr20With(AddressKind::Any)),
+                Field::new(
+                    "token",
+                    contract(vec![Field::new(
+                        "balances",
+                        Type::Map(boxed(Type::ByStr20), boxed(Type::Uint128)),
+                    )]),
+                ),
+            ]),
+        ),
+        (
+            "Map ByStr20 with library end (ByStr20 with contract end)",
+            Type::Map(
+                boxed(Type::ByStr20With(AddressKind::Library)),
+                boxed(contract(vec![])),
+            ),
+        ),
+        (
+            "List (ByStr20 with contract field owner: ByStr20 end)",
+            Type::List(boxed(contract(vec![Field::new("owner", Type::ByStr20)]))),
+        ),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(source.parse::<Type>().unwrap(), expected, "{source}");
+    }
+
+    assert!(matches!(
+        "ByStr20 with _this_address end".parse::<Type>(),
+        Err(Error::AstVisitError(_))
+    ));
+}
+
+#[test]
+fn test_parse_function_types() {
+    let function = |from, to| Type::Function(boxed(from), boxed(to));
+    let var = |name: &str| Type::TypeVar(name.to_string());
+    let cases = vec![
+        ("Uint64 -> Uint128", function(Type::Uint64, Type::Uint128)),
+        (
+            "Map (ByStr20) (Bool) -> ByStr20 -> Bool",
+            function(
+                Type::Map(boxed(Type::ByStr20), boxed(Type::Bool)),
+                function(Type::ByStr20, Type::Bool),
+            ),
+        ),
+        (
+            "(Uint32 -> Bool) -> List (Uint32)",
+            function(
+                function(Type::Uint32, Type::Bool),
+                Type::List(boxed(Type::Uint32)),
+            ),
+        ),
+        (
+            "forall 'A. List ('A) -> Uint32",
+            Type::Forall(
+                "'A".to_string(),
+                boxed(function(Type::List(boxed(var("'A"))), Type::Uint32)),
+            ),
+        ),
+        (
+            "forall 'A. forall 'B. ('A -> 'B) -> Pair ('A) ('B)",
+            Type::Forall(
+                "'A".to_string(),
+                boxed(Type::Forall(
+                    "'B".to_string(),
+                    boxed(function(
+                        function(var("'A"), var("'B")),
+                        Type::Pair(boxed(var("'A")), boxed(var("'B"))),
+                    )),
+                )),
+            ),
+        ),
+    ];
+
+    for (source, expected) in cases {
+        let r#type = source.parse::<Type>().unwrap();
+        assert_eq!(r#type, expected, "{source}");
+        assert_eq!(r#type.to_string(), source);
+    }
+}
+
+#[test]
+fn test_parse_invalid_type() {
+    for source in ["", "Map Uint32", "List Uint32 Uint32)", "Option ("] {
+        assert!(
+            matches!(source.parse::<Type>(), Err(Error::TypeParseError(_))),
+            "{source}"
+        );
+    }
+}
+
+/// Types whose `Display` output can be parsed back. `ByStr20` and `ByStrX(20)` print the same, so
+/// only the former is generated.
+fn printable_type() -> impl Strategy<Value = Type> {
+    let primitive = prop_oneof![
+        Just(Type::Int32),
+        Just(Type::Int64),
+        Just(Type::Int128),
+        Just(Type::Int256),
+        Just(Type::Uint32),
+        Just(Type::Uint64),
+        Just(Type::Uint128),
+        Just(Type::Uint256),
+        Just(Type::String),
+        Just(Type::BNum),
+        Just(Type::ByStr),
+        Just(Type::ByStr20),
+        (1..64usize)
+            .prop_filter("ByStr20 is generated on its own", |n| *n != 20)
+            .prop_map(Type::ByStrX),
+    ];
+    let leaf = prop_oneof![
+        primitive.clone(),
+        Just(Type::Bool),
+        Just(adt("Denom", vec![])),
+        Just(Type::ByStr20With(AddressKind::Any)),
+        Just(Type::ByStr20With(AddressKind::Library)),
+    ];
+    leaf.prop_recursive(4, 32, 3, move |inner| {
+        let field = (prop_oneof![Just("owner"), Just("balances")], inner.clone())
+            .prop_map(|(name, t)| Field::new(name, t));
+        prop_oneof![
+            inner.clone().prop_map(|t| Type::Option(boxed(t))),
+            inner.clone().prop_map(|t| Type::List(boxed(t))),
+            (inner.clone(), inner.clone()).prop_map(|(t1, t2)| Type::Pair(boxed(t1), boxed(t2))),
+            (primitive.clone(), inner.clone()).prop_map(|(k, v)| Type::Map(boxed(k), boxed(v))),
+            prop::collection::vec(field, 0..3)
+                .prop_map(|fields| Type::ByStr20With(AddressKind::Contract(FieldList(fields)))),
+            prop::collection::vec(inner, 1..3).prop_map(|args| adt("MyAdt", args)),
+        ]
+    })
+}
+
+/// Function types over printable types, with type variables.
+fn function_type() -> impl Strategy<Value = Type> {
+    let leaf = prop_oneof![
+        printable_type(),
+        Just(Type::TypeVar("'A".to_string())),
+        Just(Type::TypeVar("'B".to_string())),
+    ];
+    let function = leaf.prop_recursive(3, 16, 2, |inner| {
+        prop_oneof![
+            (inner.clone(), inner.clone())
+                .prop_map(|(from, to)| Type::Function(boxed(from), boxed(to))),
+            inner.prop_map(|t| Type::List(boxed(t))),
+        ]
+    });
+    function.prop_recursive(2, 4, 1, |inner| {
+        prop_oneof![
+            inner
+                .clone()
+                .prop_map(|body| Type::Forall("'A".to_string(), boxed(body))),
+            (inner.clone(), inner).prop_map(|(from, to)| Type::Function(boxed(from), boxed(to))),
+        ]
+    })
+}
+
+#[test]
+fn test_display() {
+    let cases = vec![
+        ("Uint128", "Uint128"),
+        ("(Map ByStr20 (Uint128))", "Map (ByStr20) (Uint128)"),
+        (
+            "Map ByStr20 (Map ByStr20 (List (Pair ByStr20 Uint128)))",
+            "Map (ByStr20) (Map (ByStr20) (List (Pair (ByStr20) (Uint128))))",
+        ),
+        ("Option (Option Bool)", "Option (Option (Bool))"),
+        (
+            "MyPair Uint128 (Option Int32)",
+            "MyPair (Uint128) (Option (Int32))",
+        ),
+        ("ByStr20 with end", "ByStr20 with end"),
+        (
+            "ByStr20 with contract field a: Uint128, field b: Map ByStr20 Bool end",
+            "ByStr20 with contract field a : Uint128, field b : Map (ByStr20) (Bool) end",
+        ),
+        (
+            "List (ByStr20 with library end)",
+            "List (ByStr20 with library end)",
+        ),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(source.parse::<Type>().unwrap().to_string(), expected);
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_display_round_trip(r#type in printable_type()) {
+        prop_assert_eq!(r#type.to_string().parse::<Type>().unwrap(), r#type);
+    }
+
+    #[test]
+    fn test_function_display_round_trip(r#type in function_type()) {
+        prop_assert_eq!(r#type.to_string().parse::<Type>().unwrap(), r#type);
+    }
+}
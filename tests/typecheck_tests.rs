@@ -0,0 +1,379 @@
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+
+use scilla_parser::{
+    parser::{lexer::SourcePosition, parse_program},
+    typecheck::check_program,
+    Type, TypeError, TypeErrorKind, Workspace,
+};
+
+/// Type checks `source`, returning each error with the line and column it starts at, from 1.
+fn check(source: &str) -> Vec<(usize, usize, TypeErrorKind)> {
+    let (program, errors) = parse_program(source);
+    assert!(errors.is_empty(), "{errors:?}");
+    match check_program(&program.unwrap()) {
+        Ok(()) => vec![],
+        Err(errors) => errors
+            .into_iter()
+            .map(|e| (e.start.line + 1, e.start.column + 1, e.kind))
+            .collect(),
+    }
+}
+
+#[test]
+fn test_contracts_typecheck() {
+    for entry in std::fs::read_dir("tests/contracts").unwrap() {
+        let path = entry.unwrap().path();
+        // GetFields initializes a field with parameters the contract does not declare.
+        if path.ends_with("GetFields.scilla") {
+            continue;
+        }
+        let source = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(check(&source), vec![], "{}", path.display());
+    }
+}
+
+#[test]
+fn test_undeclared_contract_parameters() {
+    let source = std::fs::read_to_string("tests/contracts/GetFields.scilla").unwrap();
+    assert_eq!(
+        check(&source),
+        vec![
+            (
+                45,
+                25,
+                TypeErrorKind::UndeclaredVariable("contract_owner".to_string())
+            ),
+            (
+                45,
+                40,
+                TypeErrorKind::UndeclaredVariable("init_supply".to_string())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_fields() {
+    let source = r#"scilla_version 0
+contract Fields ()
+field count : Uint128 = Uint128 0
+field balances : Map ByStr20 Uint128 = Emp ByStr20 Uint128
+
+transition Read (key : Uint32)
+  c <- counter;
+  b <- balances[_sender][_sender];
+  b <- balances[key];
+  x = count
+end
+
+transition Write (amount : Uint32)
+  count := amount;
+  balances[_sender] := amount
+end
+"#;
+    let balances = Type::Map(Box::new(Type::ByStr20), Box::new(Type::Uint128));
+    assert_eq!(
+        check(source),
+        vec![
+            (7, 8, TypeErrorKind::UndeclaredField("counter".to_string())),
+            (
+                8,
+                8,
+                TypeErrorKind::NotAMap {
+                    field: "balances".to_string(),
+                    r#type: balances,
+                    keys: 2,
+                }
+            ),
+            (
+                9,
+                16,
+                TypeErrorKind::Mismatch {
+                    expected: Type::ByStr20,
+                    found: Type::Uint32,
+                }
+            ),
+            (
+                10,
+                7,
+                TypeErrorKind::UndeclaredVariable("count".to_string())
+            ),
+            (
+                14,
+                12,
+                TypeErrorKind::Mismatch {
+                    expected: Type::Uint128,
+                    found: Type::Uint32,
+                }
+            ),
+            (
+                15,
+                24,
+                TypeErrorKind::Mismatch {
+                    expected: Type::Uint128,
+                    found: Type::Uint32,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_builtin_arithmetic() {
+    let source = r#"scilla_version 0
+library Arithmetic
+let one = Uint128 1
+let small = Uint32 1
+let two = builtin add one one
+let bad = builtin add one small
+let smaller = builtin lt small small
+let word = "word"
+let worse = builtin mul word word
+
+contract Arithmetic ()
+"#;
+    assert_eq!(
+        check(source),
+        vec![
+            (
+                6,
+                11,
+                TypeErrorKind::BuiltinArguments {
+                    builtin: "add".to_string(),
+                    arguments: vec![Type::Uint128, Type::Uint32],
+                }
+            ),
+            (
+                9,
+                13,
+                TypeErrorKind::BuiltinArguments {
+                    builtin: "mul".to_string(),
+                    arguments: vec![Type::String, Type::String],
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_stdlib_functions() {
+    let source = r#"scilla_version 0
+import PairUtils IntUtils
+library Stdlib
+let one = Uint128 1
+let word = "word"
+let pair = Pair {Uint128 String} one word
+let first = @fst Uint128 String
+let second = @snd Uint128 String
+let a = first pair
+let b = second pair
+let sum = builtin add a b
+let less = uint128_lt a a
+let worse = uint128_lt b b
+
+contract Stdlib ()
+"#;
+    let mismatch = || TypeErrorKind::Mismatch {
+        expected: Type::Uint128,
+        found: Type::String,
+    };
+    assert_eq!(
+        check(source),
+        vec![
+            (
+                11,
+                11,
+                TypeErrorKind::BuiltinArguments {
+                    builtin: "add".to_string(),
+                    arguments: vec![Type::Uint128, Type::String],
+                }
+            ),
+            (13, 24, mismatch()),
+            (13, 26, mismatch()),
+        ]
+    );
+}
+
+#[test]
+fn test_match_patterns() {
+    let source = r#"scilla_version 0
+library Patterns
+type Denom =
+  | Zil
+  | Token of ByStr20
+
+let is_zil =
+  fun (d : Denom) =>
+    match d with
+    | Zil => True
+    | Token a b => False
+    end
+
+let get =
+  fun (o : Option Uint32) =>
+    match o with
+    | Some x => builtin add x x
+    | Zil => Uint32 0
+    | Nothing => Uint32 0
+    end
+
+let unwrap =
+  fun (a : Uint32) =>
+    match a with
+    | Some v => v
+    | _ => a
+    end
+
+contract Patterns ()
+"#;
+    assert_eq!(
+        check(source),
+        vec![
+            (
+                11,
+                7,
+                TypeErrorKind::Arity {
+                    name: "Token".to_string(),
+                    expected: 1,
+                    found: 2,
+                }
+            ),
+            (
+                18,
+                7,
+                TypeErrorKind::ForeignConstructor {
+                    constructor: "Zil".to_string(),
+                    r#type: Type::Option(Box::new(Type::Uint32)),
+                }
+            ),
+            (
+                19,
+                7,
+                TypeErrorKind::UnknownConstructor("Nothing".to_string())
+            ),
+            (
+                25,
+                7,
+                TypeErrorKind::ForeignConstructor {
+                    constructor: "Some".to_string(),
+                    r#type: Type::Uint32,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_pattern_binders_are_typed() {
+    let source = r#"scilla_version 0
+contract Binders ()
+field owners : Map ByStr20 Uint32 = Emp ByStr20 Uint32
+
+transition Bump ()
+  o <- owners[_sender];
+  match o with
+  | Some count =>
+    one = Uint128 1;
+    next = builtin add count one
+  | None =>
+  end
+end
+"#;
+    assert_eq!(
+        check(source),
+        vec![(
+            10,
+            12,
+            TypeErrorKind::BuiltinArguments {
+                builtin: "add".to_string(),
+                arguments: vec![Type::Uint32, Type::Uint128],
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_procedure_calls() {
+    let source = r#"scilla_version 0
+contract Calls ()
+
+procedure Pay (to : ByStr20, amount : Uint128)
+  accept
+end
+
+transition Run (amount : Uint128, amounts : List Uint128)
+  Pay _sender;
+  Pay _sender _sender;
+  Refund _sender;
+  forall amounts Pay
+end
+"#;
+    assert_eq!(
+        check(source),
+        vec![
+            (
+                9,
+                3,
+                TypeErrorKind::Arity {
+                    name: "Pay".to_string(),
+                    expected: 2,
+                    found: 1,
+                }
+            ),
+            (
+                10,
+                15,
+                TypeErrorKind::Mismatch {
+                    expected: Type::Uint128,
+                    found: Type::ByStr20,
+                }
+            ),
+            (
+                11,
+                3,
+                TypeErrorKind::UndeclaredProcedure("Refund".to_string())
+            ),
+            (
+                12,
+                18,
+                TypeErrorKind::Arity {
+                    name: "Pay".to_string(),
+                    expected: 2,
+                    found: 1,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_error_display() {
+    let error = TypeError {
+        kind: TypeErrorKind::UndeclaredField("counter".to_string()),
+        start: SourcePosition {
+            position: 40,
+            line: 6,
+            column: 7,
+        },
+        end: SourcePosition {
+            position: 47,
+            line: 6,
+            column: 14,
+        },
+    };
+    assert_eq!(error.to_string(), "7:8: undeclared field counter");
+}
+
+#[test]
+fn test_project_typecheck() {
+    let workspace = Workspace::new(vec![
+        PathBuf::from("tests/projects/libs"),
+        PathBuf::from("tests/projects/stdlib"),
+    ]);
+    let project = workspace
+        .load(Path::new("tests/projects/Counting.scilla"))
+        .unwrap();
+    assert_eq!(project.typecheck(), Ok(()));
+}
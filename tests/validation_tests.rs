@@ -0,0 +1,111 @@
+#![cfg(feature = "json")]
+
+use std::path::PathBuf;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use scilla_parser::{Contract, NamedValue, Type, Value, ValueError};
+
+fn fungible_token() -> Contract {
+    Contract::parse(&PathBuf::from("tests/contracts/FungibleToken.scilla")).unwrap()
+}
+
+fn address(byte: &str, length: usize) -> String {
+    format!("0x{}", byte.repeat(length))
+}
+
+#[test]
+fn test_validate_init() {
+    let contract = fungible_token();
+    let init = json!([
+        {"vname": "_scilla_version", "type": "Uint32", "value": "0"},
+        {"vname": "contract_owner", "type": "ByStr20", "value": address("11", 20)},
+        {"vname": "name", "type": "String", "value": "Token"},
+        {"vname": "symbol", "type": "String", "value": "TOK"},
+        {"vname": "decimals", "type": "Uint32", "value": "12"},
+        {"vname": "init_supply", "type": "Uint128", "value": "1000"},
+    ]);
+
+    let values = contract.validate_init(&init).unwrap();
+    assert_eq!(values.len(), 6);
+    assert_eq!(
+        values[5],
+        NamedValue::new("init_supply", Type::Uint128, Value::Uint128(1000))
+    );
+}
+
+#[test]
+fn test_validate_init_reports_every_violation() {
+    let contract = fungible_token();
+    let init = json!([
+        {"vname": "contract_owner", "type": "ByStr20", "value": address("11", 21)},
+        {"vname": "name", "type": "String", "value": "Token"},
+        {"vname": "name", "type": "String", "value": "Token"},
+        {"vname": "decimals", "type": "Uint64", "value": "12"},
+        {"vname": "init_supply", "type": "Uint128", "value": "-1"},
+        {"vname": "owner", "type": "ByStr20", "value": address("11", 20)},
+        {"type": "String", "value": "?"},
+    ]);
+
+    assert_eq!(
+        contract.validate_init(&init),
+        Err(vec![
+            ValueError::new("contract_owner", "expected 20 bytes for ByStr20, got 21"),
+            ValueError::new("name", "duplicate parameter"),
+            ValueError::new("decimals", "expected type Uint32, got Uint64"),
+            ValueError::new("init_supply", "-1 is out of range of Uint128"),
+            ValueError::new("owner", "unexpected parameter"),
+            ValueError::new("[6]", "expected a string vname"),
+            ValueError::new("symbol", "missing parameter"),
+        ])
+    );
+}
+
+#[test]
+fn test_validate_call() {
+    let contract = fungible_token();
+    let call = json!({
+        "_tag": "Transfer",
+        "_amount": "0",
+        "params": [
+            {"vname": "to", "type": "ByStr20", "value": address("22", 20)},
+            {"vname": "amount", "type": "Uint128", "value": "5"},
+        ],
+    });
+    assert_eq!(
+        contract.validate_call(&call),
+        Ok(vec![
+            NamedValue::new("to", Type::ByStr20, Value::ByStr(vec![0x22; 20])),
+            NamedValue::new("amount", Type::Uint128, Value::Uint128(5)),
+        ])
+    );
+
+    let call = json!({
+        "_tag": "Transfer",
+        "params": [
+            {"vname": "to", "type": "ByStr20", "value": address("22", 19)},
+            {"type": "Uint128", "value": "5"},
+        ],
+    });
+    assert_eq!(
+        contract.validate_call(&call),
+        Err(vec![
+            ValueError::new("params.to", "expected 20 bytes for ByStr20, got 19"),
+            ValueError::new("params[1]", "expected a string vname"),
+            ValueError::new("params.amount", "missing parameter"),
+        ])
+    );
+
+    assert_eq!(
+        contract.validate_call(&json!({"_tag": "Mint", "params": []})),
+        Err(vec![ValueError::new("_tag", "unknown transition Mint")])
+    );
+    assert_eq!(
+        contract.validate_call(&json!({"_tag": "Transfer", "params": {}})),
+        Err(vec![ValueError::new(
+            "params",
+            "expected a list of parameters"
+        )])
+    );
+}
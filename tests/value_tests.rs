@@ -0,0 +1,253 @@
+#![cfg(feature = "json")]
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use scilla_parser::{Constructor, NamedValue, Type, Value, ValueError};
+
+fn map(key: Type, value: Type) -> Type {
+    Type::Map(Box::new(key), Box::new(value))
+}
+
+fn option(t: Type) -> Type {
+    Type::Option(Box::new(t))
+}
+
+fn list(t: Type) -> Type {
+    Type::List(Box::new(t))
+}
+
+fn pair(t1: Type, t2: Type) -> Type {
+    Type::Pair(Box::new(t1), Box::new(t2))
+}
+
+fn denom() -> Type {
+    Type::Adt {
+        name: "Denom".to_string(),
+        args: vec![],
+        constructors: vec![
+            Constructor::new("Zil", vec![]),
+            Constructor::new("Token", vec![Type::ByStr20]),
+        ],
+    }
+}
+
+#[test]
+fn test_value_round_trip() {
+    let address = vec![0xab; 20];
+    let cases = vec![
+        (Type::Int32, Value::Int32(-7), json!("-7")),
+        (Type::Uint128, Value::Uint128(100), json!("100")),
+        (
+            Type::Uint256,
+            Value::Uint256("1".repeat(70)),
+            json!("1".repeat(70)),
+        ),
+        (Type::BNum, Value::BNum("42".to_string()), json!("42")),
+        (Type::String, Value::String("hi".to_string()), json!("hi")),
+        (Type::ByStr, Value::ByStr(vec![0x01, 0xff]), json!("0x01ff")),
+        (
+            Type::ByStr20,
+            Value::ByStr(address.clone()),
+            json!(format!("0x{}", "ab".repeat(20))),
+        ),
+        (
+            Type::Bool,
+            Value::Bool(true),
+            json!({"constructor": "True", "argtypes": [], "arguments": []}),
+        ),
+        (
+            option(Type::Uint32),
+            Value::Option(Some(Box::new(Value::Uint32(1)))),
+            json!({"constructor": "Some", "argtypes": ["Uint32"], "arguments": ["1"]}),
+        ),
+        (
+            option(Type::Uint32),
+            Value::Option(None),
+            json!({"constructor": "None", "argtypes": ["Uint32"], "arguments": []}),
+        ),
+        (
+            pair(Type::String, Type::Int64),
+            Value::Pair(
+                Box::new(Value::String("a".to_string())),
+                Box::new(Value::Int64(2)),
+            ),
+            json!({"constructor": "Pair", "argtypes": ["String", "Int64"], "arguments": ["a", "2"]}),
+        ),
+        (
+            list(Type::Uint32),
+            Value::List(vec![Value::Uint32(1), Value::Uint32(2)]),
+            json!(["1", "2"]),
+        ),
+        (
+            map(Type::String, Type::Uint128),
+            Value::Map(vec![(Value::String("a".to_string()), Value::Uint128(1))]),
+            json!([{"key": "a", "val": "1"}]),
+        ),
+        (
+            denom(),
+            Value::Adt {
+                constructor: "Token".to_string(),
+                arguments: vec![Value::ByStr(address.clone())],
+            },
+            json!({
+                "constructor": "Token",
+                "argtypes": [],
+                "arguments": [format!("0x{}", "ab".repeat(20))],
+            }),
+        ),
+    ];
+
+    for (r#type, value, expected) in cases {
+        assert_eq!(value.to_json(&r#type), Ok(expected.clone()), "{type}");
+        assert_eq!(Value::from_json(&r#type, &expected), Ok(value), "{type}");
+    }
+}
+
+#[test]
+fn test_int_range_checks() {
+    let out_of_range = [
+        (Type::Int32, "2147483648"),
+        (Type::Uint32, "-1"),
+        (Type::Uint128, "340282366920938463463374607431768211456"),
+        (
+            Type::Uint256,
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+        ),
+        (
+            Type::Int256,
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819969",
+        ),
+        (Type::BNum, "-1"),
+    ];
+    for (r#type, value) in out_of_range {
+        assert_eq!(
+            Value::from_json(&r#type, &json!(value)),
+            Err(ValueError::new(
+                "",
+                format!("{value} is out of range of {type}")
+            ))
+        );
+    }
+
+    assert!(Value::from_json(
+        &Type::Int256,
+        &json!("-57896044618658097711785492504343953926634992332820282019728792003956564819968")
+    )
+    .is_ok());
+    assert_eq!(
+        Value::from_json(&Type::Uint64, &json!("1e3")),
+        Err(ValueError::new("", "1e3 is not an integer"))
+    );
+    assert_eq!(
+        Value::Uint256("-1".to_string()).check(&Type::Uint256),
+        Err(ValueError::new("", "-1 is out of range of Uint256"))
+    );
+}
+
+#[test]
+fn test_byte_string_length_checks() {
+    let too_long = json!(format!("0x{}", "00".repeat(21)));
+    assert_eq!(
+        Value::from_json(&Type::ByStr20, &too_long),
+        Err(ValueError::new("", "expected 20 bytes for ByStr20, got 21"))
+    );
+    assert_eq!(
+        Value::ByStr(vec![0; 3]).check(&Type::ByStrX(4)),
+        Err(ValueError::new("", "expected 4 bytes for ByStr4, got 3"))
+    );
+    assert_eq!(
+        Value::from_json(&Type::ByStr, &json!("0xabc")),
+        Err(ValueError::new("", "0xabc is not a hex string"))
+    );
+}
+
+#[test]
+fn test_errors_point_into_nested_values() {
+    let r#type = map(Type::ByStr20, list(option(Type::Uint32)));
+    let json = json!([{
+        "key": format!("0x{}", "00".repeat(20)),
+        "val": [{"constructor": "Some", "argtypes": ["Uint32"], "arguments": ["-3"]}],
+    }]);
+    assert_eq!(
+        Value::from_json(&r#type, &json),
+        Err(ValueError::new(
+            "[0].val[0].arguments[0]",
+            "-3 is out of range of Uint32"
+        ))
+    );
+
+    assert_eq!(
+        Value::from_json(
+            &denom(),
+            &json!({"constructor": "Coin", "argtypes": [], "arguments": []})
+        ),
+        Err(ValueError::new("", "Coin is not a constructor of Denom"))
+    );
+    assert_eq!(
+        Value::from_json(
+            &option(Type::Uint32),
+            &json!({"constructor": "None", "argtypes": ["Uint64"], "arguments": []})
+        ),
+        Err(ValueError::new(
+            "",
+            "argtypes of Option (Uint32) do not match"
+        ))
+    );
+    assert_eq!(
+        Value::Bool(true).check(&Type::String),
+        Err(ValueError::new("", "expected a value of type String"))
+    );
+}
+
+#[test]
+fn test_named_value() {
+    let named = NamedValue::new("amount", Type::Uint128, Value::Uint128(5));
+    let json = json!({"vname": "amount", "type": "Uint128", "value": "5"});
+    assert_eq!(named.to_json(), Ok(json.clone()));
+    assert_eq!(NamedValue::from_json(&Type::Uint128, &json), Ok(named));
+
+    assert_eq!(
+        NamedValue::from_json(
+            &Type::Uint128,
+            &json!({"vname": "amount", "type": "Uint128", "value": "-5"})
+        ),
+        Err(ValueError::new("amount", "-5 is out of range of Uint128"))
+    );
+    assert_eq!(
+        NamedValue::from_json(
+            &Type::Uint128,
+            &json!({"vname": "amount", "type": "Int128", "value": "5"})
+        ),
+        Err(ValueError::new(
+            "amount",
+            "expected type Uint128, got Int128"
+        ))
+    );
+
+    // Types are compared by structure, not by their words.
+    let votes = pair(list(Type::Int32), Type::Bool);
+    for (written, matches) in [
+        ("Pair (List Int32) Bool", true),
+        ("Pair (List (Int32)) (Bool)", true),
+        ("Pair List (Int32 Bool)", false),
+        ("Pair (List Int32 Bool)", false),
+    ] {
+        let json = json!({"vname": "votes", "type": written, "value": {
+            "constructor": "Pair",
+            "argtypes": ["List (Int32)", "Bool"],
+            "arguments": [[], {"constructor": "True", "argtypes": [], "arguments": []}]
+        }});
+        assert_eq!(
+            NamedValue::from_json(&votes, &json).is_ok(),
+            matches,
+            "{written}"
+        );
+    }
+
+    // The constructors of an ADT are not written in JSON.
+    let json = json!({"vname": "denom", "type": "Denom", "value": {
+        "constructor": "Zil", "argtypes": [], "arguments": []
+    }});
+    assert!(NamedValue::from_json(&denom(), &json).is_ok());
+}